@@ -1,7 +1,13 @@
+use std::{fmt::Display, time::Duration};
+
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
-use crate::app::Mode;
+use crate::{
+    app::Mode,
+    data::{QueueMove, SeedLimits, Setting, SpeedLimits, TrackerChange},
+    view::{Column, SortKey, StatusFilter},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
 pub enum Action {
@@ -11,8 +17,294 @@ pub enum Action {
     Suspend,
     Resume,
     Quit,
+    /// Close the daemon's session, then quit.
+    CloseSession,
     ClearScreen,
-    Error(String),
+    Refresh,
+    /// Something went wrong, logged and shown in a toast.
+    Error(Failure),
+    /// List the errors of the run.
+    OpenErrors,
+    /// Show a message in a toast for a few seconds.
+    Notify(Level, String),
+    /// Something happened to a torrent between two ticks, listed in the activity panel.
+    Activity(Activity, String),
+    /// Show or hide the activity panel.
+    ToggleActivity,
+    /// The daemon stopped answering, it is checked again after the delay.
+    Disconnected(Duration),
+    /// The daemon answers again.
+    Reconnected,
     Help,
+    /// Show or hide the overlay of internal measurements.
+    ToggleStats,
+    /// Change how often the torrents are refreshed and the screen drawn.
+    OpenRefreshRates,
+    /// Refresh every given milliseconds and draw the given frames per second, for this run and
+    /// the next ones.
+    SetRefreshRates(u64, u32),
+    /// Go back to the rates of the config, forgetting the ones set while running.
+    ResetRefreshRates,
+    /// Search the actions of the current view by name and run one.
+    OpenPalette,
+    /// Pick one of the servers of the config to connect to.
+    OpenServers,
+    /// Connect to the server with the given name, reloading every view.
+    SwitchServer(String),
     Mode(Mode, i64),
+    /// The torrent selected in Home.
+    Highlight(i64),
+    /// Show or hide the properties of the selected torrent beside the list.
+    ToggleSplit,
+    /// Widen the list beside the properties by the given percent of the width, narrow if negative.
+    ResizeSplit(i16),
+    Breadcrumbs(Vec<String>),
+    Menu(String, Vec<(String, Action)>),
+    ClosePopup,
+    Up,
+    Down,
+    Top,
+    Bottom,
+    /// Scroll a page, as many rows as the list shows.
+    PageUp,
+    PageDown,
+    /// Scroll half a page.
+    HalfPageUp,
+    HalfPageDown,
+    /// Open or toggle the selected item.
+    Select,
+    /// Leave the current view.
+    Back,
+    NextTab,
+    PreviousTab,
+    OpenActionMenu,
+    OpenReportsMenu,
+    /// Open the search bar with the current query.
+    Find,
+    /// Select the next item matching the search.
+    NextMatch,
+    /// Select the previous item matching the search.
+    PreviousMatch,
+    NextSort,
+    ReverseSort,
+    /// Sort by the given key, reversing the order if already sorted by it.
+    SortBy(SortKey),
+    /// Break the ties of the sort with the given key, or stop breaking them.
+    ThenBy(Option<SortKey>),
+    /// Pick the sort key and the one breaking its ties.
+    OpenSortMenu,
+    NextFilter,
+    PreviousFilter,
+    Filter(StatusFilter),
+    ToggleZebra,
+    /// Switch between formatted values and the exact numbers behind them.
+    ToggleRaw,
+    CycleDensity,
+    /// Pick the columns of the table and their order.
+    ChooseColumns,
+    /// Open the column picker, starting from the given columns.
+    OpenColumns(Vec<Column>),
+    /// Show the given columns in the table, in order.
+    SetColumns(Vec<Column>),
+    TogglePeriod,
+    /// Save the report shown to a file.
+    Export,
+    /// Switch to the next theme.
+    NextTheme,
+    /// Switch to the theme with the given name.
+    Theme(String),
+    /// Ask before removing the selected torrent, keeping its data.
+    RemoveSelected,
+    /// Ask before removing the selected torrent along with its data.
+    RemoveSelectedWithData,
+    /// Mark the selected torrent, or unmark it, to act on several torrents at once.
+    ToggleMark,
+    /// Show the torrents with the given ids together.
+    OpenSelection(Vec<i64>),
+    /// Ask for the labels of the torrents acted on, e.g. the marked ones.
+    EditLabels,
+    /// Ask for the labels of the torrents with the given ids, starting from the given ones.
+    OpenLabels(Vec<i64>, Vec<String>),
+    /// Replace the labels of the torrents with the given ids.
+    SetLabels(Vec<i64>, Vec<String>),
+    /// Ask for a new location for the selected torrent.
+    MoveSelected,
+    /// Ask for the directory the selected torrent's data is already in.
+    LocateSelected,
+    /// Rename the torrent, or the selected file or directory of the Files tab.
+    Rename,
+    /// Ask for a new name for the path of torrent `id`, its root or a file or directory in it.
+    OpenRename(i64, String),
+    /// Rename the path of torrent `id` to the given name, keeping it in the same directory.
+    RenamePath(i64, String, String),
+    /// Ask for the announce URL of a tracker to add to the torrent.
+    AddTracker,
+    /// Ask for a new announce URL for the selected tracker.
+    EditTracker,
+    /// Ask before removing the selected tracker from the torrent.
+    RemoveTracker,
+    /// Ask for the announce URL of a tracker of the torrent with the given id, starting from the
+    /// given URL. The tracker is added if it has no id yet.
+    OpenTracker(i64, Option<i64>, String),
+    /// Change the trackers of the torrent with the given id.
+    ChangeTracker(i64, TrackerChange),
+    ToggleState,
+    StartAll,
+    /// Start the stopped torrents that still have data to download, leaving the finished ones.
+    ResumeIncomplete,
+    StopAll,
+    /// Stop the active torrents, or start the ones stopped this way before.
+    TogglePauseAll,
+    Verify,
+    Reannounce,
+    WantAllFiles,
+    /// Pick a label to only show the torrents having it.
+    OpenLabelFilter,
+    /// Only show the torrents with the given label, or every torrent.
+    FilterLabel(Option<String>),
+    /// Add the favorite label to the marked torrents or the selected one, remove it if they have it.
+    ToggleFavorite,
+    /// Only show the torrents with the favorite label, or all of them again.
+    FilterFavorite,
+    /// Label the unlabeled torrents matching `label_rules`.
+    ApplyLabelRules,
+    /// Move the selected torrent in the download queue.
+    MoveInQueue(QueueMove),
+    /// Download the selected file or directory, or skip it if all of it is wanted already.
+    ToggleWanted,
+    /// Raise the priority of the selected file or directory, up to High.
+    RaisePriority,
+    /// Lower the priority of the selected file or directory, down to Low.
+    LowerPriority,
+    /// Remove the torrents with the given ids, keeping their data.
+    Remove(Vec<i64>),
+    /// Remove the torrents with the given ids along with their data.
+    RemoveWithData(Vec<i64>),
+    /// Ask for the new location of the torrents with the given ids, suggesting the given path,
+    /// moving their data there or only looking for it there.
+    OpenSetLocation(Vec<i64>, String, bool),
+    /// Move the torrents with the given ids to a directory, even over existing files if forced.
+    SetLocation(Vec<i64>, String, bool),
+    /// Look for the data of the torrents with the given ids in a directory, without moving it.
+    Locate(Vec<i64>, String),
+    /// Ask for confirmation with a message before dispatching the action.
+    Confirm(String, Box<Action>),
+    /// Show lines of text under a title until dismissed.
+    ShowInfo(String, Vec<String>),
+    /// Show the codecs, duration and resolution of the selected file.
+    Inspect,
+    /// Copy the local path of the selected file or directory to the clipboard.
+    CopyPath,
+    /// Open the folder holding the selected file, or the selected directory.
+    RevealPath,
+    OpenAddTorrent,
+    /// Open the add dialog with the magnet link or `.torrent` file in the clipboard.
+    PasteTorrent,
+    /// Open the add dialog with the given magnet link or `.torrent` file filled in.
+    OpenAddTorrentWith(String),
+    /// Apply the alternative speed limits, Transmission's turtle mode, or stop applying them.
+    ToggleAltSpeed,
+    /// Edit the speed limits of the session and of the selected torrent, if any.
+    EditSpeedLimits,
+    /// Open the speed limits of the session, and of the torrents with the given ids if any.
+    OpenSpeedLimits(Vec<i64>),
+    /// Apply limits to the session, and to the torrents with the given ids.
+    SetSpeedLimits(SpeedLimits, Option<(Vec<i64>, SpeedLimits)>),
+    /// Edit when the marked torrents, or the selected one, stop seeding.
+    EditSeedLimits,
+    /// Open the seeding limits of the torrents with the given ids.
+    OpenSeedLimits(Vec<i64>),
+    /// Apply seeding limits to the torrents with the given ids.
+    SetSeedLimits(Vec<i64>, SeedLimits),
+    /// Show and edit the daemon's settings.
+    OpenSessionSettings,
+    /// Show what the daemon counted, all time and since it started.
+    OpenStatistics,
+    /// Ask for the value of a daemon setting, starting from the given one.
+    OpenSessionSetting(Setting, String),
+    /// Set a daemon setting to the value typed.
+    SetSessionSetting(Setting, String),
+    /// Add a `.torrent` file or magnet link, to a download directory, paused or not.
+    AddTorrent(String, String, bool),
+    /// Open the search bar with the given query.
+    OpenSearch(String),
+    /// Only list the torrents whose name contains the query, all of them if empty.
+    Search(String),
+    /// The active status filter and the number of torrents matching each filter.
+    StatusCounts(StatusFilter, Vec<(StatusFilter, usize)>),
+}
+
+/// How noteworthy a notification is, which sets its color and how long it stays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize, Deserialize)]
+pub enum Level {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// What happened to a torrent, as seen by comparing it between ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize, Deserialize)]
+pub enum Activity {
+    Added,
+    Completed,
+    /// An unfinished torrent stopped receiving data.
+    Stalled,
+    /// A tracker warned or failed, the torrent may still get peers from the others.
+    TrackerError,
+    /// The torrent can't go on, e.g. its data went missing.
+    Error,
+    Removed,
+}
+
+/// What went wrong, in which component and for which torrent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Failure {
+    pub level: Level,
+    /// The component that ran into it, e.g. `Home`.
+    pub source: String,
+    /// The torrent it's about, if any.
+    pub torrent: Option<i64>,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(source: &str, message: impl Display) -> Self {
+        Self {
+            level: Level::Error,
+            source: source.to_string(),
+            torrent: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// How serious it is, e.g. a warning for what the next tick may fix.
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn torrent(mut self, id: i64) -> Self {
+        self.torrent = Some(id);
+        self
+    }
+}
+
+impl Action {
+    /// An error of `source`, see [`Failure`] to add context.
+    pub fn error(source: &str, message: impl Display) -> Self {
+        Self::Error(Failure::new(source, message))
+    }
+
+    /// A notification of how a call went, `success` if it worked, or an error of `source`.
+    pub fn outcome<E: Display>(
+        source: &str,
+        result: Result<(), E>,
+        success: impl Into<String>,
+    ) -> Self {
+        match result {
+            Ok(()) => Self::Notify(Level::Success, success.into()),
+            Err(err) => Self::error(source, err),
+        }
+    }
 }
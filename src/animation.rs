@@ -0,0 +1,100 @@
+use std::time::{Duration, Instant};
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+/// Braille spinner whose frame depends on elapsed time rather than on how
+/// often it is drawn.
+#[derive(Debug, Clone, Copy)]
+pub struct Spinner {
+    start: Instant,
+}
+
+impl Spinner {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+
+    pub fn frame(&self, now: Instant) -> &'static str {
+        let elapsed = now.saturating_duration_since(self.start);
+        let index = elapsed.as_millis() / SPINNER_INTERVAL.as_millis();
+        #[allow(clippy::cast_possible_truncation)]
+        SPINNER_FRAMES[index as usize % SPINNER_FRAMES.len()]
+    }
+}
+
+/// Animates a value towards a target over a fixed duration.
+#[derive(Debug, Clone, Copy)]
+pub struct Tween {
+    from: f64,
+    to: f64,
+    start: Instant,
+    duration: Duration,
+}
+
+impl Tween {
+    pub fn new(value: f64, duration: Duration) -> Self {
+        Self {
+            from: value,
+            to: value,
+            start: Instant::now(),
+            duration,
+        }
+    }
+
+    /// Start moving towards `to` from wherever the animation currently is.
+    pub fn retarget(&mut self, to: f64, now: Instant) {
+        self.from = self.value(now);
+        self.to = to;
+        self.start = now;
+    }
+
+    pub fn value(&self, now: Instant) -> f64 {
+        if self.duration.is_zero() {
+            return self.to;
+        }
+        let t =
+            now.saturating_duration_since(self.start).as_secs_f64() / self.duration.as_secs_f64();
+        self.from + (self.to - self.from) * ease_out_cubic(t.clamp(0.0, 1.0))
+    }
+}
+
+pub fn ease_out_cubic(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ease_out_cubic() {
+        assert_eq!(ease_out_cubic(0.0), 0.0);
+        assert_eq!(ease_out_cubic(1.0), 1.0);
+        assert!(ease_out_cubic(0.5) > 0.5);
+    }
+
+    #[test]
+    fn test_spinner_frame_depends_on_time() {
+        let spinner = Spinner::new();
+        let start = spinner.start;
+        assert_eq!(spinner.frame(start), SPINNER_FRAMES[0]);
+        assert_eq!(spinner.frame(start + SPINNER_INTERVAL), SPINNER_FRAMES[1]);
+        assert_eq!(
+            spinner.frame(start + SPINNER_INTERVAL * SPINNER_FRAMES.len() as u32),
+            SPINNER_FRAMES[0]
+        );
+    }
+
+    #[test]
+    fn test_tween() {
+        let mut tween = Tween::new(0.0, Duration::from_millis(100));
+        let start = Instant::now();
+        tween.retarget(10.0, start);
+        assert_eq!(tween.value(start), 0.0);
+        assert_eq!(tween.value(start + Duration::from_millis(100)), 10.0);
+        assert_eq!(tween.value(start + Duration::from_secs(1)), 10.0);
+    }
+}
@@ -1,18 +1,63 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
-use ratatui::prelude::Rect;
+use futures::executor::block_on;
+use ratatui::layout::{Constraint, Layout, Rect};
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
 use tokio::sync::mpsc;
-use tracing::{debug, info};
-use transmission_rpc::TransClient;
+use tracing::{debug, error, info, warn};
 
+#[cfg(feature = "clipboard")]
+use crate::clipboard;
 use crate::{
-    action::Action,
-    components::{home::Home, properties::Properties, session_stats::SessionStat, Component},
-    config::Config,
+    action::{Action, Failure, Level},
+    colors,
+    components::{
+        activity::ActivityPanel,
+        add_torrent::AddTorrent,
+        columns::ColumnPicker,
+        confirm::Confirm,
+        duplicates::Duplicates,
+        error_bar::ErrorBar,
+        footer_layout,
+        history::HistoryChart,
+        home::{close_session, Home},
+        info::Info,
+        menu::Menu,
+        mini::Mini,
+        orphans::Orphans,
+        palette::{commands, Palette},
+        prompt::Prompt,
+        properties::Properties,
+        rates::RatesDialog,
+        search::SearchBar,
+        seed_limits::SeedLimitsDialog,
+        selection::Selection,
+        session::Session,
+        session_stats::SessionStat,
+        speed_limits::SpeedLimitsDialog,
+        statistics::Statistics,
+        stats::Stats,
+        status_bar::StatusBar,
+        toast::Toast,
+        trackers::Trackers,
+        watch_dir::WatchDir,
+        watcher::Watcher,
+        Component,
+    },
+    config::{reset_rates, save_rates, Config},
+    connection::Connection,
+    credentials,
+    data::{
+        fetch_session_seed_limits, fetch_session_speed_limits, fetch_torrent_seed_limits,
+        fetch_torrent_speed_limits, ping, set_seed_limits, set_speed_limits, TrackerChange,
+    },
+    history,
+    rpc::Client,
     tui::{Event, Tui},
+    utils::redact_urls,
 };
 
 #[derive(Clone, Debug)]
@@ -20,6 +65,10 @@ pub enum Error {
     OutOfBound,
     NoRowSelected,
     WithMessage(String),
+    /// The call never reached the daemon, it may succeed if tried again.
+    Unreachable(String),
+    /// The daemon doesn't know the method, being older than it.
+    Unsupported(String),
 }
 
 impl std::fmt::Display for Error {
@@ -28,6 +77,8 @@ impl std::fmt::Display for Error {
             Error::OutOfBound => write!(f, "Index out of bound"),
             Error::NoRowSelected => write!(f, "No row selected!"),
             Error::WithMessage(msg) => write!(f, "Message: {msg}"),
+            Error::Unreachable(msg) => write!(f, "Unreachable: {msg}"),
+            Error::Unsupported(method) => write!(f, "The daemon doesn't support {method}"),
         }
     }
 }
@@ -36,40 +87,94 @@ impl std::error::Error for Error {}
 
 pub struct App {
     config: Config,
-    client: Rc<RefCell<TransClient>>,
+    client: Rc<RefCell<Client>>,
     tick_rate: f64,
     frame_rate: f64,
     components: Vec<Box<dyn Component>>,
+    /// Index in `components` of the component rendering the current mode.
+    view: usize,
+    /// Modal component that receives all input while open.
+    popup: Option<Box<dyn Component>>,
+    /// Properties of the torrent selected in Home, shown beside the list while `split`.
+    details: Option<Properties>,
+    split: bool,
+    /// Share of the width taken by the list while split.
+    list_percent: u16,
+    /// The torrent selected in Home.
+    highlighted: Option<i64>,
     should_quit: bool,
     should_suspend: bool,
     mode: Mode,
+    /// Ids of the torrents shown in [`Mode::Selection`].
+    selection: Vec<i64>,
+    /// Only show the footer and the busiest torrents.
+    mini: bool,
+    /// Render inline instead of on the alternate screen.
+    inline: bool,
+    /// Ticks are held back while the daemon doesn't answer, sparing every view the errors.
+    connection: Connection,
+    /// The stats overlay, while shown.
+    stats: Option<Stats>,
     last_tick_key_events: Vec<KeyEvent>,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
 }
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+    Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize,
+)]
+#[strum(ascii_case_insensitive)]
 pub enum Mode {
     #[default]
     Home,
     Properties,
+    Duplicates,
+    History,
+    /// Several torrents marked in Home.
+    Selection,
+    Trackers,
+    /// Data in the download directories that no torrent refers to.
+    Orphans,
+    /// The daemon's settings.
+    Session,
+    /// What the daemon counted, all time and since it started.
+    Statistics,
 }
 
+/// Lines used by `--mini` when rendering inline.
+const MINI_HEIGHT: u16 = 6;
+
 impl App {
-    pub fn new(tick_rate: f64, frame_rate: f64, client: &Rc<RefCell<TransClient>>) -> Result<Self> {
+    pub fn new(
+        tick_rate: f64,
+        frame_rate: f64,
+        client: &Rc<RefCell<Client>>,
+        config: Config,
+        mini: bool,
+        inline: bool,
+    ) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
+        let (components, view) = build_components(client, mini)?;
         Ok(Self {
             client: client.clone(),
             tick_rate,
             frame_rate,
-            components: vec![
-                Box::new(SessionStat::new(client.clone())?),
-                Box::new(Home::new(client.clone(), None)?),
-            ],
+            components,
+            view,
+            popup: None,
+            details: None,
+            split: config.split.enabled,
+            list_percent: config.split.list_percent,
+            highlighted: None,
             should_quit: false,
             should_suspend: false,
-            config: Config::new()?,
+            config,
             mode: Mode::Home,
+            selection: Vec::new(),
+            mini,
+            inline,
+            connection: Connection::default(),
+            stats: None,
             last_tick_key_events: Vec::new(),
             action_tx,
             action_rx,
@@ -78,22 +183,31 @@ impl App {
 
     pub async fn run(&mut self) -> Result<()> {
         let mut tui = Tui::new()?
-            // .mouse(true)
+            .mouse(!self.mini)
+            .paste(!self.mini)
             .tick_rate(self.tick_rate)
             .frame_rate(self.frame_rate);
-        tui.enter()?;
-
-        for component in self.components.iter_mut() {
-            component.register_action_handler(self.action_tx.clone())?;
-        }
-        for component in self.components.iter_mut() {
-            component.register_config_handler(self.config.clone())?;
-        }
-        for component in self.components.iter_mut() {
-            component.init(tui.size()?)?;
+        if self.inline {
+            let height = match self.mini {
+                true => MINI_HEIGHT,
+                false => crossterm::terminal::size()?.1,
+            };
+            tui = tui.inline(height)?;
         }
+        tui.enter()?;
+        self.init_components(&mut tui)?;
 
         let action_tx = self.action_tx.clone();
+        // Properties and Selection need torrents, they can't be opened on startup.
+        let startup_mode = self.config.startup.mode;
+        if !self.mini
+            && !matches!(
+                startup_mode,
+                Mode::Home | Mode::Properties | Mode::Selection
+            )
+        {
+            action_tx.send(Action::Mode(startup_mode, 0))?;
+        }
         loop {
             self.handle_events(&mut tui).await?;
             self.handle_actions(&mut tui)?;
@@ -101,7 +215,6 @@ impl App {
                 tui.suspend()?;
                 action_tx.send(Action::Resume)?;
                 action_tx.send(Action::ClearScreen)?;
-                // tui.mouse(true);
                 tui.enter()?;
             } else if self.should_quit {
                 tui.stop()?;
@@ -122,9 +235,15 @@ impl App {
             Event::Tick => action_tx.send(Action::Tick)?,
             Event::Render => action_tx.send(Action::Render)?,
             Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
-            Event::Key(key) => self.handle_key_event(key)?,
+            Event::Key(key) if self.popup.is_none() => self.handle_key_event(key)?,
             _ => {}
         }
+        if let Some(popup) = self.popup.as_mut() {
+            if let Some(action) = popup.handle_events(Some(event))? {
+                action_tx.send(action)?;
+            }
+            return Ok(());
+        }
         for component in self.components.iter_mut() {
             if let Some(action) = component.handle_events(Some(event.clone()))? {
                 action_tx.send(action)?;
@@ -159,63 +278,659 @@ impl App {
     }
 
     fn handle_actions(&mut self, tui: &mut Tui) -> Result<()> {
+        if let Some(stats) = self.stats.as_mut() {
+            stats.queued(self.action_rx.len());
+        }
         while let Ok(action) = self.action_rx.try_recv() {
             if action != Action::Tick && action != Action::Render {
-                debug!("{action:?}");
+                debug!("{}", redact_urls(&format!("{action:?}")));
             }
             match action {
                 Action::Tick => {
                     self.last_tick_key_events.drain(..);
+                    if let Some(stats) = self.stats.as_mut() {
+                        stats.tick(Instant::now());
+                    }
+                    if !self.check_connection()? {
+                        continue;
+                    }
                 }
                 Action::Quit => self.should_quit = true,
+                Action::CloseSession => match block_on(close_session(&self.client)) {
+                    Ok(true) => self.should_quit = true,
+                    Ok(false) => {}
+                    Err(err) => self.action_tx.send(Action::error("App", err))?,
+                },
+                Action::Error(ref failure) => log_failure(failure),
+                // Views opened later start the way the current one shows values.
+                Action::ToggleRaw => self.config.table.raw = !self.config.table.raw,
                 Action::Suspend => self.should_suspend = true,
                 Action::Resume => self.should_suspend = false,
                 Action::ClearScreen => tui.terminal.clear()?,
                 Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
-                Action::Render => self.render(tui)?,
-                Action::Mode(mode, id) => self.handle_modes(mode, id)?,
+                Action::Render => {
+                    if let Some(stats) = self.stats.as_mut() {
+                        stats.frame(Instant::now());
+                    }
+                    self.render(tui)?;
+                }
+                Action::ToggleStats => {
+                    self.stats = match self.stats {
+                        Some(_) => None,
+                        None => Some(Stats::new(self.tick_rate, self.frame_rate)),
+                    };
+                }
+                Action::OpenRefreshRates => {
+                    self.open_popup(tui, RatesDialog::new(self.tick_rate, self.frame_rate))?;
+                }
+                Action::SetRefreshRates(interval, frames) => {
+                    #[allow(clippy::cast_precision_loss)]
+                    let tick_rate = 1000.0 / interval as f64;
+                    self.set_rates(tui, tick_rate, f64::from(frames));
+                    let message = match save_rates(tick_rate, self.frame_rate) {
+                        Ok(()) => Action::Notify(
+                            Level::Success,
+                            format!("Refreshing every {interval} ms at {frames} fps"),
+                        ),
+                        Err(err) => {
+                            Action::error("App", format!("Failed to save the rates: {err}"))
+                        }
+                    };
+                    self.action_tx.send(message)?;
+                }
+                Action::ResetRefreshRates => match reset_rates() {
+                    Ok((tick_rate, frame_rate)) => {
+                        self.set_rates(tui, tick_rate, frame_rate);
+                        self.action_tx.send(Action::Notify(
+                            Level::Success,
+                            "Back to the rates of the config".to_string(),
+                        ))?;
+                    }
+                    Err(err) => self.action_tx.send(Action::error(
+                        "App",
+                        format!("Failed to reset the rates: {err}"),
+                    ))?,
+                },
+                Action::Mode(mode, id) => self.handle_modes(tui, mode, id)?,
+                Action::Highlight(id) => {
+                    self.highlighted = Some(id);
+                    if self.split && self.mode == Mode::Home {
+                        self.show_details(id)?;
+                    }
+                }
+                Action::ToggleSplit => {
+                    self.split = !self.split;
+                    match (self.split, self.highlighted) {
+                        (true, Some(id)) => self.show_details(id)?,
+                        _ => self.details = None,
+                    }
+                }
+                Action::ResizeSplit(step) => {
+                    let percent = i16::try_from(self.list_percent).unwrap_or_default() + step;
+                    self.list_percent = u16::try_from(percent.clamp(25, 75)).unwrap_or_default();
+                }
+                Action::OpenSelection(ref ids) => {
+                    self.selection.clone_from(ids);
+                    self.handle_modes(tui, Mode::Selection, 0)?;
+                }
+                Action::Menu(ref title, ref items) => {
+                    self.open_popup(tui, Menu::new(title.clone(), items.clone()))?;
+                }
+                Action::Confirm(ref message, ref action) => {
+                    self.open_popup(tui, Confirm::new(message.clone(), *action.clone()))?;
+                }
+                Action::ShowInfo(ref title, ref lines) => {
+                    self.open_popup(tui, Info::new(title.clone(), lines.clone()))?;
+                }
+                Action::OpenSetLocation(ref ids, ref location, move_data) => {
+                    let ids = ids.clone();
+                    let prompt = match move_data {
+                        true => Prompt::new("Move data to", location.clone(), move |path| {
+                            Action::SetLocation(ids.clone(), path, false)
+                        }),
+                        false => Prompt::new("Find data in", location.clone(), move |path| {
+                            Action::Locate(ids.clone(), path)
+                        }),
+                    };
+                    self.open_popup(tui, prompt)?;
+                }
+                Action::OpenLabels(ref ids, ref labels) => {
+                    let ids = ids.clone();
+                    let prompt =
+                        Prompt::new("Labels, comma separated", labels.join(", "), move |value| {
+                            let labels = value
+                                .split(',')
+                                .map(str::trim)
+                                .filter(|label| !label.is_empty())
+                                .map(str::to_string)
+                                .collect();
+                            Action::SetLabels(ids.clone(), labels)
+                        });
+                    self.open_popup(tui, prompt)?;
+                }
+                Action::OpenRename(id, ref path) => {
+                    let path = path.clone();
+                    let name = path.rsplit('/').next().unwrap_or_default().to_string();
+                    let prompt = Prompt::new("Rename to", name, move |name| {
+                        Action::RenamePath(id, path.clone(), name)
+                    });
+                    self.open_popup(tui, prompt)?;
+                }
+                Action::OpenTracker(id, tracker, ref url) => {
+                    let prompt = Prompt::new("Tracker URL", url.clone(), move |url| {
+                        let change = match tracker {
+                            Some(tracker) => TrackerChange::Replace(tracker, url),
+                            None => TrackerChange::Add(url),
+                        };
+                        Action::ChangeTracker(id, change)
+                    });
+                    self.open_popup(tui, prompt)?;
+                }
+                Action::OpenSessionSetting(setting, ref value) => {
+                    let prompt = Prompt::new(setting.title(), value.clone(), move |value| {
+                        Action::SetSessionSetting(setting, value)
+                    });
+                    self.open_popup(tui, prompt)?;
+                }
+                Action::OpenColumns(ref columns) => {
+                    self.open_popup(tui, ColumnPicker::new(columns))?;
+                }
+                Action::OpenAddTorrent => {
+                    self.open_popup(tui, AddTorrent::new(self.download_dir()))?;
+                }
+                Action::PasteTorrent => self.paste_torrent(tui)?,
+                Action::OpenAddTorrentWith(ref source) => {
+                    self.open_add_torrent_with(tui, source.clone())?;
+                }
+                Action::OpenSpeedLimits(ref ids) => {
+                    let limits =
+                        block_on(fetch_session_speed_limits(&self.client)).and_then(|session| {
+                            // Several torrents are shown with the limits of the first one.
+                            let torrent = match ids.first() {
+                                Some(&id) => {
+                                    let limits =
+                                        block_on(fetch_torrent_speed_limits(&self.client, id))?;
+                                    Some((ids.clone(), limits))
+                                }
+                                None => None,
+                            };
+                            Ok((session, torrent))
+                        });
+                    match limits {
+                        Ok((session, torrent)) => {
+                            self.open_popup(tui, SpeedLimitsDialog::new(session, torrent))?;
+                        }
+                        Err(err) => self.action_tx.send(Action::error("App", err))?,
+                    }
+                }
+                Action::SetSpeedLimits(session, ref torrents) => {
+                    let res =
+                        block_on(set_speed_limits(&self.client, None, session)).and_then(|()| {
+                            match torrents {
+                                Some((ids, limits)) => block_on(set_speed_limits(
+                                    &self.client,
+                                    Some(ids.clone()),
+                                    *limits,
+                                )),
+                                None => Ok(()),
+                            }
+                        });
+                    self.action_tx
+                        .send(Action::outcome("App", res, "Speed limits saved"))?;
+                }
+                Action::OpenSeedLimits(ref ids) => {
+                    // Several torrents are shown with the limits of the first one.
+                    let limits =
+                        block_on(fetch_session_seed_limits(&self.client)).and_then(|session| {
+                            let id = ids.first().copied().unwrap_or_default();
+                            let limits = block_on(fetch_torrent_seed_limits(&self.client, id))?;
+                            Ok((session, limits))
+                        });
+                    match limits {
+                        Ok((session, limits)) => {
+                            let dialog = SeedLimitsDialog::new(ids.clone(), limits, &session);
+                            self.open_popup(tui, dialog)?;
+                        }
+                        Err(err) => self.action_tx.send(Action::error("App", err))?,
+                    }
+                }
+                Action::SetSeedLimits(ref ids, limits) => {
+                    let res = block_on(set_seed_limits(&self.client, ids.clone(), limits));
+                    self.action_tx
+                        .send(Action::outcome("App", res, "Seeding limits saved"))?;
+                }
+                Action::OpenPalette => {
+                    let keymap = self.config.keybindings.get(&self.mode);
+                    let commands = keymap.map(commands).unwrap_or_default();
+                    self.open_popup(tui, Palette::new(commands))?;
+                }
+                Action::OpenServers => {
+                    let current = self.config.profile.as_deref();
+                    let items = self
+                        .config
+                        .servers
+                        .keys()
+                        .map(|name| {
+                            let marker = if Some(name.as_str()) == current {
+                                "●"
+                            } else {
+                                " "
+                            };
+                            (
+                                format!("{marker} {name}"),
+                                Action::SwitchServer(name.clone()),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    match items.is_empty() {
+                        true => self.action_tx.send(Action::Notify(
+                            Level::Warning,
+                            "No servers in the config".to_string(),
+                        ))?,
+                        false => self.open_popup(tui, Menu::new("Servers".to_string(), items))?,
+                    }
+                }
+                Action::SwitchServer(ref name) => {
+                    // The views are rebuilt, they don't see the action.
+                    self.switch_server(tui, name)?;
+                    continue;
+                }
+                Action::OpenSearch(ref query) => {
+                    self.open_popup(tui, SearchBar::new(query.clone()))?;
+                }
+                Action::ClosePopup => self.popup = None,
+                Action::NextTheme => {
+                    let names = self.config.theme_names();
+                    let current = names.iter().position(|n| n == self.config.theme_name());
+                    let next = current.map_or(0, |i| (i + 1) % names.len());
+                    self.action_tx.send(Action::Theme(names[next].clone()))?;
+                }
+                Action::Theme(ref name) => match self.config.theme_colors(name) {
+                    Ok(colors) => {
+                        colors::set_theme(colors);
+                        self.config.theme = Some(name.clone());
+                        self.action_tx
+                            .send(Action::Notify(Level::Info, format!("Theme: {name}")))?;
+                    }
+                    Err(err) => self.action_tx.send(Action::error("App", err))?,
+                },
                 _ => {}
             }
+            if let Some(popup) = self.popup.as_mut() {
+                if let Some(action) = popup.update(action.clone())? {
+                    self.action_tx.send(action)?;
+                };
+            }
             for component in self.components.iter_mut() {
                 if let Some(action) = component.update(action.clone())? {
                     self.action_tx.send(action)?;
                 };
             }
+            self.update_details(action)?;
+        }
+        Ok(())
+    }
+
+    /// Show the properties of torrent `id` beside the list.
+    fn show_details(&mut self, id: i64) -> Result<()> {
+        let result = match self.details.as_mut() {
+            Some(details) if details.id() == id => return Ok(()),
+            Some(details) => details.follow(id),
+            None => Properties::new(self.client.clone(), id).and_then(|mut details| {
+                // Without the action handler, it leaves the status bar to the list.
+                details.register_config_handler(self.config.clone())?;
+                self.details = Some(details);
+                Ok(None)
+            }),
+        };
+        match result {
+            // Removed since the list was drawn, the list selects another one soon.
+            Ok(_) if self.details.as_ref().is_some_and(Properties::is_gone) => {
+                self.details = None;
+                Ok(())
+            }
+            Ok(action) => action.map_or(Ok(()), |action| Ok(self.action_tx.send(action)?)),
+            Err(err) => {
+                self.details = None;
+                Ok(self.action_tx.send(Action::error(
+                    "App",
+                    format!("Can't show torrent {id}: {err}"),
+                ))?)
+            }
+        }
+    }
+
+    /// Pass the properties beside the list what concerns them, the keys move the list.
+    fn update_details(&mut self, action: Action) -> Result<()> {
+        if let Some(action) = update_details(&mut self.details, action)? {
+            self.action_tx.send(action)?;
+        }
+        Ok(())
+    }
+
+    /// The areas of the list and of the properties beside it, if split.
+    fn split_areas(&self, area: Rect) -> Option<(Rect, Rect)> {
+        if self.details.is_none()
+            || !self.split
+            || self.mode != Mode::Home
+            || area.width < self.config.split.min_width
+        {
+            return None;
+        }
+        let [list, details] = Layout::horizontal([
+            Constraint::Percentage(self.list_percent),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        // The properties draw their tabs below the status bar and stop above the footer.
+        let [details, _] = footer_layout(details);
+        Some((list, details))
+    }
+
+    /// Directory suggested when adding torrents, the server's if configured, else the daemon's.
+    fn download_dir(&self) -> String {
+        match self.config.server().and_then(|s| s.download_dir.clone()) {
+            Some(dir) => dir,
+            None => block_on(self.client.borrow_mut().session_get())
+                .map(|res| res.arguments.download_dir)
+                .unwrap_or_default(),
+        }
+    }
+
+    #[cfg(feature = "clipboard")]
+    fn paste_torrent(&mut self, tui: &mut Tui) -> Result<()> {
+        let source = match clipboard::read() {
+            Ok(text) => crate::components::add_torrent::torrent_source(&text),
+            Err(err) => {
+                self.action_tx.send(Action::Notify(Level::Warning, err))?;
+                return Ok(());
+            }
+        };
+        match source {
+            Some(source) => self.open_add_torrent_with(tui, source)?,
+            None => self.action_tx.send(Action::Notify(
+                Level::Warning,
+                "No magnet link or .torrent file in the clipboard".to_string(),
+            ))?,
         }
         Ok(())
     }
 
+    fn open_add_torrent_with(&mut self, tui: &mut Tui, source: String) -> Result<()> {
+        let dialog = AddTorrent::new(self.download_dir()).with_source(source);
+        self.open_popup(tui, dialog)
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    fn paste_torrent(&mut self, _tui: &mut Tui) -> Result<()> {
+        self.action_tx.send(Action::Notify(
+            Level::Warning,
+            "sparrow was built without the clipboard feature".to_string(),
+        ))?;
+        Ok(())
+    }
+
+    fn init_components(&mut self, tui: &mut Tui) -> Result<()> {
+        for component in self.components.iter_mut() {
+            component.register_action_handler(self.action_tx.clone())?;
+        }
+        for component in self.components.iter_mut() {
+            component.register_config_handler(self.config.clone())?;
+        }
+        for component in self.components.iter_mut() {
+            component.init(tui.size()?)?;
+        }
+        Ok(())
+    }
+
+    /// Connect to the server `name` of the config and start over in Home, staying on the
+    /// current server if the new one doesn't answer.
+    fn switch_server(&mut self, tui: &mut Tui, name: &str) -> Result<()> {
+        let Some(mut server) = self.config.servers.get(name).cloned() else {
+            self.action_tx
+                .send(Action::error("App", format!("No server named `{name}`")))?;
+            return Ok(());
+        };
+        if let Err(err) = credentials::fill(&mut server, false) {
+            self.action_tx.send(Action::error("App", err))?;
+            return Ok(());
+        }
+        let client = match self.client.borrow().reconnect(&server) {
+            Ok(client) => Rc::new(RefCell::new(client)),
+            Err(err) => {
+                self.action_tx.send(Action::error("App", err))?;
+                return Ok(());
+            }
+        };
+        if let Err(err) = block_on(ping(&client)) {
+            self.action_tx.send(Action::error(
+                "App",
+                format!("{name} doesn't answer: {err}"),
+            ))?;
+            return Ok(());
+        }
+        // The views share the client, they talk to the new server from now on.
+        self.client.swap(&client);
+        info!("Switched to server {name}");
+        self.config.profile = Some(name.to_string());
+        self.connection = Connection::default();
+        self.mode = Mode::Home;
+        self.popup = None;
+        // Their torrent ids are the previous daemon's.
+        self.details = None;
+        self.highlighted = None;
+        self.selection.clear();
+        history::reset_run();
+        (self.components, self.view) = build_components(&self.client, self.mini)?;
+        self.init_components(tui)?;
+        self.action_tx.send(Action::Notify(
+            Level::Success,
+            format!("Connected to {name}"),
+        ))?;
+        Ok(())
+    }
+
+    /// Check that the daemon answers, if due, returning whether it does.
+    fn check_connection(&mut self) -> Result<bool> {
+        let now = Instant::now();
+        if !self.connection.is_due(now) {
+            return Ok(false);
+        }
+        match block_on(ping(&self.client)) {
+            Ok(()) => {
+                if self.connection.succeeded() {
+                    info!("Reconnected");
+                    self.action_tx.send(Action::Reconnected)?;
+                    self.action_tx.send(Action::Refresh)?;
+                }
+                Ok(true)
+            }
+            Err(err) => {
+                let delay = self.connection.failed(now);
+                warn!("Disconnected, retrying in {delay:?}: {err}");
+                self.action_tx.send(Action::Disconnected(delay))?;
+                Ok(false)
+            }
+        }
+    }
+
     fn handle_resize(&mut self, tui: &mut Tui, w: u16, h: u16) -> Result<()> {
         tui.resize(Rect::new(0, 0, w, h))?;
         self.render(tui)?;
         Ok(())
     }
 
-    fn handle_modes(&mut self, mode: Mode, id: i64) -> Result<()> {
-        self.components.pop();
-        match mode {
-            Mode::Home => {
-                self.components
-                    .push(Box::new(Home::new(self.client.clone(), Some(id))?));
-            }
-            Mode::Properties => {
-                self.components
-                    .push(Box::new(Properties::new(self.client.clone(), id)?));
-            }
+    /// Tick and draw at new rates, restarting the event loop.
+    fn set_rates(&mut self, tui: &mut Tui, tick_rate: f64, frame_rate: f64) {
+        self.tick_rate = tick_rate;
+        self.frame_rate = frame_rate;
+        tui.tick_rate = tick_rate;
+        tui.frame_rate = frame_rate;
+        tui.start();
+        if let Some(stats) = self.stats.as_mut() {
+            stats.set_rates(tick_rate, frame_rate);
         }
+    }
+
+    fn open_popup(&mut self, tui: &mut Tui, popup: impl Component + 'static) -> Result<()> {
+        let mut popup: Box<dyn Component> = Box::new(popup);
+        popup.register_action_handler(self.action_tx.clone())?;
+        popup.register_config_handler(self.config.clone())?;
+        popup.init(tui.size()?)?;
+        self.popup = Some(popup);
+        Ok(())
+    }
+
+    fn handle_modes(&mut self, tui: &mut Tui, mode: Mode, id: i64) -> Result<()> {
+        self.mode = mode;
+        // Home reports its selection again once back.
+        self.details = None;
+        let mut component: Box<dyn Component> = match mode {
+            Mode::Home => Box::new(Home::new(self.client.clone(), Some(id))?),
+            Mode::Properties => Box::new(Properties::new(self.client.clone(), id)?),
+            Mode::Duplicates => Box::new(Duplicates::new(self.client.clone(), id)?),
+            Mode::History => Box::new(HistoryChart::new(id)?),
+            Mode::Selection => {
+                Box::new(Selection::new(self.client.clone(), self.selection.clone())?)
+            }
+            Mode::Trackers => Box::new(Trackers::new(self.client.clone(), id)?),
+            Mode::Orphans => Box::new(Orphans::new(self.client.clone(), id)),
+            Mode::Session => Box::new(Session::new(self.client.clone(), id)?),
+            Mode::Statistics => Box::new(Statistics::new(self.client.clone(), id)?),
+        };
+        component.register_action_handler(self.action_tx.clone())?;
+        component.register_config_handler(self.config.clone())?;
+        component.init(tui.size()?)?;
+        self.components[self.view] = component;
         Ok(())
     }
 
     fn render(&mut self, tui: &mut Tui) -> Result<()> {
         tui.draw(|frame| {
-            for component in &mut self.components {
-                if let Err(err) = component.draw(frame, frame.area()) {
-                    let _ = self
-                        .action_tx
-                        .send(Action::Error(format!("Failed to draw: {err:?}")));
+            let area = frame.area();
+            let split = self.split_areas(area);
+            let mut results = Vec::new();
+            for (i, component) in self.components.iter_mut().enumerate() {
+                match split {
+                    Some((list, details)) if i == self.view => {
+                        results.push(component.draw(frame, list));
+                        results.extend(self.details.as_mut().map(|d| d.draw(frame, details)));
+                    }
+                    _ => results.push(component.draw(frame, area)),
                 }
             }
+            let stats = self.stats.as_mut().map(|s| s as &mut dyn Component);
+            for overlay in self.popup.iter_mut().map(|c| c.as_mut()).chain(stats) {
+                results.push(overlay.draw(frame, area));
+            }
+            for err in results.into_iter().filter_map(Result::err) {
+                let _ = self
+                    .action_tx
+                    .send(Action::error("App", format!("Failed to draw: {err:?}")));
+            }
         })?;
         Ok(())
     }
 }
+
+/// The components of the layout and the index of the one rendering the current mode.
+fn build_components(
+    client: &Rc<RefCell<Client>>,
+    mini: bool,
+) -> Result<(Vec<Box<dyn Component>>, usize)> {
+    Ok(match mini {
+        true => (
+            vec![
+                Box::new(SessionStat::new(client.clone())?),
+                Box::new(Mini::new(client.clone())?),
+            ],
+            1,
+        ),
+        false => (
+            vec![
+                Box::new(StatusBar::new()),
+                Box::new(ErrorBar::new()),
+                Box::new(SessionStat::new(client.clone())?),
+                Box::new(Home::new(client.clone(), None)?),
+                Box::new(Watcher::new(client.clone())),
+                Box::new(WatchDir::new(client.clone())),
+                Box::new(ActivityPanel::new()),
+                Box::new(Toast::new()),
+            ],
+            3,
+        ),
+    })
+}
+
+fn log_failure(failure: &Failure) {
+    let Failure {
+        level,
+        source,
+        torrent,
+        message,
+    } = failure;
+    let message = redact_urls(message);
+    match level {
+        Level::Error => error!(source, torrent, "{message}"),
+        Level::Warning => warn!(source, torrent, "{message}"),
+        Level::Info | Level::Success => info!(source, torrent, "{message}"),
+    }
+}
+
+/// Pass `details` the actions concerning them, closing them once their torrent is removed.
+fn update_details(details: &mut Option<Properties>, action: Action) -> Result<Option<Action>> {
+    let Some(properties) = details.as_mut() else {
+        return Ok(None);
+    };
+    let action = match action {
+        // The list samples the rates already.
+        Action::Tick => Action::Refresh,
+        Action::Refresh
+        | Action::Theme(_)
+        | Action::ToggleRaw
+        | Action::NextTab
+        | Action::PreviousTab => action,
+        _ => return Ok(None),
+    };
+    let reply = properties.update(action)?;
+    if properties.is_gone() {
+        // Going back to the list is for the full view, the list is already shown.
+        *details = None;
+        return Ok(None);
+    }
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        data::fixtures::torrent_json,
+        rpc::fixtures::{serve, success},
+    };
+
+    // The calls block on the runtime's reactor, which needs another thread to run.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_details_close_once_their_torrent_is_removed() {
+        // The torrents, then their queue positions, before and after the removal.
+        let removed = success(json!({ "torrents": [] }));
+        let url = serve(vec![
+            (
+                "200 OK",
+                success(json!({ "torrents": [torrent_json(7, true)] })),
+            ),
+            (
+                "200 OK",
+                success(json!({ "torrents": [{ "id": 7, "queuePosition": 0 }] })),
+            ),
+            ("200 OK", removed.clone()),
+            ("200 OK", removed),
+        ]);
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let mut details = Some(Properties::new(client, 7).unwrap());
+        assert_eq!(update_details(&mut details, Action::Tick).unwrap(), None);
+        assert!(details.is_none());
+    }
+}
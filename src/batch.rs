@@ -0,0 +1,135 @@
+//! Torrent actions applied to many torrents a chunk of ids at a time, so that no single call
+//! grows large enough to time out.
+
+use std::collections::VecDeque;
+
+use transmission_rpc::types::{Id, TorrentAction};
+
+use crate::action::{Action, Level};
+
+/// Ids sent per call.
+pub const BATCH_SIZE: usize = 50;
+
+pub struct Batch {
+    pub action: TorrentAction,
+    /// What is being done, e.g. `Starting`, shown along with the progress.
+    pub label: &'static str,
+    /// What was done, e.g. `Started`, used in the summary.
+    pub done_label: &'static str,
+    /// Remember the stopped torrents to resume them later, see [`Action::TogglePauseAll`].
+    pub remember: bool,
+    chunks: VecDeque<Vec<Id>>,
+    pub total: usize,
+    pub succeeded: Vec<Id>,
+    pub failed: Vec<Id>,
+    errors: Vec<String>,
+}
+
+impl Batch {
+    pub fn new(
+        action: TorrentAction,
+        (label, done_label): (&'static str, &'static str),
+        ids: Vec<Id>,
+    ) -> Self {
+        let total = ids.len();
+        let mut ids = ids.into_iter().peekable();
+        let mut chunks = VecDeque::new();
+        while ids.peek().is_some() {
+            chunks.push_back(ids.by_ref().take(BATCH_SIZE).collect());
+        }
+        Self {
+            action,
+            label,
+            done_label,
+            remember: false,
+            chunks,
+            total,
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn remember(mut self) -> Self {
+        self.remember = true;
+        self
+    }
+
+    pub fn next_chunk(&mut self) -> Option<Vec<Id>> {
+        self.chunks.pop_front()
+    }
+
+    /// Record the outcome of the call made for `chunk`.
+    pub fn finish_chunk(&mut self, chunk: Vec<Id>, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.succeeded.extend(chunk),
+            Err(err) => {
+                self.failed.extend(chunk);
+                self.errors.push(err);
+            }
+        }
+    }
+
+    pub fn done(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Whether the batch takes more than one call, and so is worth showing progress for.
+    pub fn is_large(&self) -> bool {
+        self.total > BATCH_SIZE
+    }
+
+    /// Report how many torrents were done, and why some weren't.
+    pub fn summary(&self) -> Action {
+        let Self {
+            done_label, total, ..
+        } = self;
+        match self.errors.first() {
+            None => Action::Notify(Level::Success, format!("{done_label} {total} torrents")),
+            Some(err) => Action::error(
+                "Home",
+                format!(
+                    "{done_label} {} of {total} torrents, {} failed: {err}",
+                    self.succeeded.len(),
+                    self.failed.len(),
+                ),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch() {
+        let ids = (0..120).map(Id::Id).collect();
+        let mut batch = Batch::new(TorrentAction::Start, ("Starting", "Started"), ids);
+        assert!(batch.is_large());
+
+        let mut sizes = Vec::new();
+        while let Some(chunk) = batch.next_chunk() {
+            sizes.push(chunk.len());
+            let result = match sizes.len() {
+                2 => Err("timed out".to_string()),
+                _ => Ok(()),
+            };
+            batch.finish_chunk(chunk, result);
+        }
+        assert_eq!(sizes, [50, 50, 20]);
+        assert!(batch.is_done());
+        assert_eq!(batch.done(), 120);
+        assert_eq!(
+            batch.summary(),
+            Action::error(
+                "Home",
+                "Started 70 of 120 torrents, 50 failed: timed out".to_string()
+            )
+        );
+    }
+}
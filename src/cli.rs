@@ -1,6 +1,13 @@
-use clap::Parser;
+use std::{net::SocketAddr, path::PathBuf};
 
-use crate::config::{get_config_dir, get_data_dir};
+use clap::{Parser, Subcommand};
+use reqwest::Url;
+
+use crate::{
+    app::Mode,
+    config::{get_config_dir, get_data_dir, Config, ServerProfile},
+    view::{Group, Sort, StatusFilter},
+};
 
 const DEFAULT_URL: &str = "http://localhost:9091/transmission/rpc";
 
@@ -8,28 +15,117 @@ const DEFAULT_URL: &str = "http://localhost:9091/transmission/rpc";
 #[command(author, version = version(), about)]
 /// TUI for transmission remote
 pub struct Cli {
-    /// RPC url
-    #[arg(
-        short,
-        long,
-        value_name = "URL",
-        value_parser = validate_url,
-        default_value = DEFAULT_URL
-    )]
-    pub url: String,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// RPC url [default: http://localhost:9091/transmission/rpc]
+    #[arg(short, long, global = true, value_name = "URL", value_parser = validate_url)]
+    pub url: Option<String>,
     /// Set username for authentication
-    #[arg(long, value_name = "USERNAME")]
+    #[arg(long, global = true, value_name = "USERNAME")]
     pub username: Option<String>,
-    /// Set password for authentication
-    #[arg(long, value_name = "PASSWORD")]
+    /// Set password for authentication, prompted for if only the username is given
+    #[arg(long, global = true, value_name = "PASSWORD")]
     pub password: Option<String>,
-    /// Tick rate, i.e. number of ticks per second
-    #[arg(short, long, value_name = "FLOAT", default_value_t = 0.5)]
-    pub tick_rate: f64,
+    /// Connect to a server defined in the `servers` section of the config
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+    /// Trust the certificates of a PEM file, e.g. the self-signed one of a seedbox
+    #[arg(long, global = true, value_name = "PATH")]
+    pub cacert: Option<PathBuf>,
+    /// Don't verify the server's certificate
+    #[arg(long, global = true)]
+    pub insecure: bool,
+    /// Tick rate, i.e. number of ticks per second [default: 0.5, or `tick_rate` of the config]
+    #[arg(short, long, value_name = "FLOAT")]
+    pub tick_rate: Option<f64>,
+
+    /// Frame rate, i.e. number of frames per second [default: 24, or `frame_rate` of the config]
+    #[arg(short, long, value_name = "FLOAT")]
+    pub frame_rate: Option<f64>,
 
-    /// Frame rate, i.e. number of frames per second
-    #[arg(short, long, value_name = "FLOAT", default_value_t = 24.0)]
-    pub frame_rate: f64,
+    /// Mode to start in, e.g. `home` or `duplicates`
+    #[arg(long, value_name = "MODE")]
+    pub mode: Option<Mode>,
+    /// Only list torrents in a state: all, downloading, seeding, paused, error, or completed
+    /// today, this week or this month: today, week, month
+    #[arg(long, value_name = "STATUS")]
+    pub filter: Option<StatusFilter>,
+    /// Sort the list, e.g. `added:desc` or `status,name` to break ties by name (name, done, eta,
+    /// down, up, ratio, added, queue, status)
+    #[arg(long, value_name = "KEY[:ORDER][,KEY[:ORDER]]")]
+    pub sort: Option<Sort>,
+    /// Group the list: none, tracker
+    #[arg(long, value_name = "GROUP")]
+    pub group: Option<Group>,
+    /// Only show the session stats and the most active torrents, e.g. in a tmux pane
+    #[arg(long)]
+    pub mini: bool,
+    /// Render in the current terminal buffer so the output stays in scrollback
+    #[arg(long)]
+    pub no_alt_screen: bool,
+    /// Color theme: dark, light, gruvbox, solarized or one defined in the config
+    #[arg(long, value_name = "THEME")]
+    pub theme: Option<String>,
+    /// Log every RPC call with its arguments, duration and response size
+    #[arg(long, global = true)]
+    pub debug_rpc: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Serve a local HTTP/JSON API forwarding to the daemon, without the TUI
+    Proxy {
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:9092")]
+        listen: SocketAddr,
+    },
+}
+
+impl Cli {
+    /// Override the startup settings of `config` with the ones given on the command line.
+    pub fn apply(&self, config: &mut Config) {
+        let startup = &mut config.startup;
+        startup.mode = self.mode.unwrap_or(startup.mode);
+        startup.filter = self.filter.unwrap_or(startup.filter);
+        startup.sort = self.sort.unwrap_or(startup.sort);
+        startup.group = self.group.unwrap_or(startup.group);
+        if let Some(theme) = &self.theme {
+            config.theme = Some(theme.clone());
+        }
+        if let Some(profile) = &self.profile {
+            config.profile = Some(profile.clone());
+        }
+    }
+
+    /// The server to connect to: the profile picked, if any, with the url and
+    /// credentials given on the command line taking precedence.
+    pub fn server(&self, config: &Config) -> Result<ServerProfile, String> {
+        let mut server = match &config.profile {
+            Some(name) => config
+                .servers
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("No server named `{name}` in the config"))?,
+            None => ServerProfile {
+                url: DEFAULT_URL.to_string(),
+                ..ServerProfile::default()
+            },
+        };
+        if let Some(url) = &self.url {
+            server.url.clone_from(url);
+        }
+        if self.username.is_some() {
+            server.username.clone_from(&self.username);
+        }
+        if self.password.is_some() {
+            server.password.clone_from(&self.password);
+        }
+        if self.cacert.is_some() {
+            server.ca_cert.clone_from(&self.cacert);
+        }
+        server.insecure |= self.insecure;
+        Ok(server)
+    }
 }
 
 fn validate_url(url: &str) -> Result<String, String> {
@@ -43,7 +139,11 @@ fn validate_url(url: &str) -> Result<String, String> {
         return Err("Invalid scheme: Scheme should be either 'http' or 'https'.".to_string());
     }
 
-    Ok(url.to_string())
+    // Any port and path go, for daemons behind a reverse proxy.
+    match Url::parse(url) {
+        Ok(_) => Ok(url.to_string()),
+        Err(err) => Err(format!("Invalid URL: {err}.")),
+    }
 }
 
 const VERSION_MESSAGE: &str = concat!(
@@ -65,3 +165,51 @@ Config directory: {config_dir_path}
 Data directory: {data_dir_path}"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server() {
+        let mut config = Config::default();
+        let seedbox = ServerProfile {
+            url: "https://seedbox.example/transmission/rpc".to_string(),
+            username: Some("me".to_string()),
+            password: Some("secret".to_string()),
+            download_dir: Some("/data".to_string()),
+            ..ServerProfile::default()
+        };
+        config
+            .servers
+            .insert("seedbox".to_string(), seedbox.clone());
+        let server = |args: &[&str], config: &mut Config| {
+            let cli = Cli::parse_from([&["sparrow"], args].concat());
+            cli.apply(config);
+            cli.server(config)
+        };
+
+        let local = server(&[], &mut config).unwrap();
+        assert_eq!(local.url, DEFAULT_URL);
+        assert_eq!(local.username, None);
+        assert_eq!(server(&["--profile", "seedbox"], &mut config), Ok(seedbox));
+        let overridden = server(&["--password", "other"], &mut config).unwrap();
+        assert_eq!(overridden.password.as_deref(), Some("other"));
+        assert_eq!(overridden.username.as_deref(), Some("me"));
+        let tls = server(&["--cacert", "ca.pem", "--insecure"], &mut config).unwrap();
+        assert_eq!(tls.ca_cert, Some(PathBuf::from("ca.pem")));
+        assert!(tls.insecure);
+        assert!(server(&["--profile", "nas"], &mut config).is_err());
+    }
+
+    #[test]
+    fn test_validate_url() {
+        let behind_proxy = "https://seedbox.example:8443/user/me/transmission/rpc";
+        assert_eq!(validate_url(behind_proxy), Ok(behind_proxy.to_string()));
+        assert!(validate_url("http://[::1]:9091/transmission/rpc").is_ok());
+        assert!(validate_url("localhost:9091").is_err());
+        assert!(validate_url("ftp://localhost/transmission/rpc").is_err());
+        assert!(validate_url("http://localhost:99999/transmission/rpc").is_err());
+        assert!(validate_url("http://:9091/transmission/rpc").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! Reading and writing the system clipboard through the tools of the platform, which also
+//! covers sessions over SSH with a forwarded display.
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// Commands printing the clipboard, tried in order until one succeeds.
+#[cfg(target_os = "macos")]
+const PASTE_COMMANDS: &[&[&str]] = &[&["pbpaste"]];
+#[cfg(target_os = "windows")]
+const PASTE_COMMANDS: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard"]];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const PASTE_COMMANDS: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-o"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+/// Commands filling the clipboard with their input, tried in order until one succeeds.
+#[cfg(target_os = "macos")]
+const COPY_COMMANDS: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(target_os = "windows")]
+const COPY_COMMANDS: &[&[&str]] = &[&["clip"]];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const COPY_COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard", "-i"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+/// The text in the clipboard.
+pub fn read() -> Result<String, String> {
+    for command in PASTE_COMMANDS {
+        let Ok(output) = Command::new(command[0]).args(&command[1..]).output() else {
+            continue;
+        };
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+    }
+    Err(missing("read", PASTE_COMMANDS))
+}
+
+/// Put `text` in the clipboard.
+pub fn write(text: &str) -> Result<(), String> {
+    for command in COPY_COMMANDS {
+        // The X11 tools stay around to serve the selection, their output is left alone so that
+        // waiting for them doesn't wait for that.
+        let Ok(mut child) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        if child.wait().is_ok_and(|status| status.success()) && written {
+            return Ok(());
+        }
+    }
+    Err(missing("write", COPY_COMMANDS))
+}
+
+/// The error when none of `commands` worked.
+fn missing(verb: &str, commands: &[&[&str]]) -> String {
+    format!(
+        "Can't {verb} the clipboard, install one of: {}",
+        commands
+            .iter()
+            .map(|command| command[0])
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
@@ -1,5 +1,20 @@
+use std::sync::RwLock;
+
 use ratatui::style::{palette::tailwind, Color};
+use serde::Deserialize;
+
+/// Names of the built-in themes, in the order they're cycled through.
+pub const BUILTIN_THEMES: [&str; 4] = ["dark", "light", "gruvbox", "solarized"];
+
+/// The colors components are created with, see [`set_theme`].
+static THEME: RwLock<Colors> = RwLock::new(Colors::DARK);
 
+/// Make `colors` the colors of components created from now on.
+pub fn set_theme(colors: Colors) {
+    *THEME.write().unwrap_or_else(|err| err.into_inner()) = colors;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Colors {
     pub buffer_bg: Color,
     pub header_bg: Color,
@@ -9,26 +24,156 @@ pub struct Colors {
     pub normal_row_color: Color,
     pub alt_row_color: Color,
     pub footer_border_color: Color,
+    pub warning_fg: Color,
+    pub error_fg: Color,
 
     pub tab_title_bg: Color,
     pub tab_selected: Color,
 }
 
 impl Colors {
-    /// Creates a new [`Colors`].
-    pub const fn new() -> Self {
-        let color = tailwind::BLUE;
-        Self {
-            buffer_bg: tailwind::SLATE.c950,
-            header_bg: color.c900,
-            header_fg: tailwind::SLATE.c200,
-            row_fg: tailwind::SLATE.c200,
-            selected_style_fg: color.c400,
-            normal_row_color: tailwind::SLATE.c950,
-            alt_row_color: tailwind::SLATE.c900,
-            footer_border_color: color.c400,
-            tab_title_bg: color.c900,
-            tab_selected: color.c400,
+    pub const DARK: Self = Self {
+        buffer_bg: tailwind::SLATE.c950,
+        header_bg: tailwind::BLUE.c900,
+        header_fg: tailwind::SLATE.c200,
+        row_fg: tailwind::SLATE.c200,
+        selected_style_fg: tailwind::BLUE.c400,
+        normal_row_color: tailwind::SLATE.c950,
+        alt_row_color: tailwind::SLATE.c900,
+        footer_border_color: tailwind::BLUE.c400,
+        warning_fg: tailwind::YELLOW.c400,
+        error_fg: tailwind::RED.c400,
+        tab_title_bg: tailwind::BLUE.c900,
+        tab_selected: tailwind::BLUE.c400,
+    };
+
+    pub const LIGHT: Self = Self {
+        buffer_bg: tailwind::SLATE.c50,
+        header_bg: tailwind::BLUE.c200,
+        header_fg: tailwind::SLATE.c900,
+        row_fg: tailwind::SLATE.c900,
+        selected_style_fg: tailwind::BLUE.c600,
+        normal_row_color: tailwind::SLATE.c50,
+        alt_row_color: tailwind::SLATE.c200,
+        footer_border_color: tailwind::BLUE.c600,
+        warning_fg: tailwind::AMBER.c600,
+        error_fg: tailwind::RED.c600,
+        tab_title_bg: tailwind::BLUE.c200,
+        tab_selected: tailwind::BLUE.c600,
+    };
+
+    pub const GRUVBOX: Self = Self {
+        buffer_bg: Color::from_u32(0x0028_2828),
+        header_bg: Color::from_u32(0x0050_4945),
+        header_fg: Color::from_u32(0x00eb_dbb2),
+        row_fg: Color::from_u32(0x00eb_dbb2),
+        selected_style_fg: Color::from_u32(0x00fa_bd2f),
+        normal_row_color: Color::from_u32(0x0028_2828),
+        alt_row_color: Color::from_u32(0x003c_3836),
+        footer_border_color: Color::from_u32(0x0083_a598),
+        warning_fg: Color::from_u32(0x00fa_bd2f),
+        error_fg: Color::from_u32(0x00fb_4934),
+        tab_title_bg: Color::from_u32(0x0050_4945),
+        tab_selected: Color::from_u32(0x00fe_8019),
+    };
+
+    pub const SOLARIZED: Self = Self {
+        buffer_bg: Color::from_u32(0x0000_2b36),
+        header_bg: Color::from_u32(0x0007_3642),
+        header_fg: Color::from_u32(0x0093_a1a1),
+        row_fg: Color::from_u32(0x0083_9496),
+        selected_style_fg: Color::from_u32(0x0026_8bd2),
+        normal_row_color: Color::from_u32(0x0000_2b36),
+        alt_row_color: Color::from_u32(0x0007_3642),
+        footer_border_color: Color::from_u32(0x002a_a198),
+        warning_fg: Color::from_u32(0x00b5_8900),
+        error_fg: Color::from_u32(0x00dc_322f),
+        tab_title_bg: Color::from_u32(0x0007_3642),
+        tab_selected: Color::from_u32(0x0026_8bd2),
+    };
+
+    /// The colors of the current theme.
+    pub fn new() -> Self {
+        *THEME.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "dark" => Some(Self::DARK),
+            "light" => Some(Self::LIGHT),
+            "gruvbox" => Some(Self::GRUVBOX),
+            "solarized" => Some(Self::SOLARIZED),
+            _ => None,
+        }
+    }
+}
+
+/// A theme defined in the config file, any color left out is taken from `base`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CustomTheme {
+    /// Built-in theme to start from, `dark` if not set.
+    pub base: Option<String>,
+    pub buffer_bg: Option<Color>,
+    pub header_bg: Option<Color>,
+    pub header_fg: Option<Color>,
+    pub row_fg: Option<Color>,
+    pub selected_style_fg: Option<Color>,
+    pub normal_row_color: Option<Color>,
+    pub alt_row_color: Option<Color>,
+    pub footer_border_color: Option<Color>,
+    pub warning_fg: Option<Color>,
+    pub error_fg: Option<Color>,
+    pub tab_title_bg: Option<Color>,
+    pub tab_selected: Option<Color>,
+}
+
+impl CustomTheme {
+    pub fn colors(&self) -> Result<Colors, String> {
+        let base = self.base.as_deref().unwrap_or("dark");
+        let base = Colors::builtin(base).ok_or(format!("Unknown base theme `{base}`"))?;
+        Ok(Colors {
+            buffer_bg: self.buffer_bg.unwrap_or(base.buffer_bg),
+            header_bg: self.header_bg.unwrap_or(base.header_bg),
+            header_fg: self.header_fg.unwrap_or(base.header_fg),
+            row_fg: self.row_fg.unwrap_or(base.row_fg),
+            selected_style_fg: self.selected_style_fg.unwrap_or(base.selected_style_fg),
+            normal_row_color: self.normal_row_color.unwrap_or(base.normal_row_color),
+            alt_row_color: self.alt_row_color.unwrap_or(base.alt_row_color),
+            footer_border_color: self.footer_border_color.unwrap_or(base.footer_border_color),
+            warning_fg: self.warning_fg.unwrap_or(base.warning_fg),
+            error_fg: self.error_fg.unwrap_or(base.error_fg),
+            tab_title_bg: self.tab_title_bg.unwrap_or(base.tab_title_bg),
+            tab_selected: self.tab_selected.unwrap_or(base.tab_selected),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes() {
+        for name in BUILTIN_THEMES {
+            assert!(Colors::builtin(name).is_some(), "{name}");
         }
+        assert_eq!(Colors::builtin("Gruvbox"), Some(Colors::GRUVBOX));
+        assert_eq!(Colors::builtin("nord"), None);
+    }
+
+    #[test]
+    fn test_custom_theme() {
+        let theme: CustomTheme =
+            serde_json::from_str(r##"{ "base": "light", "row_fg": "#ff0000" }"##).unwrap();
+        let colors = theme.colors().unwrap();
+        assert_eq!(colors.row_fg, Color::Rgb(255, 0, 0));
+        assert_eq!(colors.buffer_bg, Colors::LIGHT.buffer_bg);
+
+        let theme = CustomTheme {
+            base: Some("nord".to_string()),
+            ..Default::default()
+        };
+        assert!(theme.colors().is_err());
     }
 }
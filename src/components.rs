@@ -1,16 +1,117 @@
+use std::fmt::Display;
+
 use color_eyre::Result;
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::{
-    layout::{Rect, Size},
+    layout::{Constraint, Layout, Position, Rect, Size},
     Frame,
 };
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{action::Action, config::Config, tui::Event};
 
+pub mod activity;
+pub mod add_torrent;
+pub mod columns;
+pub mod confirm;
+pub mod duplicates;
+pub mod error_bar;
+pub mod history;
 pub mod home;
+pub mod info;
+pub mod input;
+pub mod menu;
+pub mod mini;
+pub mod orphans;
+pub mod palette;
+pub mod prompt;
 pub mod properties;
+pub mod rates;
+pub mod search;
+pub mod seed_limits;
+pub mod selection;
+pub mod session;
 pub mod session_stats;
+#[cfg(test)]
+pub mod snapshot;
+pub mod speed_limits;
+pub mod statistics;
+pub mod stats;
+pub mod status_bar;
+pub mod toast;
+pub mod trackers;
+pub mod watch_dir;
+pub mod watcher;
+
+/// Notify how a call went, through `tx` if it worked, and refresh right away so that the change
+/// shows before the next tick. Failures are errors of `source`.
+pub fn report(
+    tx: Option<&UnboundedSender<Action>>,
+    source: &str,
+    result: std::result::Result<(), impl Display>,
+    success: impl Into<String>,
+) -> Result<Option<Action>> {
+    if result.is_err() {
+        return Ok(Some(Action::outcome(source, result, success)));
+    }
+    if let Some(tx) = tx {
+        tx.send(Action::outcome(source, result, success))?;
+    }
+    Ok(Some(Action::Refresh))
+}
+
+/// Split the screen into the status line, the main body and the footer.
+pub fn main_layout(area: Rect) -> [Rect; 3] {
+    Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(5),
+        Constraint::Length(3),
+    ])
+    .areas(area)
+}
+
+/// Split the screen into everything above the footer and the footer.
+pub fn footer_layout(area: Rect) -> [Rect; 2] {
+    Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).areas(area)
+}
+
+/// A `width` x `height` rectangle centered in `area`, clamped to fit.
+pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// The index of the row at `position` in a table drawn in `area`, given the height of its header
+/// and of its rows and the index of the first row shown.
+pub fn row_at(
+    area: Rect,
+    header: u16,
+    row_height: u16,
+    offset: usize,
+    position: Position,
+) -> Option<usize> {
+    let top = area.y + header;
+    if !area.contains(position) || position.y < top || row_height == 0 {
+        return None;
+    }
+    Some(offset + usize::from((position.y - top) / row_height))
+}
+
+/// How many rows a page scroll moves in a list showing `rows`: all of them, or half for `half`,
+/// and at least one.
+pub fn page_rows(rows: usize, half: bool) -> usize {
+    match half {
+        true => rows / 2,
+        false => rows,
+    }
+    .max(1)
+}
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
@@ -69,6 +170,7 @@ pub trait Component {
         let action = match event {
             Some(Event::Key(key_event)) => self.handle_key_event(key_event)?,
             Some(Event::Mouse(mouse_event)) => self.handle_mouse_event(mouse_event)?,
+            Some(Event::Paste(text)) => self.handle_paste_event(text)?,
             _ => None,
         };
         Ok(action)
@@ -99,6 +201,19 @@ pub trait Component {
         let _ = mouse; // to appease clippy
         Ok(None)
     }
+    /// Handle text pasted into the terminal and produce actions if necessary.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text pasted, all at once.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Action>>` - An action to be processed or none.
+    fn handle_paste_event(&mut self, text: String) -> Result<Option<Action>> {
+        let _ = text; // to appease clippy
+        Ok(None)
+    }
     /// Update the state of the component based on a received action. (REQUIRED)
     ///
     /// # Arguments
@@ -124,3 +239,30 @@ pub trait Component {
     /// * `Result<()>` - An Ok result or an error.
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_at() {
+        let area = Rect::new(2, 5, 40, 10);
+        let at = |x, y| row_at(area, 1, 2, 3, Position::new(x, y));
+        assert_eq!(at(2, 6), Some(3));
+        assert_eq!(at(10, 7), Some(3));
+        assert_eq!(at(10, 8), Some(4));
+        assert_eq!(at(10, 14), Some(7));
+        // The header, and outside of the table.
+        assert_eq!(at(10, 5), None);
+        assert_eq!(at(10, 15), None);
+        assert_eq!(at(1, 8), None);
+    }
+
+    #[test]
+    fn test_page_rows() {
+        assert_eq!(page_rows(20, false), 20);
+        assert_eq!(page_rows(20, true), 10);
+        assert_eq!(page_rows(1, true), 1);
+        assert_eq!(page_rows(0, false), 1);
+    }
+}
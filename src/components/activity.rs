@@ -0,0 +1,177 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+use color_eyre::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    action::{Action, Activity},
+    app::Mode,
+    colors::Colors,
+};
+
+use super::{main_layout, Component};
+
+/// Events kept, the oldest are dropped first.
+const MAX_EVENTS: usize = 200;
+const PANEL_WIDTH: u16 = 48;
+
+/// What happened to the torrents lately, newest first, in a panel at the right of the torrent
+/// list.
+pub struct ActivityPanel {
+    /// Newest last.
+    events: VecDeque<(DateTime<Local>, Activity, String)>,
+    visible: bool,
+    /// The panel only covers the torrent list.
+    home: bool,
+    /// Events that came while the panel was hidden, counted in its title until it's hidden again.
+    unseen: usize,
+    colors: Colors,
+}
+
+impl ActivityPanel {
+    pub fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+            visible: false,
+            home: true,
+            unseen: 0,
+            colors: Colors::new(),
+        }
+    }
+
+    fn push(&mut self, time: DateTime<Local>, activity: Activity, text: String) {
+        self.events.push_back((time, activity, text));
+        if self.events.len() > MAX_EVENTS {
+            self.events.pop_front();
+        }
+        if !self.visible {
+            self.unseen += 1;
+        }
+    }
+
+    fn line(&self, time: DateTime<Local>, activity: Activity, text: &str) -> Line<'static> {
+        let (label, color) = match activity {
+            Activity::Added => ("Added", self.colors.row_fg),
+            Activity::Completed => ("Completed", self.colors.selected_style_fg),
+            Activity::Stalled => ("Stalled", self.colors.warning_fg),
+            Activity::TrackerError => ("Tracker", self.colors.warning_fg),
+            Activity::Error => ("Error", self.colors.error_fg),
+            Activity::Removed => ("Removed", self.colors.footer_border_color),
+        };
+        // Events of other days tell which.
+        let format = match time.date_naive() == Local::now().date_naive() {
+            true => "%H:%M",
+            false => "%d/%m %H:%M",
+        };
+        Line::from(vec![
+            Span::from(format!("{} ", time.format(format))).fg(self.colors.footer_border_color),
+            Span::from(format!("{label:<9} ")).fg(color).bold(),
+            Span::from(text.to_string()).fg(self.colors.row_fg),
+        ])
+    }
+}
+
+impl Component for ActivityPanel {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Activity(activity, text) => self.push(Local::now(), activity, text),
+            Action::ToggleActivity => {
+                self.visible = !self.visible;
+                if !self.visible {
+                    self.unseen = 0;
+                }
+            }
+            Action::Mode(mode, _) => self.home = mode == Mode::Home,
+            Action::Theme(_) => self.colors = Colors::new(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible || !self.home {
+            return Ok(());
+        }
+        let [_, body, _] = main_layout(area);
+        let width = PANEL_WIDTH.min(body.width);
+        let rect = Rect {
+            x: body.right() - width,
+            width,
+            ..body
+        };
+        let title = match self.unseen {
+            0 => " Activity ".to_string(),
+            unseen => format!(" Activity · {unseen} new "),
+        };
+        let block = Block::bordered()
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title(title.bold().fg(self.colors.row_fg));
+        let lines = match self.events.is_empty() {
+            true => vec![Line::from("Nothing happened yet").fg(self.colors.footer_border_color)],
+            false => self
+                .events
+                .iter()
+                .rev()
+                .map(|(time, activity, text)| self.line(*time, *activity, text))
+                .collect(),
+        };
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .style(Style::default().bg(self.colors.buffer_bg)),
+            rect,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_unseen() {
+        let mut panel = ActivityPanel::new();
+        panel.push(Local::now(), Activity::Added, "a".to_string());
+        panel.update(Action::ToggleActivity).unwrap();
+        panel.push(Local::now(), Activity::Added, "b".to_string());
+        assert_eq!(panel.unseen, 1);
+        panel.update(Action::ToggleActivity).unwrap();
+        assert_eq!(panel.unseen, 0);
+    }
+
+    // The panel only gets narrower on the small terminal.
+    #[test]
+    fn test_snapshot() {
+        let day = Local::now().date_naive();
+        let at = |hour, minute| {
+            Local
+                .from_local_datetime(&day.and_hms_opt(hour, minute, 0).unwrap())
+                .unwrap()
+        };
+        assert_snapshot("activity", SIZES[0], |frame| {
+            let mut panel = ActivityPanel::new();
+            panel.push(at(9, 5), Activity::Added, "debian-12.iso".to_string());
+            panel.push(at(9, 40), Activity::Stalled, "debian-12.iso".to_string());
+            panel.push(
+                at(10, 2),
+                Activity::TrackerError,
+                "ubuntu.iso: Tracker gave HTTP response code 404".to_string(),
+            );
+            panel.push(at(11, 30), Activity::Completed, "debian-12.iso".to_string());
+            panel.update(Action::ToggleActivity).unwrap();
+            panel.draw(frame, frame.area()).unwrap();
+        });
+    }
+}
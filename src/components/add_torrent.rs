@@ -0,0 +1,237 @@
+use std::path::Path;
+
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors};
+
+use super::{centered_rect, input::Input, Component};
+
+const DIALOG_WIDTH: u16 = 64;
+const DIALOG_HEIGHT: u16 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Source,
+    Location,
+    Paused,
+}
+
+/// A popup asking for a `.torrent` file, a link to one or a magnet link to add.
+pub struct AddTorrent {
+    source: Input,
+    location: Input,
+    paused: bool,
+    focus: Field,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl AddTorrent {
+    /// `location` pre-fills the download directory, usually the session's default.
+    pub fn new(location: String) -> Self {
+        Self {
+            source: Input::default(),
+            location: Input::new(location),
+            paused: false,
+            focus: Field::Source,
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    /// Start with `source` filled in, leaving the download directory to confirm.
+    pub fn with_source(mut self, source: String) -> Self {
+        self.source = Input::new(source);
+        self.focus = Field::Location;
+        self
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+
+    fn next_field(&mut self) {
+        self.focus = match self.focus {
+            Field::Source => Field::Location,
+            Field::Location => Field::Paused,
+            Field::Paused => Field::Source,
+        };
+    }
+
+    fn previous_field(&mut self) {
+        self.focus = match self.focus {
+            Field::Source => Field::Paused,
+            Field::Location => Field::Source,
+            Field::Paused => Field::Location,
+        };
+    }
+
+    fn field_block(&self, title: &'static str, field: Field) -> Block<'static> {
+        let color = match self.focus == field {
+            true => self.colors.selected_style_fg,
+            false => self.colors.footer_border_color,
+        };
+        Block::bordered()
+            .border_style(Style::default().fg(color))
+            .title(title)
+    }
+}
+
+/// `text` as a source to add, if it's a magnet link or the path of a `.torrent` file.
+pub fn torrent_source(text: &str) -> Option<String> {
+    let text = text.trim();
+    let is_magnet = text
+        .get(..8)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("magnet:?"))
+        && (text.contains("xt=urn:btih:") || text.contains("xt=urn:btmh:"));
+    let path = text.strip_prefix("file://").unwrap_or(text);
+    let is_torrent_file = Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("torrent"))
+        && Path::new(path).is_file();
+    match (is_magnet, is_torrent_file) {
+        (true, _) => Some(text.to_string()),
+        (_, true) => Some(path.to_string()),
+        _ => None,
+    }
+}
+
+impl Component for AddTorrent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.close()?,
+            KeyCode::Tab | KeyCode::Down => self.next_field(),
+            KeyCode::BackTab | KeyCode::Up => self.previous_field(),
+            KeyCode::Enter => {
+                let source = self.source.value().trim();
+                if source.is_empty() {
+                    self.focus = Field::Source;
+                    return Ok(None);
+                }
+                self.close()?;
+                return Ok(Some(Action::AddTorrent(
+                    source.to_string(),
+                    self.location.value().trim().to_string(),
+                    self.paused,
+                )));
+            }
+            KeyCode::Char(' ') if self.focus == Field::Paused => self.paused = !self.paused,
+            _ => {
+                match self.focus {
+                    Field::Source => self.source.handle_key(key),
+                    Field::Location => self.location.handle_key(key),
+                    Field::Paused => false,
+                };
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_paste_event(&mut self, text: String) -> Result<Option<Action>> {
+        match self.focus {
+            Field::Source => self.source.insert(&text),
+            Field::Location => self.location.insert(&text),
+            Field::Paused => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let rect = centered_rect(area, DIALOG_WIDTH, DIALOG_HEIGHT);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title("Add torrent".bold())
+            .title_bottom(Line::from(" Tab: next · Enter: add · Esc: cancel ").right_aligned())
+            .style(style);
+        let [source, location, paused] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(block.inner(rect));
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(block, rect);
+        self.source.draw(
+            frame,
+            source,
+            self.field_block(".torrent file, URL or magnet link", Field::Source),
+            style,
+            self.focus == Field::Source,
+        );
+        self.location.draw(
+            frame,
+            location,
+            self.field_block("Download directory", Field::Location),
+            style,
+            self.focus == Field::Location,
+        );
+        let checkbox = format!(" [{}] Start paused", if self.paused { "x" } else { " " });
+        let checkbox = match self.focus {
+            Field::Paused => Line::from(checkbox).fg(self.colors.selected_style_fg),
+            _ => Line::from(checkbox),
+        };
+        frame.render_widget(checkbox, paused);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_torrent_source() {
+        let magnet = "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a&dn=x";
+        assert_eq!(
+            torrent_source(&format!("  {magnet}\n")),
+            Some(magnet.to_string())
+        );
+        assert_eq!(torrent_source("magnet:?dn=no-hash"), None);
+        assert_eq!(torrent_source("https://example.com"), None);
+        assert_eq!(torrent_source("/nowhere/missing.torrent"), None);
+
+        let path = std::env::temp_dir().join("sparrow-test-source.torrent");
+        std::fs::write(&path, "d4:infod4:name1:xee").unwrap();
+        let path = path.to_string_lossy().to_string();
+        assert_eq!(
+            torrent_source(&format!("file://{path}")),
+            Some(path.clone())
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    // A dialog of fixed size, which only gets its titles cut on the small terminal.
+    #[test]
+    fn test_snapshot() {
+        let mut dialog = AddTorrent::new("/downloads".to_string()).with_source(
+            "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a".to_string(),
+        );
+        dialog.paused = true;
+        assert_snapshot("add_torrent", SIZES[0], |frame| {
+            dialog.draw(frame, frame.area()).unwrap();
+        });
+    }
+}
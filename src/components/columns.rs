@@ -0,0 +1,186 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, HighlightSpacing, List, ListState},
+    Frame,
+};
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors, view::Column};
+
+use super::{centered_rect, Component};
+
+const PICKER_WIDTH: u16 = 44;
+
+/// A popup choosing the columns of Home's table and their order, applied on `Enter`.
+pub struct ColumnPicker {
+    /// Every column in the order they'd appear, with whether it's shown.
+    columns: Vec<(Column, bool)>,
+    state: ListState,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl ColumnPicker {
+    /// A picker starting from the `shown` columns, followed by the hidden ones.
+    pub fn new(shown: &[Column]) -> Self {
+        let hidden = Column::iter().filter(|column| !shown.contains(column));
+        Self {
+            columns: shown
+                .iter()
+                .map(|&column| (column, true))
+                .chain(hidden.map(|column| (column, false)))
+                .collect(),
+            state: ListState::default().with_selected(Some(0)),
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    /// The columns shown, in order.
+    fn shown(&self) -> Vec<Column> {
+        self.columns
+            .iter()
+            .filter(|(_, shown)| *shown)
+            .map(|&(column, _)| column)
+            .collect()
+    }
+
+    /// Show or hide the selected column, keeping at least one shown.
+    fn toggle(&mut self) {
+        let last_shown = self.shown().len() == 1;
+        if let Some((_, shown)) = self.state.selected().and_then(|i| self.columns.get_mut(i)) {
+            if !(*shown && last_shown) {
+                *shown = !*shown;
+            }
+        }
+    }
+
+    /// Move the selected column up if `up`, down otherwise, keeping it selected.
+    fn shift(&mut self, up: bool) {
+        let Some(i) = self.state.selected() else {
+            return;
+        };
+        let target = match up {
+            true => i.checked_sub(1),
+            false => Some(i + 1).filter(|&j| j < self.columns.len()),
+        };
+        if let Some(j) = target {
+            self.columns.swap(i, j);
+            self.state.select(Some(j));
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for ColumnPicker {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.state.select_previous(),
+            KeyCode::Char('J') => self.shift(false),
+            KeyCode::Char('K') => self.shift(true),
+            KeyCode::Char(' ') | KeyCode::Char('x') => self.toggle(),
+            KeyCode::Enter => {
+                self.close()?;
+                return Ok(Some(Action::SetColumns(self.shown())));
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.close()?,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        #[allow(clippy::cast_possible_truncation)]
+        let height = self.columns.len() as u16 + 2;
+        let rect = centered_rect(area, PICKER_WIDTH, height);
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(self.colors.selected_style_fg);
+
+        let items = self.columns.iter().map(|(column, shown)| {
+            let check = if *shown { "[x]" } else { "[ ]" };
+            format!("{check} {}", column.title())
+        });
+        let list = List::new(items)
+            .style(
+                Style::default()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .highlight_style(selected_style)
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(self.colors.footer_border_color))
+                    .title("Columns".bold())
+                    .title_bottom(
+                        Line::from(" Space: show · J/K: move · Enter: apply ").right_aligned(),
+                    ),
+            );
+        frame.render_widget(Clear, rect);
+        frame.render_stateful_widget(list, rect, &mut self.state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        view::DEFAULT_COLUMNS,
+    };
+
+    #[test]
+    fn test_column_picker() {
+        let mut picker = ColumnPicker::new(&[Column::Eta, Column::Name]);
+        assert_eq!(picker.columns.len(), Column::iter().count());
+        assert_eq!(picker.columns[2], (Column::Done, false));
+
+        picker.shift(false);
+        assert_eq!(picker.shown(), [Column::Name, Column::Eta]);
+        assert_eq!(picker.state.selected(), Some(1));
+        picker.toggle();
+        assert_eq!(picker.shown(), [Column::Name]);
+        // The last column shown stays.
+        picker.state.select(Some(0));
+        picker.toggle();
+        assert_eq!(picker.shown(), [Column::Name]);
+
+        picker.state.select(Some(2));
+        picker.toggle();
+        picker.shift(true);
+        picker.shift(true);
+        assert_eq!(picker.shown(), [Column::Done, Column::Name]);
+        picker.shift(true);
+        assert_eq!(picker.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut picker = ColumnPicker::new(&DEFAULT_COLUMNS);
+        for size in SIZES {
+            assert_snapshot("columns", size, |frame| {
+                picker.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
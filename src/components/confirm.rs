@@ -0,0 +1,137 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors};
+
+use super::{centered_rect, Component};
+
+const CONFIRM_WIDTH: u16 = 50;
+const CONFIRM_HEIGHT: u16 = 7;
+
+/// A yes/no popup dispatching `action` only when confirmed.
+pub struct Confirm {
+    message: String,
+    action: Action,
+    /// Whether "Yes" is focused, "No" is by default.
+    yes: bool,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Confirm {
+    pub fn new(message: String, action: Action) -> Self {
+        Self {
+            message,
+            action,
+            yes: false,
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for Confirm {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.close()?;
+                return Ok(Some(self.action.clone()));
+            }
+            KeyCode::Enter => {
+                self.close()?;
+                return Ok(self.yes.then(|| self.action.clone()));
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') | KeyCode::Esc => {
+                self.close()?;
+            }
+            KeyCode::Char('h')
+            | KeyCode::Char('l')
+            | KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::Tab
+            | KeyCode::BackTab => self.yes = !self.yes,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        // Grow for long messages, e.g. with warnings, allowing a line for wrapping words.
+        #[allow(clippy::cast_possible_truncation)]
+        let lines = (self.message.chars().count() as u16).div_ceil(CONFIRM_WIDTH - 2) + 1;
+        let rect = centered_rect(area, CONFIRM_WIDTH, CONFIRM_HEIGHT.max(lines + 3));
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.colors.warning_fg))
+            .title("Confirm".bold())
+            .style(
+                Style::default()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            );
+        let [message, buttons] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(block.inner(rect));
+        let [yes, no] = Layout::horizontal([Constraint::Length(9); 2])
+            .flex(Flex::Center)
+            .spacing(4)
+            .areas(buttons);
+        let focused = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(self.colors.selected_style_fg);
+        let (yes_style, no_style) = match self.yes {
+            true => (focused, Style::default()),
+            false => (Style::default(), focused),
+        };
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(block, rect);
+        frame.render_widget(
+            Paragraph::new(self.message.as_str())
+                .centered()
+                .wrap(Wrap { trim: true }),
+            message,
+        );
+        frame.render_widget(Line::from("[Y]es").centered().style(yes_style), yes);
+        frame.render_widget(Line::from("[N]o").centered().style(no_style), no);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_snapshot() {
+        let mut confirm = Confirm::new(
+            "Remove ubuntu.iso and delete its data?".to_string(),
+            Action::RemoveSelectedWithData,
+        );
+        for size in SIZES {
+            assert_snapshot("confirm", size, |frame| {
+                confirm.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
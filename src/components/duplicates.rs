@@ -0,0 +1,214 @@
+use std::{cell::RefCell, rc::Rc};
+
+use color_eyre::Result;
+use futures::executor::block_on;
+use itertools::Itertools;
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{
+        Block, HighlightSpacing, List, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState,
+    },
+    Frame,
+};
+
+use crate::{
+    action::Action,
+    app::Mode,
+    colors::Colors,
+    data::{find_duplicates, map_torrent_data, Torrent},
+    rpc::Client,
+};
+
+use super::{main_layout, Component};
+
+/// A torrent of a duplicate group, with the group's header when it's the first member.
+struct Entry {
+    header: Option<String>,
+    torrent: Torrent,
+}
+
+/// Report of torrents sharing a name or files on disk, e.g. after cross-seeding.
+pub struct Duplicates {
+    client: Rc<RefCell<Client>>,
+    entries: Vec<Entry>,
+    /// Torrent selected in Home, restored when going back.
+    home_id: i64,
+    state: ListState,
+    scroll_state: ScrollbarState,
+    colors: Colors,
+}
+
+impl Duplicates {
+    pub fn new(client: Rc<RefCell<Client>>, home_id: i64) -> Result<Self> {
+        let mut duplicates = Self {
+            client,
+            entries: Vec::new(),
+            home_id,
+            state: ListState::default().with_selected(Some(0)),
+            scroll_state: ScrollbarState::default(),
+            colors: Colors::new(),
+        };
+        duplicates.refresh()?;
+        Ok(duplicates)
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        let torrents = block_on(map_torrent_data(&self.client, None))?;
+        self.entries = find_duplicates(&torrents)
+            .into_iter()
+            .flat_map(|group| {
+                let reason = match (group.same_name, group.overlapping_files) {
+                    (true, true) => "Same name, overlapping files",
+                    (true, false) => "Same name",
+                    _ => "Overlapping files",
+                };
+                let header = format!("{reason} ({} torrents)", group.members.len());
+                group
+                    .members
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, member)| Entry {
+                        header: (i == 0).then(|| header.clone()),
+                        torrent: torrents[member].clone(),
+                    })
+                    .collect_vec()
+            })
+            .collect();
+        self.scroll_state = self.scroll_state.content_length(self.entries.len());
+        Ok(())
+    }
+
+    fn select(&mut self, index: usize) {
+        self.state.select(Some(index));
+        self.scroll_state = self.scroll_state.position(index);
+    }
+}
+
+impl Component for Duplicates {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let last = self.entries.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0);
+        match action {
+            Action::Refresh => {
+                if let Err(err) = self.refresh() {
+                    return Ok(Some(Action::error("Duplicates", err)));
+                }
+            }
+            Action::Back => return Ok(Some(Action::Mode(Mode::Home, self.home_id))),
+            Action::Theme(_) => self.colors = Colors::new(),
+            Action::Down => self.select((selected + 1).min(last)),
+            Action::Up => self.select(selected.saturating_sub(1)),
+            Action::Top => self.select(0),
+            Action::Bottom => self.select(last),
+            Action::Select => {
+                if let Some(entry) = self.entries.get(selected) {
+                    return Ok(Some(Action::Mode(Mode::Properties, entry.torrent.id)));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let block = Block::bordered()
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title("Duplicates".bold().fg(self.colors.row_fg));
+
+        if self.entries.is_empty() {
+            let empty = Paragraph::new("No duplicates found")
+                .style(style)
+                .block(block);
+            frame.render_widget(empty, body);
+            return Ok(());
+        }
+
+        let items = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let torrent = &entry.torrent;
+                let mut lines = Vec::new();
+                if let Some(header) = &entry.header {
+                    lines.push(Line::raw(header.clone()).bold().fg(self.colors.header_fg));
+                }
+                lines.push(Line::raw(format!("  {}", torrent.name)));
+                lines.push(
+                    Line::raw(format!(
+                        "    {}  {}  {}",
+                        torrent.total_size(),
+                        torrent.location,
+                        torrent.hash
+                    ))
+                    .gray(),
+                );
+                Text::from(lines)
+            })
+            .collect_vec();
+        let list = List::new(items)
+            .style(style)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.colors.selected_style_fg),
+            )
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(block);
+
+        frame.render_stateful_widget(list, body, &mut self.state);
+        frame.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            body.inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            &mut self.scroll_state,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        data::fixtures::torrent_json,
+        rpc::fixtures::{serve, success},
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_snapshot() {
+        let torrents = [1, 2, 3].map(|id| {
+            let mut torrent = torrent_json(id, false);
+            if id < 3 {
+                torrent["name"] = json!("debian-12.5.0-amd64-netinst.iso");
+                torrent["downloadDir"] = json!(format!("/downloads/{id}"));
+            }
+            torrent
+        });
+        let url = serve(vec![
+            ("200 OK", success(json!({ "torrents": torrents }))),
+            ("200 OK", success(json!({ "torrents": [] }))),
+        ]);
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let mut duplicates = Duplicates::new(client, 1).unwrap();
+        for size in SIZES {
+            assert_snapshot("duplicates", size, |frame| {
+                duplicates.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
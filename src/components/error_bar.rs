@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+use color_eyre::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    action::{Action, Failure, Level},
+    colors::Colors,
+    utils::redact_urls,
+};
+
+use super::{main_layout, Component};
+
+/// Errors kept for the history, the oldest are dropped first.
+const MAX_ERRORS: usize = 100;
+
+/// The latest error, at the right of the status bar until a tick goes by without one, and the
+/// errors of the run.
+pub struct ErrorBar {
+    /// Newest last.
+    errors: VecDeque<(DateTime<Local>, Failure)>,
+    /// Whether the latest error is still shown.
+    current: bool,
+    /// Whether an error came since the last tick, which tells refreshes that failed again.
+    since_tick: bool,
+    /// The status bar shows the lost connection instead.
+    disconnected: bool,
+    colors: Colors,
+}
+
+impl ErrorBar {
+    pub fn new() -> Self {
+        Self {
+            errors: VecDeque::new(),
+            current: false,
+            since_tick: false,
+            disconnected: false,
+            colors: Colors::new(),
+        }
+    }
+
+    /// The errors, newest first, for the history.
+    fn history(&self) -> Vec<String> {
+        self.errors
+            .iter()
+            .rev()
+            .map(|(time, failure)| format!("{} {}", time.format("%H:%M:%S"), describe(failure)))
+            .collect()
+    }
+}
+
+/// What went wrong and where, e.g. `Properties · torrent 4: Connection refused`.
+fn describe(failure: &Failure) -> String {
+    let torrent = failure
+        .torrent
+        .map(|id| format!(" · torrent {id}"))
+        .unwrap_or_default();
+    format!(
+        "{}{torrent}: {}",
+        failure.source,
+        redact_urls(&failure.message)
+    )
+}
+
+/// `text` cut to `width` columns, ending with an ellipsis if cut.
+fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut cut = String::new();
+    for c in text.chars() {
+        if cut.width() + c.to_string().width() + 1 > width {
+            break;
+        }
+        cut.push(c);
+    }
+    format!("{cut}…")
+}
+
+impl Component for ErrorBar {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Error(failure) => {
+                self.errors.push_back((Local::now(), failure));
+                if self.errors.len() > MAX_ERRORS {
+                    self.errors.pop_front();
+                }
+                self.current = true;
+                self.since_tick = true;
+            }
+            // The refreshes of the last tick went through.
+            Action::Tick if !self.since_tick => self.current = false,
+            Action::Tick => self.since_tick = false,
+            Action::Disconnected(_) => self.disconnected = true,
+            Action::Reconnected => {
+                self.disconnected = false;
+                self.current = false;
+            }
+            Action::OpenErrors => {
+                let lines = match self.errors.is_empty() {
+                    true => vec!["No errors so far".to_string()],
+                    false => self.history(),
+                };
+                return Ok(Some(Action::ShowInfo("Errors".to_string(), lines)));
+            }
+            Action::Theme(_) => self.colors = Colors::new(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some((time, failure)) = self.errors.back().filter(|_| self.current) else {
+            return Ok(());
+        };
+        if self.disconnected {
+            return Ok(());
+        }
+        let [header, _, _] = main_layout(area);
+        let color = match failure.level {
+            Level::Error => self.colors.error_fg,
+            _ => self.colors.warning_fg,
+        };
+        let text = format!(" ⚠ {} {} ", time.format("%H:%M:%S"), describe(failure));
+        let text = truncate(&text, usize::from(header.width / 2));
+        #[allow(clippy::cast_possible_truncation)]
+        let width = text.width() as u16;
+        let rect = Rect::new(header.right() - width, header.y, width, 1);
+        let banner = Paragraph::new(Line::from(text)).style(
+            Style::default()
+                .fg(color)
+                .bg(self.colors.header_bg)
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_widget(banner, rect);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_cleared_after_a_quiet_tick() {
+        let mut bar = ErrorBar::new();
+        let failure = Failure::new("Home", "Connection refused").level(Level::Warning);
+        bar.update(Action::Error(failure.clone())).unwrap();
+        bar.update(Action::Tick).unwrap();
+        assert!(bar.current);
+        bar.update(Action::Error(failure)).unwrap();
+        bar.update(Action::Tick).unwrap();
+        assert!(bar.current);
+        bar.update(Action::Tick).unwrap();
+        assert!(!bar.current);
+        assert_eq!(bar.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a longer message", 8), "a longe…");
+    }
+
+    // Over the right half of the status bar, cut to fit it on the small terminal.
+    #[test]
+    fn test_snapshot() {
+        let mut bar = ErrorBar::new();
+        let failure = Failure::new("Properties", "Connection refused").torrent(4);
+        bar.update(Action::Error(failure)).unwrap();
+        let time = Local.with_ymd_and_hms(2024, 3, 1, 12, 34, 56).unwrap();
+        bar.errors.back_mut().unwrap().0 = time;
+        for size in SIZES {
+            assert_snapshot("error_bar", size, |frame| {
+                bar.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Local, Utc};
+use color_eyre::Result;
+use itertools::Itertools;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Block},
+    Frame,
+};
+
+use crate::{
+    action::{Action, Failure, Level},
+    app::Mode,
+    colors::Colors,
+    history::{aggregate, run_traffic, Bucket, History, Period},
+    utils::{convert_bytes, convert_eta},
+};
+
+use super::{main_layout, Component};
+
+/// Bar charts of the traffic recorded by the session stats footer.
+pub struct HistoryChart {
+    history: History,
+    period: Period,
+    /// Torrent selected in Home, restored when going back.
+    home_id: i64,
+    colors: Colors,
+}
+
+impl HistoryChart {
+    pub fn new(home_id: i64) -> Result<Self> {
+        Ok(Self {
+            history: History::load()?,
+            period: Period::default(),
+            home_id,
+            colors: Colors::new(),
+        })
+    }
+
+    fn chart<'a>(
+        &self,
+        title: &'a str,
+        buckets: &[Bucket],
+        value: fn(&Bucket) -> u64,
+        width: u16,
+    ) -> BarChart<'a> {
+        let count = buckets.len() as u16;
+        let bar_width = (width.saturating_sub(2 + count) / count).max(1);
+        let bars = buckets
+            .iter()
+            .map(|bucket| {
+                let start = DateTime::<Utc>::from_timestamp(bucket.start, 0)
+                    .unwrap_or_default()
+                    .with_timezone(&Local);
+                let label = match self.period {
+                    Period::Hourly => start.format("%H"),
+                    Period::Daily => start.format("%d"),
+                };
+                let text = match bar_width {
+                    8.. => convert_bytes(value(bucket) as i64),
+                    _ => String::new(),
+                };
+                Bar::default()
+                    .value(value(bucket))
+                    .label(Line::from(label.to_string()))
+                    .text_value(text)
+            })
+            .collect_vec();
+        let total = buckets.iter().map(value).sum::<u64>();
+        BarChart::default()
+            .block(
+                Block::bordered()
+                    .border_style(Style::default().fg(self.colors.footer_border_color))
+                    .title(format!("{title} · {}", convert_bytes(total as i64)).bold()),
+            )
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(self.colors.selected_style_fg))
+            .value_style(
+                Style::default()
+                    .fg(self.colors.header_fg)
+                    .bg(self.colors.selected_style_fg),
+            )
+            .style(Style::default().bg(self.colors.buffer_bg))
+    }
+}
+
+impl Component for HistoryChart {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick | Action::Refresh => {
+                if let Err(err) = self.history.reload() {
+                    return Ok(Some(Action::Error(
+                        Failure::new("History", format!("Failed to read history: {err}"))
+                            .level(Level::Warning),
+                    )));
+                }
+            }
+            Action::Back => return Ok(Some(Action::Mode(Mode::Home, self.home_id))),
+            Action::TogglePeriod => self.period = self.period.toggle(),
+            Action::Theme(_) => self.colors = Colors::new(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let now = Local::now();
+        let offset = i64::from(now.offset().local_minus_utc());
+        let buckets = aggregate(self.history.samples(), self.period, now.timestamp(), offset);
+
+        let [title, down, up] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ])
+        .areas(body);
+        let mut heading = format!(" {} traffic (Tab to switch)", self.period);
+        if let Some(run) = run_traffic() {
+            heading.push_str(&format!(
+                " · Since sparrow started {}: ↓ {} ↑ {}",
+                match run.time {
+                    0 => "just now".to_string(),
+                    secs => format!("{} ago", convert_eta(secs)),
+                },
+                convert_bytes(run.downloaded),
+                convert_bytes(run.uploaded),
+            ));
+        }
+        let heading = Line::from(heading)
+            .bold()
+            .fg(self.colors.header_fg)
+            .bg(self.colors.buffer_bg);
+        frame.render_widget(heading, title);
+        frame.render_widget(
+            self.chart("Downloaded", &buckets, |b| b.downloaded, down.width),
+            down,
+        );
+        frame.render_widget(
+            self.chart("Uploaded", &buckets, |b| b.uploaded, up.width),
+            up,
+        );
+        Ok(())
+    }
+}
@@ -1,49 +1,95 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use futures::executor::block_on;
 use itertools::Itertools;
 use ratatui::{
-    prelude::{Constraint, Frame, Layout, Margin, Modifier, Rect, Style, Stylize, Text},
+    prelude::{
+        Constraint, Frame, Layout, Line, Margin, Modifier, Position, Rect, Span, Style, Stylize,
+        Text,
+    },
     widgets::{
-        Cell, HighlightSpacing, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table,
-        TableState,
+        Block, BorderType, Cell, Clear, Gauge, HighlightSpacing, Row, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Table, TableState,
     },
 };
+use strum::IntoEnumIterator;
 use tokio::sync::mpsc::UnboundedSender;
-use transmission_rpc::{
-    types::{self, Id, TorrentAction},
-    TransClient,
-};
+use transmission_rpc::types::{self, Id, TorrentAction, TorrentSetArgs, TorrentStatus};
 use unicode_width::UnicodeWidthStr;
 
-use super::Component;
+use super::{
+    add_torrent::torrent_source, centered_rect, main_layout, page_rows, report, row_at, Component,
+};
 use crate::{
-    action::Action,
+    action::{Action, Failure, Level},
+    animation::{Spinner, Tween},
     app::{self, Mode},
+    batch::Batch,
     colors::Colors,
-    config::Config,
-    data::{self, map_torrent_data},
+    config::{label_color, local_path, mapped_path, Config, PathMapping},
+    data::{
+        self, add_torrent, data_path, edit_labels, map_torrent_data, move_in_queue, rule_labels,
+        set_labels, toggle_label, QueueMove, TorrentCache,
+    },
+    history::record_torrent_rates,
+    metrics::record_cache,
+    opener::{open, primary_file, OnEnter},
+    rpc::Client,
+    store::Store,
+    utils::count,
+    view::{
+        matches_search, status_counts, Column, Sort, SortKey, StatusFilter, View, DEFAULT_COLUMNS,
+    },
 };
 
-const ITEM_HEIGHT: usize = 4;
-const SCROLL_SIZE: usize = 4;
+const SCROLL_DURATION: Duration = Duration::from_millis(120);
 
 pub struct Home {
-    client: Rc<RefCell<TransClient>>,
+    client: Rc<RefCell<Client>>,
     state: TableState,
+    /// Every torrent, before the view and search are applied.
+    torrents: Vec<data::Torrent>,
+    /// The torrents of the previous tick, updated with the active ones.
+    cache: TorrentCache,
+    /// The torrents shown in the table.
     items: Vec<data::Torrent>,
-    longest_item_lens: (u16, u16, u16, u16, u16, u16),
+    columns: Vec<Column>,
+    /// Where the table was last drawn, to find the rows clicked.
+    table_area: Rect,
+    /// Width of the content of each column, for laying out the table.
+    column_lens: Vec<u16>,
     colors: Colors,
     scroll_state: ScrollbarState,
+    scroll_tween: Tween,
+    spinner: Spinner,
+    frame_time: Instant,
+    row_height: u16,
+    zebra: bool,
+    /// Show the exact numbers instead of formatted values.
+    raw: bool,
+    view: View,
+    /// Only show torrents whose name contains this, if not empty.
+    search: String,
+    /// Only show the torrents with this label.
+    label: Option<String>,
+    /// Ids of the torrents marked to be acted on together.
+    marked: Vec<i64>,
+    /// The bulk action being sent a chunk at a time, one per frame.
+    batch: Option<Batch>,
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
 }
 
 impl Home {
-    pub fn new(client: Rc<RefCell<TransClient>>, id: Option<i64>) -> Result<Self> {
-        let data_vec = block_on(map_torrent_data(&client, None))?;
+    pub fn new(client: Rc<RefCell<Client>>, id: Option<i64>) -> Result<Self> {
+        let mut cache = TorrentCache::default();
+        let data_vec = block_on(cache.load(&client))?;
         let index = match id {
             Some(id) => {
                 data_vec
@@ -54,29 +100,47 @@ impl Home {
             None => Some(0),
         };
 
+        let config = Config::default();
+        let item_height = usize::from(config.table.row_height);
+
         Ok(Self {
             client,
             state: TableState::default().with_selected(index),
-            longest_item_lens: constraint_len_calculator(&data_vec),
+            columns: DEFAULT_COLUMNS.to_vec(),
+            table_area: Rect::default(),
+            column_lens: constraint_len_calculator(&data_vec, &DEFAULT_COLUMNS, config.table.raw),
             colors: Colors::new(),
-            scroll_state: ScrollbarState::new((data_vec.len()) * ITEM_HEIGHT),
+            scroll_state: ScrollbarState::new((data_vec.len()) * item_height),
+            scroll_tween: Tween::new((index.unwrap_or(0) * item_height) as f64, SCROLL_DURATION),
+            spinner: Spinner::new(),
+            frame_time: Instant::now(),
+            row_height: config.table.row_height,
+            zebra: config.table.zebra,
+            raw: config.table.raw,
+            view: View::default(),
+            search: String::new(),
+            label: None,
+            marked: Vec::new(),
+            batch: None,
+            torrents: data_vec.clone(),
             items: data_vec,
+            cache,
             command_tx: None,
-            config: Config::default(),
+            config,
         })
     }
 
-    async fn toggle_state(&mut self) -> types::Result<()> {
-        let id = self
+    /// Start the selected torrent or stop it, returning what was done.
+    async fn toggle_state(&mut self) -> types::Result<String> {
+        let torrent = self
             .items
             .get(self.state.selected().ok_or(app::Error::NoRowSelected)?)
-            .ok_or(app::Error::OutOfBound)?
-            .id;
-        let state = self
-            .items
-            .get(self.state.selected().ok_or(app::Error::NoRowSelected)?)
-            .ok_or(app::Error::OutOfBound)?
-            .is_stalled;
+            .ok_or(app::Error::OutOfBound)?;
+        let (id, state) = (torrent.id, torrent.is_stalled);
+        let message = match state {
+            true => format!("Started {}", torrent.name),
+            false => format!("Stopped {}", torrent.name),
+        };
         let mut client = self.client.borrow_mut();
         async move {
             if state {
@@ -90,36 +154,517 @@ impl Home {
             }
         }
         .await?;
-        Ok(())
+        Ok(message)
     }
 
-    async fn start_all(&mut self) -> types::Result<()> {
+    /// Mark every file of the selected torrent as wanted and start it.
+    async fn want_all_files(&mut self) -> types::Result<()> {
+        let torrent = self
+            .items
+            .get(self.state.selected().ok_or(app::Error::NoRowSelected)?)
+            .ok_or(app::Error::OutOfBound)?;
+        let id = torrent.id;
         let mut client = self.client.borrow_mut();
-        let ids = self.items.iter().map(|t| Id::Id(t.id)).collect_vec();
-        async move { client.torrent_action(TorrentAction::Start, ids).await }.await?;
+        async move {
+            // No indices stand for every file, the list doesn't hold the files anyway.
+            let args = TorrentSetArgs {
+                files_wanted: Some(Vec::new()),
+                ..TorrentSetArgs::default()
+            };
+            client.torrent_set(args, Some(vec![Id::Id(id)])).await?;
+            client
+                .torrent_action(TorrentAction::Start, vec![Id::Id(id)])
+                .await
+        }
+        .await?;
         Ok(())
     }
 
-    async fn stop_all(&mut self) -> types::Result<()> {
+    /// Label the unlabeled torrents matching a label rule.
+    fn apply_label_rules(&self) -> Action {
+        let unlabeled = self
+            .torrents
+            .iter()
+            .filter(|t| t.labels.is_empty())
+            .map(|t| (t.id, data_path(&t.location, &t.name)));
+        let groups = rule_labels(&self.config.label_rules, unlabeled);
+        if groups.is_empty() {
+            return Action::Notify(
+                Level::Info,
+                "No unlabeled torrents match the label rules".to_string(),
+            );
+        }
+        let mut labeled = 0;
+        for (label, ids) in groups {
+            let count = ids.len();
+            if let Err(err) = block_on(set_labels(&self.client, ids, vec![label])) {
+                return Action::error(
+                    "Home",
+                    format!("Labeled {labeled} torrents, then failed: {err}"),
+                );
+            }
+            labeled += count;
+        }
+        Action::Notify(Level::Success, format!("Labeled {labeled} torrents"))
+    }
+
+    /// The marked torrents, or the selected one if none is marked.
+    fn targets(&self) -> Vec<&data::Torrent> {
+        match self.marked.is_empty() {
+            true => self.selected().into_iter().collect(),
+            false => self
+                .torrents
+                .iter()
+                .filter(|t| self.marked.contains(&t.id))
+                .collect(),
+        }
+    }
+
+    /// Apply `action` to the marked torrents, or to the selected one if none is marked.
+    async fn act_on_targets(&mut self, action: TorrentAction) -> types::Result<()> {
+        let ids = self.targets().iter().map(|t| t.id).collect_vec();
+        if ids.is_empty() {
+            return Err(app::Error::NoRowSelected.into());
+        }
         let mut client = self.client.borrow_mut();
-        let ids = self.items.iter().map(|t| Id::Id(t.id)).collect_vec();
-        async move { client.torrent_action(TorrentAction::Stop, ids).await }.await?;
+        async move {
+            client
+                .torrent_action(action, ids.into_iter().map(Id::Id).collect())
+                .await
+        }
+        .await?;
         Ok(())
     }
 
-    async fn remove_torrent(&mut self, with_files: bool) -> types::Result<()> {
-        let id = self
+    /// Build the popup listing every action applicable to the selected torrent.
+    fn context_menu(&self) -> Result<Action> {
+        let torrent = self
             .items
             .get(self.state.selected().ok_or(app::Error::NoRowSelected)?)
-            .ok_or(app::Error::OutOfBound)?
-            .id;
+            .ok_or(app::Error::OutOfBound)?;
+        let items = [
+            ("Properties", Action::Mode(Mode::Properties, torrent.id)),
+            ("Start / stop", Action::ToggleState),
+            ("Verify", Action::Verify),
+            ("Reannounce", Action::Reannounce),
+            (
+                "Set location",
+                Action::OpenSetLocation(vec![torrent.id], torrent.location.clone(), true),
+            ),
+            ("Download all files", Action::WantAllFiles),
+            ("Remove", confirm_remove(&[torrent], false, &[])),
+            ("Remove with data", self.confirm_remove_data(&[torrent])?),
+        ]
+        .into_iter()
+        .map(|(label, action)| (label.to_string(), action))
+        .collect();
+        Ok(Action::Menu(torrent.name.clone(), items))
+    }
+
+    /// Build the popup listing the available reports.
+    fn reports_menu(&self) -> Action {
+        let id = self
+            .state
+            .selected()
+            .and_then(|i| self.items.get(i))
+            .map_or(0, |t| t.id);
+        let items = vec![
+            ("Duplicates".to_string(), Action::Mode(Mode::Duplicates, id)),
+            ("History".to_string(), Action::Mode(Mode::History, id)),
+            ("Statistics".to_string(), Action::Mode(Mode::Statistics, id)),
+            ("Trackers".to_string(), Action::Mode(Mode::Trackers, id)),
+            ("Orphaned data".to_string(), Action::Mode(Mode::Orphans, id)),
+        ];
+        Action::Menu("Reports".to_string(), items)
+    }
+
+    /// The sort keys, then the keys breaking their ties, marking the ones in use.
+    fn sort_menu(&self) -> Action {
+        let sort = self.view.sort;
+        let arrow = |descending| if descending { "▼" } else { "▲" };
+        let primary = SortKey::iter().map(|key| {
+            let mark = match key == sort.key {
+                true => format!(" {}", arrow(sort.descending)),
+                false => String::new(),
+            };
+            (format!("Sort by {key}{mark}"), Action::SortBy(key))
+        });
+        let secondary = SortKey::iter().filter(|&key| key != sort.key).map(|key| {
+            let mark = match sort.then {
+                Some((then, descending)) if then == key => format!(" {}", arrow(descending)),
+                _ => String::new(),
+            };
+            (format!("Then by {key}{mark}"), Action::ThenBy(Some(key)))
+        });
+        let items = primary
+            .chain(secondary)
+            .chain([("No tie breaker".to_string(), Action::ThenBy(None))])
+            .collect();
+        Action::Menu("Sort".to_string(), items)
+    }
+
+    /// Put the torrent `id` in the list right away and select it.
+    fn insert_torrent(&mut self, id: i64) -> Result<()> {
+        let added = block_on(map_torrent_data(&self.client, Some(id)))?;
+        self.cache.insert(added.clone());
+        self.torrents.retain(|t| t.id != id);
+        self.torrents.extend(added);
+        self.apply_view()?;
+        if let Some(index) = self.items.iter().position(|t| t.id == id) {
+            self.state.select(Some(index));
+            self.scroll_to(index);
+        }
+        self.send_breadcrumbs()
+    }
+
+    /// Reorder the list, keeping the selected torrent selected.
+    fn sort_by(&mut self, sort: Sort) -> Result<()> {
+        self.view.sort = sort;
+        self.apply_view()
+    }
+
+    fn set_items(&mut self, items: Vec<data::Torrent>) -> Result<()> {
+        self.torrents = items;
+        self.apply_view()
+    }
+
+    /// Show the torrents through the view and search, following the selected torrent if it
+    /// moved.
+    fn apply_view(&mut self) -> Result<()> {
+        let selected = self
+            .state
+            .selected()
+            .and_then(|i| self.items.get(i))
+            .map(|t| t.id);
+        self.items = self
+            .view
+            .apply(self.torrents.clone())
+            .into_iter()
+            .filter(|t| matches_search(&t.name, &self.search))
+            .filter(|t| match &self.label {
+                Some(label) => t.labels.contains(label),
+                None => true,
+            })
+            .collect();
+        let index = match selected.and_then(|id| self.items.iter().position(|t| t.id == id)) {
+            Some(index) => Some(index),
+            None if self.items.is_empty() => None,
+            None => self
+                .state
+                .selected()
+                .map(|i| i.min(self.items.len() - 1))
+                .or(Some(0)),
+        };
+        if index != self.state.selected() {
+            self.state.select(index);
+            self.scroll_to(index.unwrap_or(0));
+        }
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::StatusCounts(
+                self.view.filter,
+                status_counts(&self.torrents),
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn filter_by(&mut self, filter: StatusFilter) -> Result<()> {
+        self.view.filter = filter;
+        self.apply_view()?;
+        self.send_breadcrumbs()
+    }
+
+    fn search(&mut self, query: String) -> Result<()> {
+        self.search = query;
+        self.apply_view()?;
+        self.send_breadcrumbs()
+    }
+
+    fn filter_label(&mut self, label: Option<String>) -> Result<()> {
+        self.label = label;
+        self.apply_view()?;
+        self.send_breadcrumbs()
+    }
+
+    /// Open the folder or the primary file of the finished `torrent`, as `open.on_enter` says.
+    fn open(&self, torrent: &data::Torrent) -> Action {
+        let remote = match self.config.open.on_enter {
+            OnEnter::File => {
+                let files = block_on(map_torrent_data(&self.client, Some(torrent.id)))
+                    .map(|mut torrents| torrents.pop().map(|t| t.files).unwrap_or_default());
+                match files {
+                    Ok(files) => match primary_file(&files) {
+                        Some(file) => data_path(&torrent.location, &file.name),
+                        None => return Action::Notify(Level::Warning, "No file to open".into()),
+                    },
+                    Err(err) => return Action::error("Home", err),
+                }
+            }
+            _ => data_path(&torrent.location, &torrent.name),
+        };
+        let mut path = local_path(&self.config.path_mappings, &remote);
+        if self.config.open.on_enter == OnEnter::Folder && !path.is_dir() {
+            // A torrent of a single file has no folder of its own.
+            path = local_path(&self.config.path_mappings, &torrent.location);
+        }
+        if !path.exists() {
+            return Action::Notify(
+                Level::Warning,
+                format!("{} isn't reachable, see path_mappings", path.display()),
+            );
+        }
+        match open(&path, self.config.open.command.as_deref()) {
+            Ok(()) => Action::Notify(Level::Info, format!("Opened {}", path.display())),
+            Err(err) => Action::error("Home", err),
+        }
+    }
+
+    /// Toggle the favorite label on the marked torrents, or on the selected one.
+    fn toggle_favorite(&self) -> Result<Option<Action>> {
+        let Some(label) = self.config.favorite_label.as_deref() else {
+            return Ok(Some(no_favorite()));
+        };
+        let targets = self.targets();
+        if targets.is_empty() {
+            return Ok(None);
+        }
+        let labels = targets.iter().map(|t| t.labels.clone()).collect_vec();
+        let (added, labels) = toggle_label(&labels, label);
+        // Torrents keep their other labels, each needs a call of its own.
+        let result = targets
+            .iter()
+            .zip(labels)
+            .try_for_each(|(torrent, labels)| {
+                block_on(set_labels(&self.client, vec![torrent.id], labels))
+            });
+        let torrents = count(targets.len(), "torrent");
+        let message = match added {
+            true => format!("Added {label} to {torrents}"),
+            false => format!("Removed {label} from {torrents}"),
+        };
+        report(self.command_tx.as_ref(), "Home", result, message)
+    }
+
+    /// Apply the labels added to and removed from the ones the torrents with `ids` share,
+    /// keeping the labels only some of them have.
+    fn set_labels(&self, ids: &[i64], edited: &[String]) -> Result<Option<Action>> {
+        let targets = self
+            .torrents
+            .iter()
+            .filter(|t| ids.contains(&t.id))
+            .collect_vec();
+        let labels = targets.iter().map(|t| t.labels.clone()).collect_vec();
+        // Torrents keep their own labels, each needs a call of its own.
+        let result = targets
+            .iter()
+            .zip(edit_labels(&labels, edited))
+            .try_for_each(|(torrent, labels)| {
+                block_on(set_labels(&self.client, vec![torrent.id], labels))
+            });
+        let message = format!("Labels set on {}", count(targets.len(), "torrent"));
+        report(self.command_tx.as_ref(), "Home", result, message)
+    }
+
+    /// Build the popup listing the labels to filter by, with how many torrents have each.
+    fn label_menu(&self) -> Action {
+        let counts = self.torrents.iter().flat_map(|t| &t.labels).counts();
+        let items = std::iter::once(("All labels".to_string(), Action::FilterLabel(None)))
+            .chain(counts.into_iter().sorted().map(|(label, count)| {
+                (
+                    format!("{label} ({count})"),
+                    Action::FilterLabel(Some(label.clone())),
+                )
+            }))
+            .collect();
+        Action::Menu("Filter by label".to_string(), items)
+    }
+
+    /// Ask before moving torrents `ids` onto files already in `location`.
+    fn check_location(&self, ids: &[i64], location: &str) -> Result<Option<Action>> {
+        // The list leaves the files out.
+        let all = block_on(map_torrent_data(&self.client, None))?;
+        let torrents = all.iter().filter(|t| ids.contains(&t.id)).collect_vec();
+        let local = self.client.borrow().is_local();
+        let mappings = &self.config.path_mappings;
+        Ok(confirm_location(&torrents, &all, location, mappings, local))
+    }
+
+    /// Ask before deleting the data of `targets`, warning about other torrents seeding it too.
+    fn confirm_remove_data(&self, targets: &[&data::Torrent]) -> Result<Action> {
+        // The list leaves the files out.
+        let all = block_on(map_torrent_data(&self.client, None))?;
+        let ids = targets.iter().map(|t| t.id).collect_vec();
+        let targets = all.iter().filter(|t| ids.contains(&t.id)).collect_vec();
+        Ok(confirm_remove(
+            &targets,
+            true,
+            &data::sharing_data(&targets, &all),
+        ))
+    }
+
+    /// Start the marked torrents if they are all stopped, or stop them.
+    fn toggle_marked(&mut self) -> Option<Action> {
+        let targets = self.targets();
+        let stopped = targets.iter().all(|t| t.status == TorrentStatus::Stopped);
+        let ids = targets.iter().map(|t| Id::Id(t.id)).collect_vec();
+        self.start_batch(match stopped {
+            true => Batch::new(TorrentAction::Start, ("Starting", "Started"), ids),
+            false => Batch::new(TorrentAction::Stop, ("Stopping", "Stopped"), ids),
+        })
+    }
+
+    /// Start every torrent, including the ones the view or search hides.
+    fn start_all(&mut self) -> Option<Action> {
+        let ids = self.torrents.iter().map(|t| Id::Id(t.id)).collect_vec();
+        self.start_batch(Batch::new(
+            TorrentAction::Start,
+            ("Starting", "Started"),
+            ids,
+        ))
+    }
+
+    /// Start every stopped torrent that isn't complete, hidden or not.
+    fn resume_incomplete(&mut self) -> Option<Action> {
+        let ids = self
+            .torrents
+            .iter()
+            .filter(|t| t.status == TorrentStatus::Stopped && t.left_bytes > 0)
+            .map(|t| Id::Id(t.id))
+            .collect_vec();
+        if ids.is_empty() {
+            return Some(Action::Notify(
+                Level::Info,
+                "No stopped torrent left to download".to_string(),
+            ));
+        }
+        self.start_batch(Batch::new(
+            TorrentAction::Start,
+            ("Resuming", "Resumed"),
+            ids,
+        ))
+    }
+
+    /// Stop every torrent, including the ones the view or search hides.
+    fn stop_all(&mut self) -> Option<Action> {
+        let ids = self.torrents.iter().map(|t| Id::Id(t.id)).collect_vec();
+        self.start_batch(Batch::new(
+            TorrentAction::Stop,
+            ("Stopping", "Stopped"),
+            ids,
+        ))
+    }
+
+    /// Stop every active torrent and remember them, or start the remembered ones again.
+    fn toggle_pause_all(&mut self) -> Result<Option<Action>> {
+        let paused = Store::open()?.paused(&self.client.borrow().server())?;
+        let batch = match paused.is_empty() {
+            true => {
+                let active = self
+                    .torrents
+                    .iter()
+                    .filter(|t| t.status != TorrentStatus::Stopped)
+                    .map(|t| Id::Hash(t.hash.clone()))
+                    .collect_vec();
+                Batch::new(TorrentAction::Stop, ("Pausing", "Paused"), active)
+            }
+            false => {
+                let paused = paused.into_iter().map(Id::Hash).collect_vec();
+                Batch::new(TorrentAction::Start, ("Resuming", "Resumed"), paused)
+            }
+        };
+        Ok(self.start_batch(batch.remember()))
+    }
+
+    /// Work through `batch` from the next frame on, unless another one is still running.
+    fn start_batch(&mut self, batch: Batch) -> Option<Action> {
+        if let Some(running) = &self.batch {
+            return Some(Action::Notify(
+                Level::Warning,
+                format!("{} torrents, try again when done", running.label),
+            ));
+        }
+        self.batch = Some(batch);
+        None
+    }
+
+    /// Send the next chunk of the running batch, and report on the batch once it's done.
+    fn continue_batch(&mut self) -> Result<Option<Action>> {
+        let Some(batch) = self.batch.as_mut() else {
+            return Ok(None);
+        };
+        if let Some(chunk) = batch.next_chunk() {
+            let (action, ids) = (batch.action, chunk.clone());
+            let res = {
+                let mut client = self.client.borrow_mut();
+                async move { client.torrent_action(action, ids).await }
+            };
+            let result = block_on(res).map(|_| ()).map_err(|err| err.to_string());
+            batch.finish_chunk(chunk, result);
+        }
+        if !batch.is_done() {
+            return Ok(None);
+        }
+        let Some(batch) = self.batch.take() else {
+            return Ok(None);
+        };
+        if batch.remember {
+            // Torrents that failed to resume stay remembered, to be resumed next time.
+            let remembered = match batch.action {
+                TorrentAction::Stop => &batch.succeeded,
+                _ => &batch.failed,
+            };
+            let hashes = remembered
+                .iter()
+                .filter_map(|id| match id {
+                    Id::Hash(hash) => Some(hash.clone()),
+                    Id::Id(_) => None,
+                })
+                .collect_vec();
+            Store::open()?.set_paused(&self.client.borrow().server(), &hashes)?;
+        }
+        Ok(Some(batch.summary()))
+    }
+
+    async fn remove_torrents(&mut self, ids: Vec<i64>, with_files: bool) -> types::Result<()> {
+        self.marked.retain(|id| !ids.contains(id));
+        let ids = ids.into_iter().map(Id::Id).collect_vec();
         let mut client = self.client.borrow_mut();
-        async move { client.torrent_remove(vec![Id::Id(id)], with_files).await }.await?;
+        async move { client.torrent_remove(ids, with_files).await }.await?;
 
         Ok(())
     }
 
+    fn selected(&self) -> Option<&data::Torrent> {
+        self.items.get(self.state.selected()?)
+    }
+
+    /// Report the selected torrent to the status bar, and to the properties beside the list.
+    fn send_breadcrumbs(&self) -> Result<()> {
+        let mut crumbs = self
+            .state
+            .selected()
+            .and_then(|i| self.items.get(i))
+            .map(|t| vec![t.name.clone()])
+            .unwrap_or_default();
+        if let Some(label) = &self.label {
+            crumbs.push(format!("label: {label}"));
+        }
+        if !self.marked.is_empty() {
+            crumbs.push(format!("{} marked", self.marked.len()));
+        }
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Breadcrumbs(crumbs))?;
+            if let Some(torrent) = self.selected() {
+                tx.send(Action::Highlight(torrent.id))?;
+            }
+        }
+        Ok(())
+    }
+
     fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -131,10 +676,13 @@ impl Home {
             None => 0,
         };
         self.state.select(Some(i));
-        self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+        self.scroll_to(i);
     }
 
     fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -146,36 +694,126 @@ impl Home {
             None => 0,
         };
         self.state.select(Some(i));
-        self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+        self.scroll_to(i);
     }
 
     fn top(&mut self) {
         self.state.select_first();
-        self.scroll_state.first();
+        self.scroll_to(0);
     }
 
     fn bottom(&mut self) {
         self.state.select_last();
-        self.scroll_state.last();
+        self.scroll_to(self.items.len().saturating_sub(1));
     }
 
     fn scroll_up(&mut self, amount: usize) {
         self.state.scroll_up_by(amount as u16);
-        self.scroll_state = self
-            .scroll_state
-            .position(self.state.selected().unwrap_or(0) * ITEM_HEIGHT);
+        self.scroll_to(self.state.selected().unwrap_or(0));
     }
 
     fn scroll_down(&mut self, amount: usize) {
         self.state.scroll_down_by(amount as u16);
-        self.scroll_state = self
-            .scroll_state
-            .position(self.state.selected().unwrap_or(0) * ITEM_HEIGHT);
+        self.scroll_to(self.state.selected().unwrap_or(0));
+    }
+
+    /// Ease the scrollbar towards the row at `index`.
+    fn scroll_to(&mut self, index: usize) {
+        self.scroll_tween
+            .retarget((index * self.item_height()) as f64, Instant::now());
+    }
+
+    /// Torrents the table showed at the last draw, below its header.
+    fn rows(&self) -> usize {
+        usize::from(self.table_area.height.saturating_sub(1) / self.row_height.max(1))
+    }
+
+    fn item_height(&self) -> usize {
+        usize::from(self.row_height)
+    }
+
+    /// Cycle the row height between 4, 2 and 1 lines.
+    fn cycle_density(&mut self) {
+        self.row_height = match self.row_height {
+            4 => 2,
+            2 => 1,
+            _ => 4,
+        };
+        self.scroll_tween.retarget(
+            (self.state.selected().unwrap_or(0) * self.item_height()) as f64,
+            Instant::now(),
+        );
+    }
+
+    /// Show `columns` in the table, the default ones if empty.
+    fn set_columns(&mut self, columns: Vec<Column>) {
+        self.columns = match columns.is_empty() {
+            true => DEFAULT_COLUMNS.to_vec(),
+            false => columns.into_iter().unique().collect(),
+        };
+        self.column_lens = constraint_len_calculator(&self.torrents, &self.columns, self.raw);
+    }
+
+    /// The cells of a row, showing fewer details the shorter the row is. The first column
+    /// holds the mark and the group.
+    ///
+    /// `group` is the label of the row's group and `group_start` whether the row opens it.
+    fn row_cells(
+        &self,
+        data: &data::Torrent,
+        group: Option<&str>,
+        group_start: bool,
+    ) -> Vec<String> {
+        let mark = match self.marked.contains(&data.id) {
+            true => "● ",
+            false => "",
+        };
+        let verifying = matches!(
+            data.status,
+            TorrentStatus::Verifying | TorrentStatus::QueuedToVerify
+        );
+        let mut item = self
+            .columns
+            .iter()
+            .map(|&column| {
+                let content = column.content(data, self.raw);
+                match column {
+                    Column::Name if self.row_height == 1 => {
+                        content.lines().next().unwrap_or_default().to_string()
+                    }
+                    Column::Done if verifying => {
+                        format!("{} {content}", self.spinner.frame(self.frame_time))
+                    }
+                    _ => content,
+                }
+            })
+            .collect_vec();
+        item[0] = format!("{mark}{}", item[0]);
+        match self.row_height {
+            1 | 2 => {
+                if let Some(group) = group {
+                    item[0] = format!("[{group}] {}", item[0]);
+                }
+                item
+            }
+            _ => {
+                // The padding line above the name holds the group label.
+                let mut item = item
+                    .into_iter()
+                    .map(|content| format!("\n{content}\n"))
+                    .collect_vec();
+                if let (Some(group), true) = (group, group_start) {
+                    item[0] = format!("▸ {group}{}", item[0]);
+                }
+                item
+            }
+        }
     }
 }
 
 impl Home {
     fn render_table(&mut self, frame: &mut Frame, area: Rect) {
+        self.table_area = area;
         let header_style = Style::default()
             .fg(self.colors.header_fg)
             .bg(self.colors.header_bg);
@@ -183,49 +821,160 @@ impl Home {
             .add_modifier(Modifier::REVERSED)
             .fg(self.colors.selected_style_fg);
 
-        let header = ["NAME", "DONE", "ETA", "DOWN", "UP", "RATIO"]
+        let sort = self.view.sort;
+        let arrow = if sort.descending { "▼" } else { "▲" };
+        // Hollow for the key breaking the ties.
+        let then_arrow = match sort.then {
+            Some((_, true)) => "▽",
+            _ => "△",
+        };
+        // The queue position is worth a column of its own while the table is in queue order.
+        let queue = sort.key == SortKey::Queue && !self.columns.contains(&Column::Queue);
+        let added_hidden = !self.columns.contains(&Column::Added);
+        let queue_header = queue.then(|| format!("# {arrow}"));
+        let header = queue_header
             .into_iter()
+            .chain(self.columns.iter().map(|&column| {
+                let title = column.title();
+                match column.sort_key() {
+                    Some(key) if key == sort.key => format!("{title} {arrow}"),
+                    Some(SortKey::Name) if sort.key == SortKey::Added && added_hidden => {
+                        format!("{title} · ADDED {arrow}")
+                    }
+                    Some(key) if sort.then.map(|(then, _)| then) == Some(key) => {
+                        format!("{title} {then_arrow}")
+                    }
+                    _ => title.to_string(),
+                }
+            }))
             .map(Cell::from)
             .collect::<Row>()
             .style(header_style)
             .height(1);
+        let groups = self
+            .items
+            .iter()
+            .map(|t| self.view.group.key(t))
+            .collect_vec();
         let rows = self.items.iter().enumerate().map(|(i, data)| {
             let color = match i % 2 {
-                0 => self.colors.normal_row_color,
-                _ => self.colors.alt_row_color,
+                1 if self.zebra => self.colors.alt_row_color,
+                _ => self.colors.normal_row_color,
+            };
+            let group_start = i == 0 || groups[i] != groups[i - 1];
+            let style = match self.marked.contains(&data.id) {
+                true => Style::new()
+                    .fg(self.colors.selected_style_fg)
+                    .add_modifier(Modifier::BOLD),
+                false => Style::new().fg(self.colors.row_fg),
             };
-            let item = data.ref_array();
-            item.into_iter()
-                .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
+            let position = queue.then(|| match self.row_height {
+                1 | 2 => (data.queue_position + 1).to_string(),
+                _ => format!("\n{}\n", data.queue_position + 1),
+            });
+            // Labels with a color tint the name, and the labels if shown.
+            let tint = label_color(&self.config.label_colors, &data.labels);
+            let cells = self
+                .row_cells(data, groups[i].as_deref(), group_start)
+                .into_iter()
+                .zip(&self.columns)
+                .map(|(content, column)| {
+                    let cell = Cell::from(Text::from(content));
+                    match tint {
+                        Some(color) if matches!(column, Column::Name | Column::Label) => {
+                            cell.fg(color)
+                        }
+                        _ => cell,
+                    }
+                });
+            position
+                .into_iter()
+                .map(|content| Cell::from(Text::from(content)))
+                .chain(cells)
                 .collect::<Row>()
-                .style(Style::new().fg(self.colors.row_fg).bg(color))
-                .height(4)
+                .style(style.bg(color))
+                .height(self.row_height)
         });
         let bar = " █ ";
+        let highlight_symbol = match self.row_height {
+            1 => Text::from(bar),
+            2 => Text::from(vec![bar.into(), bar.into()]),
+            _ => Text::from(vec!["".into(), bar.into(), bar.into(), "".into()]),
+        };
+        let position_len = self
+            .items
+            .iter()
+            .map(|t| (t.queue_position + 1).to_string().len())
+            .max()
+            .unwrap_or(0);
+        #[allow(clippy::cast_possible_truncation)]
+        let queue_column = queue.then(|| Constraint::Length(position_len.max(3) as u16 + 1));
         let t = Table::new(
             rows,
-            [
-                Constraint::Length(self.longest_item_lens.0 + 1),
-                Constraint::Min(self.longest_item_lens.1 + 1),
-                Constraint::Min(self.longest_item_lens.2 + 1),
-                Constraint::Min(self.longest_item_lens.3 + 1),
-                Constraint::Min(self.longest_item_lens.4 + 1),
-                Constraint::Min(self.longest_item_lens.5 + 1),
-            ],
+            queue_column
+                .into_iter()
+                .chain(self.columns.iter().zip(&self.column_lens).map(
+                    |(&column, &len)| match column {
+                        Column::Name => Constraint::Length(len + 1),
+                        _ => Constraint::Min(len + 1),
+                    },
+                )),
         )
         .header(header)
         .highlight_style(selected_style)
-        .highlight_symbol(Text::from(vec![
-            "".into(),
-            bar.into(),
-            bar.into(),
-            "".into(),
-        ]))
+        .highlight_symbol(highlight_symbol)
         .bg(self.colors.buffer_bg)
         .highlight_spacing(HighlightSpacing::Always);
         frame.render_stateful_widget(t, area, &mut self.state);
     }
 
+    /// Show the active search and how many torrents it matches.
+    fn render_search(&self, frame: &mut Frame, area: Rect) {
+        let line = Line::from(vec![
+            Span::from(format!("/{}", self.search)).bold(),
+            Span::from(format!(
+                "  {} of {} · Esc: clear",
+                self.items.len(),
+                self.torrents.len()
+            ))
+            .fg(self.colors.footer_border_color),
+        ]);
+        frame.render_widget(
+            line.style(
+                Style::default()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            ),
+            area,
+        );
+    }
+
+    /// Show the progress of a batch that takes more than one call.
+    fn render_batch(&self, frame: &mut Frame, area: Rect) {
+        let Some(batch) = self.batch.as_ref().filter(|b| b.is_large()) else {
+            return;
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = batch.done() as f64 / batch.total as f64;
+        let gauge = Gauge::default()
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(self.colors.footer_border_color))
+                    .title(format!("{} torrents", batch.label).bold()),
+            )
+            .gauge_style(
+                Style::default()
+                    .fg(self.colors.selected_style_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .label(format!("{} / {}", batch.done(), batch.total))
+            .ratio(ratio.clamp(0.0, 1.0));
+        let rect = centered_rect(area, 40, 3);
+        frame.render_widget(Clear, rect);
+        frame.render_widget(gauge, rect);
+    }
+
     fn render_scrollbar(&mut self, frame: &mut Frame, area: Rect) {
         frame.render_stateful_widget(
             Scrollbar::default()
@@ -243,116 +992,443 @@ impl Home {
 impl Component for Home {
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
         self.command_tx = Some(tx);
-        Ok(())
+        self.send_breadcrumbs()
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.row_height = match config.table.row_height {
+            0 | 1 => 1,
+            2 | 3 => 2,
+            _ => 4,
+        };
+        self.zebra = config.table.zebra;
+        self.raw = config.table.raw;
+        self.set_columns(config.table.columns.clone());
+        self.view = config.startup.view();
+        self.apply_view()?;
         self.config = config;
+        self.scroll_to(self.state.selected().unwrap_or(0));
         Ok(())
     }
 
-    fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<Option<Action>> {
-        match key_event.code {
-            KeyCode::Char('q') => {
-                return Ok(Some(Action::Quit));
-            }
-            KeyCode::Char('Q') => {
-                match block_on(close_session(&self.client)) {
-                    Ok(status) => {
-                        if status {
-                            return Ok(Some(Action::Quit));
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick | Action::Refresh => {
+                let items = match action {
+                    Action::Tick => block_on(self.cache.update(&self.client)),
+                    _ => block_on(self.cache.load(&self.client)),
+                };
+                match items {
+                    Ok(items) => {
+                        if action == Action::Tick {
+                            record_torrent_rates(&items, true);
+                            let (torrents, bytes) = self.cache.footprint();
+                            record_cache(torrents, bytes);
                         }
+                        self.set_items(items)?;
+                    }
+                    Err(err) => {
+                        let failure = Failure::new("Home", err).level(Level::Warning);
+                        return Ok(Some(Action::Error(failure)));
                     }
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
                 };
             }
-            KeyCode::Char('l') | KeyCode::Enter => {
-                let id = self
-                    .items
-                    .get(self.state.selected().ok_or(app::Error::NoRowSelected)?)
-                    .ok_or(app::Error::OutOfBound)?
-                    .id;
-                return Ok(Some(Action::Mode(Mode::Properties, id)));
+            Action::Select if !self.marked.is_empty() => {
+                return Ok(Some(Action::OpenSelection(self.marked.clone())));
+            }
+            Action::Select => {
+                if let Some(torrent) = self.selected() {
+                    return Ok(Some(match self.config.open.on_enter {
+                        OnEnter::Folder | OnEnter::File if torrent.percent >= 1.0 => {
+                            self.open(torrent)
+                        }
+                        _ => Action::Mode(Mode::Properties, torrent.id),
+                    }));
+                }
             }
-            KeyCode::Char('j') | KeyCode::Down => {
+            Action::ToggleMark => {
+                if let Some(id) = self.selected().map(|t| t.id) {
+                    match self.marked.iter().position(|&m| m == id) {
+                        Some(i) => _ = self.marked.remove(i),
+                        None => self.marked.push(id),
+                    }
+                    if self.state.selected() < Some(self.items.len().saturating_sub(1)) {
+                        self.next();
+                    }
+                    self.send_breadcrumbs()?;
+                }
+            }
+            Action::Down => {
                 self.next();
+                self.send_breadcrumbs()?;
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            Action::Up => {
                 self.previous();
+                self.send_breadcrumbs()?;
             }
-            KeyCode::Char('g') => {
+            Action::Top => {
                 self.top();
+                self.send_breadcrumbs()?;
             }
-            KeyCode::Char('G') => {
+            Action::Bottom => {
                 self.bottom();
+                self.send_breadcrumbs()?;
             }
-            KeyCode::Char('u') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.scroll_up(SCROLL_SIZE);
+            Action::PageUp | Action::HalfPageUp => {
+                self.scroll_up(page_rows(self.rows(), action == Action::HalfPageUp));
+                self.send_breadcrumbs()?;
             }
-            KeyCode::Char('d') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.scroll_down(SCROLL_SIZE);
+            Action::PageDown | Action::HalfPageDown => {
+                self.scroll_down(page_rows(self.rows(), action == Action::HalfPageDown));
+                self.send_breadcrumbs()?;
             }
-            KeyCode::Char('p') => {
-                match block_on(self.toggle_state()) {
-                    Ok(()) => {}
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
-                };
+            Action::Back if !self.marked.is_empty() => {
+                self.marked.clear();
+                self.send_breadcrumbs()?;
             }
-            KeyCode::Char('s') => {
-                match block_on(self.start_all()) {
-                    Ok(()) => {}
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
-                };
+            Action::Back if !self.search.is_empty() => self.search(String::new())?,
+            Action::Back if self.label.is_some() => self.filter_label(None)?,
+            Action::RemoveSelected => {
+                let targets = self.targets();
+                return Ok((!targets.is_empty()).then(|| confirm_remove(&targets, false, &[])));
             }
-            KeyCode::Char('S') => {
-                match block_on(self.stop_all()) {
-                    Ok(()) => {}
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
+            Action::RemoveSelectedWithData => {
+                let targets = self.targets();
+                if targets.is_empty() {
+                    return Ok(None);
+                }
+                return match self.confirm_remove_data(&targets) {
+                    Ok(action) => Ok(Some(action)),
+                    Err(err) => Ok(Some(Action::error("Home", err))),
                 };
             }
-            KeyCode::Char('d') => {
-                match block_on(self.remove_torrent(false)) {
-                    Ok(()) => {}
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
-                };
+            Action::MoveSelected => {
+                let targets = self.targets();
+                return Ok(targets.first().map(|t| {
+                    let ids = targets.iter().map(|t| t.id).collect();
+                    Action::OpenSetLocation(ids, t.location.clone(), true)
+                }));
+            }
+            Action::EditSpeedLimits => {
+                return Ok(Some(Action::OpenSpeedLimits(
+                    self.selected().map(|t| t.id).into_iter().collect(),
+                )));
+            }
+            Action::EditSeedLimits => {
+                let ids = self.targets().iter().map(|t| t.id).collect_vec();
+                return Ok((!ids.is_empty()).then_some(Action::OpenSeedLimits(ids)));
             }
-            KeyCode::Char('D') => {
-                match block_on(self.remove_torrent(true)) {
-                    Ok(()) => {}
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
+            Action::OpenActionMenu => return Ok(Some(self.context_menu()?)),
+            Action::OpenReportsMenu => return Ok(Some(self.reports_menu())),
+            Action::OpenSessionSettings => {
+                let id = self.selected().map_or(0, |t| t.id);
+                return Ok(Some(Action::Mode(Mode::Session, id)));
+            }
+            Action::OpenStatistics => {
+                let id = self.selected().map_or(0, |t| t.id);
+                return Ok(Some(Action::Mode(Mode::Statistics, id)));
+            }
+            Action::Find => return Ok(Some(Action::OpenSearch(self.search.clone()))),
+            Action::NextSort => {
+                let sort = self.view.sort;
+                self.sort_by(Sort {
+                    key: sort.key.next(),
+                    ..sort
+                })?;
+            }
+            Action::ReverseSort => {
+                let sort = self.view.sort;
+                self.sort_by(sort.toggle(sort.key))?;
+            }
+            Action::SortBy(key) => self.sort_by(self.view.sort.toggle(key))?,
+            Action::ThenBy(key) => self.sort_by(self.view.sort.then_by(key))?,
+            Action::OpenSortMenu => return Ok(Some(self.sort_menu())),
+            Action::NextFilter => self.filter_by(self.view.filter.next())?,
+            Action::PreviousFilter => self.filter_by(self.view.filter.previous())?,
+            Action::Filter(filter) => self.filter_by(filter)?,
+            Action::ToggleZebra => self.zebra = !self.zebra,
+            Action::ToggleRaw => {
+                self.raw = !self.raw;
+                self.column_lens =
+                    constraint_len_calculator(&self.torrents, &self.columns, self.raw);
+            }
+            Action::Theme(_) => self.colors = Colors::new(),
+            Action::CycleDensity => self.cycle_density(),
+            Action::ChooseColumns => return Ok(Some(Action::OpenColumns(self.columns.clone()))),
+            Action::SetColumns(columns) => self.set_columns(columns),
+            Action::ToggleState if !self.marked.is_empty() => return Ok(self.toggle_marked()),
+            Action::ToggleState => {
+                return Ok(Some(match block_on(self.toggle_state()) {
+                    Ok(message) => Action::Notify(Level::Success, message),
+                    Err(err) => Action::error("Home", err),
+                }));
+            }
+            Action::WantAllFiles => {
+                let result = block_on(self.want_all_files());
+                return report(
+                    self.command_tx.as_ref(),
+                    "Home",
+                    result,
+                    "Downloading every file",
+                );
+            }
+            Action::ApplyLabelRules => return Ok(Some(self.apply_label_rules())),
+            Action::OpenLabelFilter => return Ok(Some(self.label_menu())),
+            Action::FilterLabel(label) => self.filter_label(label)?,
+            Action::ToggleFavorite => return self.toggle_favorite(),
+            Action::FilterFavorite => match self.config.favorite_label.clone() {
+                Some(label) if self.label.as_ref() != Some(&label) => {
+                    self.filter_label(Some(label))?;
+                }
+                Some(_) => self.filter_label(None)?,
+                None => return Ok(Some(no_favorite())),
+            },
+            Action::EditLabels => {
+                let targets = self.targets();
+                if !targets.is_empty() {
+                    let ids = targets.iter().map(|t| t.id).collect();
+                    let labels =
+                        data::shared(&targets.iter().map(|t| t.labels.clone()).collect_vec());
+                    return Ok(Some(Action::OpenLabels(ids, labels)));
+                }
+            }
+            Action::SetLabels(ids, labels) => return self.set_labels(&ids, &labels),
+            Action::MoveInQueue(to) => {
+                if let Some(id) = self.selected().map(|t| t.id) {
+                    let message = match to {
+                        QueueMove::Up | QueueMove::Down => format!("Moved {to} in the queue"),
+                        QueueMove::Top | QueueMove::Bottom => {
+                            format!("Moved to the {to} of the queue")
+                        }
+                    };
+                    let result = block_on(move_in_queue(&self.client, vec![id], to));
+                    return report(self.command_tx.as_ref(), "Home", result, message);
+                }
+            }
+            Action::StartAll => return Ok(self.start_all()),
+            Action::ResumeIncomplete => return Ok(self.resume_incomplete()),
+            Action::StopAll => return Ok(self.stop_all()),
+            Action::TogglePauseAll => {
+                return Ok(match self.toggle_pause_all() {
+                    Ok(action) => action,
+                    Err(err) => Some(Action::error("Home", err)),
+                });
+            }
+            Action::Verify | Action::Reannounce => {
+                let targets = count(self.targets().len(), "torrent");
+                let (torrent_action, message) = match action {
+                    Action::Verify => (TorrentAction::Verify, format!("Verifying {targets}")),
+                    _ => (TorrentAction::Reannounce, format!("Reannounced {targets}")),
                 };
+                // Refresh right away so that the table shows the torrents verifying.
+                let result = block_on(self.act_on_targets(torrent_action));
+                return report(self.command_tx.as_ref(), "Home", result, message);
+            }
+            Action::Remove(ids) => {
+                let message = format!("Removed {}", count(ids.len(), "torrent"));
+                let result = block_on(self.remove_torrents(ids, false));
+                return report(self.command_tx.as_ref(), "Home", result, message);
+            }
+            Action::RemoveWithData(ids) => {
+                let message = format!("Removed {} with its data", count(ids.len(), "torrent"));
+                let result = block_on(self.remove_torrents(ids, true));
+                return report(self.command_tx.as_ref(), "Home", result, message);
+            }
+            Action::AddTorrent(source, location, paused) => {
+                let sites = &self.config.sites;
+                match block_on(add_torrent(&self.client, sites, source, location, paused)) {
+                    Ok((id, duplicate)) => {
+                        self.insert_torrent(id)?;
+                        return Ok(Some(match duplicate {
+                            true => {
+                                Action::Notify(Level::Info, "Torrent already added".to_string())
+                            }
+                            false => Action::Notify(Level::Success, "Torrent added".to_string()),
+                        }));
+                    }
+                    Err(err) => return Ok(Some(Action::error("Home", err))),
+                }
+            }
+            Action::Search(query) => self.search(query)?,
+            Action::SetLocation(ids, location, force) => {
+                if !force {
+                    if let Some(action) = self.check_location(&ids, &location)? {
+                        return Ok(Some(action));
+                    }
+                }
+                let message = format!("Moved {} to {location}", count(ids.len(), "torrent"));
+                let result = block_on(set_location(&self.client, ids, location, true));
+                return report(self.command_tx.as_ref(), "Home", result, message);
+            }
+            Action::Render => {
+                self.frame_time = Instant::now();
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let position = self.scroll_tween.value(self.frame_time).round() as usize;
+                self.scroll_state = self
+                    .scroll_state
+                    .content_length(self.items.len() * self.item_height())
+                    .position(position);
+                if self.batch.is_some() {
+                    return Ok(self
+                        .continue_batch()
+                        .unwrap_or_else(|err| Some(Action::error("Home", err))));
+                }
             }
-            // Other handlers you could add here.
             _ => {}
         }
         Ok(None)
     }
 
-    fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        match action {
-            Action::Tick => {
-                self.items = match block_on(map_torrent_data(&self.client, None)) {
-                    Ok(items) => items,
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Right) => return Ok(Some(self.context_menu()?)),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position::new(mouse.column, mouse.row);
+                let offset = self.state.offset();
+                let Some(index) = row_at(self.table_area, 1, self.row_height, offset, position)
+                    .filter(|&i| i < self.items.len())
+                else {
+                    return Ok(None);
                 };
+                // Clicking the selected row again opens it.
+                if self.state.selected() == Some(index) {
+                    return Ok(Some(Action::Select));
+                }
+                self.state.select(Some(index));
+                self.scroll_to(index);
             }
-            Action::Render => {}
-            _ => {}
+            MouseEventKind::ScrollDown => self.scroll_down(1),
+            MouseEventKind::ScrollUp => self.scroll_up(1),
+            _ => return Ok(None),
         }
+        self.send_breadcrumbs()?;
         Ok(None)
     }
 
+    fn handle_paste_event(&mut self, text: String) -> Result<Option<Action>> {
+        Ok(Some(match torrent_source(&text) {
+            Some(source) => Action::OpenAddTorrentWith(source),
+            None => Action::Notify(
+                Level::Warning,
+                "Paste a magnet link or .torrent file to add it".to_string(),
+            ),
+        }))
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(3)]);
-        let rects = vertical.split(area);
+        let [_, body, _] = main_layout(area);
+        let search_height = u16::from(!self.search.is_empty());
+        let [table, search] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(search_height)]).areas(body);
 
-        self.render_table(frame, rects[0]);
-        self.render_scrollbar(frame, rects[0]);
+        self.render_table(frame, table);
+        self.render_scrollbar(frame, table);
+        self.render_search(frame, search);
+        self.render_batch(frame, body);
         Ok(())
     }
 }
 
-pub async fn close_session(client: &Rc<RefCell<TransClient>>) -> Result<bool, app::Error> {
+fn no_favorite() -> Action {
+    Action::Notify(
+        Level::Warning,
+        "No favorite_label in the config".to_string(),
+    )
+}
+
+/// Ask before removing `targets`, and their data if `with_data`, warning about the torrents
+/// `sharing` that data.
+fn confirm_remove(
+    targets: &[&data::Torrent],
+    with_data: bool,
+    sharing: &[&data::Torrent],
+) -> Action {
+    let ids = targets.iter().map(|t| t.id).collect_vec();
+    let (what, its) = match targets {
+        [torrent] => (format!("\"{}\"", torrent.name), "its"),
+        _ => (format!("{} torrents", targets.len()), "their"),
+    };
+    let warning = match sharing {
+        [] => String::new(),
+        [torrent] => format!(
+            " \"{}\" seeds the same files and would break.",
+            torrent.name
+        ),
+        [torrent, ..] => format!(
+            " {} other torrents, like \"{}\", seed the same files and would break.",
+            sharing.len(),
+            torrent.name
+        ),
+    };
+    match with_data {
+        true => Action::Confirm(
+            format!("Remove {what} and delete {its} data?{warning}"),
+            Box::new(Action::RemoveWithData(ids)),
+        ),
+        false => Action::Confirm(format!("Remove {what}?"), Box::new(Action::Remove(ids))),
+    }
+}
+
+/// Ask before moving `targets` onto files already in `location`, if there are any. The files
+/// on disk are looked for through `mappings`, or as they are if the daemon is `local`, and not
+/// at all otherwise.
+pub fn confirm_location(
+    targets: &[&data::Torrent],
+    torrents: &[data::Torrent],
+    location: &str,
+    mappings: &[PathMapping],
+    local: bool,
+) -> Option<Action> {
+    let exists = |path: &str| match mapped_path(mappings, path) {
+        Some(path) => path.exists(),
+        None => local && std::path::Path::new(path).exists(),
+    };
+    let collisions = targets
+        .iter()
+        .flat_map(|torrent| data::location_collisions(torrent, torrents, location, exists))
+        .collect_vec();
+    let first = collisions.first()?;
+    let message = match collisions.len() {
+        1 => format!("\"{first}\" already exists in {location}. Move and overwrite it?"),
+        n => format!(
+            "{n} files, like \"{first}\", already exist in {location}. Move and overwrite them?"
+        ),
+    };
+    Some(Action::Confirm(
+        message,
+        Box::new(Action::SetLocation(
+            targets.iter().map(|t| t.id).collect(),
+            location.to_string(),
+            true,
+        )),
+    ))
+}
+
+/// Point torrents `ids` at `location`, moving their data there if `move_data`.
+pub async fn set_location(
+    client: &Rc<RefCell<Client>>,
+    ids: Vec<i64>,
+    location: String,
+    move_data: bool,
+) -> types::Result<()> {
+    let res = {
+        let mut client = client.borrow_mut();
+        async move {
+            client
+                .torrent_set_location(
+                    ids.into_iter().map(Id::Id).collect(),
+                    location,
+                    Some(move_data),
+                )
+                .await
+        }
+    }
+    .await;
+    res?;
+    Ok(())
+}
+
+pub async fn close_session(client: &Rc<RefCell<Client>>) -> Result<bool, app::Error> {
     let res = {
         let mut client = client.borrow_mut();
         async move { client.session_close().await }
@@ -365,52 +1441,66 @@ pub async fn close_session(client: &Rc<RefCell<TransClient>>) -> Result<bool, ap
     }
 }
 
-fn constraint_len_calculator(items: &[data::Torrent]) -> (u16, u16, u16, u16, u16, u16) {
-    let name_len = items
-        .iter()
-        .map(data::Torrent::formatted_name)
-        .map(UnicodeWidthStr::width)
-        .min()
-        .unwrap_or(0);
-    let done_len = items
-        .iter()
-        .map(data::Torrent::percent_done)
-        .flat_map(str::lines)
-        .map(UnicodeWidthStr::width)
-        .max()
-        .unwrap_or(0);
-    let eta_len = items
-        .iter()
-        .map(data::Torrent::eta)
-        .map(UnicodeWidthStr::width)
-        .max()
-        .unwrap_or(0);
-    let up_len = items
+/// The width of each of `columns` for `items`: the narrowest name, so that the other columns
+/// keep their room, and the widest content or title of the rest.
+fn constraint_len_calculator(items: &[data::Torrent], columns: &[Column], raw: bool) -> Vec<u16> {
+    columns
         .iter()
-        .map(data::Torrent::upload_speed)
-        .map(UnicodeWidthStr::width)
-        .max()
-        .unwrap_or(0);
-    let down_len = items
-        .iter()
-        .map(data::Torrent::download_speed)
-        .map(UnicodeWidthStr::width)
-        .max()
-        .unwrap_or(0);
-    let ratio_len = items
-        .iter()
-        .map(data::Torrent::ratio)
-        .map(UnicodeWidthStr::width)
-        .max()
-        .unwrap_or(0);
-
-    #[allow(clippy::cast_possible_truncation)]
-    (
-        name_len as u16,
-        done_len as u16,
-        eta_len as u16,
-        down_len as u16,
-        up_len as u16,
-        ratio_len as u16,
-    )
+        .map(|&column| {
+            let widths = items.iter().map(|t| column.content(t, raw));
+            let len = match column {
+                Column::Name => widths.map(|name| name.width()).min().unwrap_or(0),
+                _ => widths
+                    .flat_map(|content| content.lines().map(str::width).collect_vec())
+                    .chain([column.title().width()])
+                    .max()
+                    .unwrap_or(0),
+            };
+            u16::try_from(len).unwrap_or(u16::MAX)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        data::fixtures::torrent_json,
+        rpc::fixtures::{serve, success},
+    };
+
+    // The name gives way to the other columns on the small terminal. The calls block on the
+    // runtime's reactor, which needs another thread to run.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_snapshot() {
+        let mut seeding = torrent_json(2, false);
+        seeding["name"] = json!("debian-12.5.0-amd64-netinst.iso");
+        seeding["status"] = json!(6);
+        seeding["percentDone"] = json!(1.0);
+        seeding["leftUntilDone"] = json!(0);
+        seeding["rateDownload"] = json!(0);
+        let url = serve(vec![
+            (
+                "200 OK",
+                success(json!({ "torrents": [torrent_json(1, false), seeding] })),
+            ),
+            (
+                "200 OK",
+                success(json!({ "torrents": [
+                    { "id": 1, "queuePosition": 0 },
+                    { "id": 2, "queuePosition": 1 },
+                ] })),
+            ),
+        ]);
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let mut home = Home::new(client, None).unwrap();
+        for size in SIZES {
+            assert_snapshot("home", size, |frame| {
+                home.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
 }
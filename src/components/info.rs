@@ -0,0 +1,109 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors};
+
+use super::{centered_rect, Component};
+
+const INFO_WIDTH: u16 = 60;
+
+/// A popup showing lines of text until dismissed, scrolled when they don't fit.
+pub struct Info {
+    title: String,
+    lines: Vec<String>,
+    /// Index of the first line shown.
+    scroll: usize,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Info {
+    pub fn new(title: String, lines: Vec<String>) -> Self {
+        Self {
+            title,
+            lines,
+            scroll: 0,
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+}
+
+impl Component for Info {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let last = self.lines.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                if let Some(tx) = &self.command_tx {
+                    tx.send(Action::ClosePopup)?;
+                }
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.scroll = (self.scroll + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Char('g') | KeyCode::Home => self.scroll = 0,
+            KeyCode::Char('G') | KeyCode::End => self.scroll = last,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        #[allow(clippy::cast_possible_truncation)]
+        let height = self.lines.len() as u16 + 2;
+        let rect = centered_rect(area, INFO_WIDTH, height);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title(self.title.as_str().bold())
+            .style(
+                Style::default()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            );
+        let lines = self
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .map(|line| Line::raw(line.as_str()));
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(Paragraph::new(lines.collect::<Vec<_>>()).block(block), rect);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_snapshot() {
+        let mut info = Info::new(
+            "Media".to_string(),
+            vec![
+                "Format: matroska".to_string(),
+                "Duration: 42:10".to_string(),
+                "Video: h264 1920x1080".to_string(),
+            ],
+        );
+        for size in SIZES {
+            assert_snapshot("info", size, |frame| {
+                info.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Position, Rect},
+    style::Style,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+/// A single line text field for dialogs.
+#[derive(Debug, Default, Clone)]
+pub struct Input {
+    value: String,
+    /// Cursor position in characters.
+    cursor: usize,
+}
+
+impl Input {
+    pub fn new(value: impl Into<String>) -> Self {
+        let value = value.into();
+        let cursor = value.chars().count();
+        Self { value, cursor }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Byte offset of the character at `index`.
+    fn byte_index(&self, index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(index)
+            .map_or(self.value.len(), |(i, _)| i)
+    }
+
+    /// Edit the value with `key`, returning whether the key was used.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let len = self.value.chars().count();
+        match key.code {
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.value.drain(..self.byte_index(self.cursor));
+                self.cursor = 0;
+            }
+            KeyCode::Char(c) => {
+                let index = self.byte_index(self.cursor);
+                self.value.insert(index, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let index = self.byte_index(self.cursor);
+                self.value.remove(index);
+            }
+            KeyCode::Delete if self.cursor < len => {
+                let index = self.byte_index(self.cursor);
+                self.value.remove(index);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(len),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = len,
+            KeyCode::Backspace | KeyCode::Delete => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Insert `text` at the cursor, joining its lines since the field holds one.
+    pub fn insert(&mut self, text: &str) {
+        let text = text.lines().map(str::trim).collect::<String>();
+        let index = self.byte_index(self.cursor);
+        self.value.insert_str(index, &text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Draw the field in `block`, placing the terminal cursor when `focused`.
+    pub fn draw(&self, frame: &mut Frame, area: Rect, block: Block, style: Style, focused: bool) {
+        let inner = block.inner(area);
+        // Scroll horizontally to keep the cursor visible.
+        let scroll = (self.cursor + 1).saturating_sub(usize::from(inner.width));
+        #[allow(clippy::cast_possible_truncation)]
+        let paragraph = Paragraph::new(self.value.as_str())
+            .style(style)
+            .scroll((0, scroll as u16))
+            .block(block);
+        frame.render_widget(paragraph, area);
+        if focused {
+            #[allow(clippy::cast_possible_truncation)]
+            frame.set_cursor_position(Position::new(
+                inner.x + (self.cursor - scroll) as u16,
+                inner.y,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(input: &mut Input, code: KeyCode) {
+        input.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_input_editing() {
+        let mut input = Input::new("ab");
+        press(&mut input, KeyCode::Left);
+        press(&mut input, KeyCode::Char('é'));
+        assert_eq!(input.value(), "aéb");
+        press(&mut input, KeyCode::Backspace);
+        press(&mut input, KeyCode::Backspace);
+        press(&mut input, KeyCode::Backspace);
+        assert_eq!(input.value(), "b");
+        press(&mut input, KeyCode::End);
+        press(&mut input, KeyCode::Char('c'));
+        press(&mut input, KeyCode::Home);
+        press(&mut input, KeyCode::Delete);
+        assert_eq!(input.value(), "c");
+        input.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert_eq!(input.value(), "c");
+        press(&mut input, KeyCode::End);
+        input.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert_eq!(input.value(), "");
+    }
+
+    #[test]
+    fn test_input_insert() {
+        let mut input = Input::new("ad");
+        press(&mut input, KeyCode::Left);
+        input.insert("bé\r\n c");
+        assert_eq!(input.value(), "abécd");
+        press(&mut input, KeyCode::Char('!'));
+        assert_eq!(input.value(), "abéc!d");
+    }
+}
@@ -0,0 +1,121 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style, Stylize},
+    widgets::{Block, BorderType, Clear, HighlightSpacing, List, ListState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors};
+
+use super::{centered_rect, Component};
+
+const MENU_WIDTH: u16 = 40;
+
+/// A popup listing actions, the chosen one is dispatched on `Enter`.
+pub struct Menu {
+    title: String,
+    items: Vec<(String, Action)>,
+    state: ListState,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Menu {
+    pub fn new(title: String, items: Vec<(String, Action)>) -> Self {
+        Self {
+            title,
+            items,
+            state: ListState::default().with_selected(Some(0)),
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for Menu {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.state.select_previous(),
+            KeyCode::Char('g') | KeyCode::Home => self.state.select_first(),
+            KeyCode::Char('G') | KeyCode::End => self.state.select_last(),
+            KeyCode::Enter | KeyCode::Char('l') => {
+                let action = self
+                    .state
+                    .selected()
+                    .and_then(|i| self.items.get(i))
+                    .map(|(_, action)| action.clone());
+                self.close()?;
+                return Ok(action);
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('m') => self.close()?,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        #[allow(clippy::cast_possible_truncation)]
+        let height = self.items.len() as u16 + 2;
+        let rect = centered_rect(area, MENU_WIDTH, height);
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(self.colors.selected_style_fg);
+
+        let list = List::new(self.items.iter().map(|(label, _)| label.as_str()))
+            .style(
+                Style::default()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .highlight_style(selected_style)
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(self.colors.footer_border_color))
+                    .title(self.title.as_str().bold()),
+            );
+        frame.render_widget(Clear, rect);
+        frame.render_stateful_widget(list, rect, &mut self.state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_snapshot() {
+        let mut menu = Menu::new(
+            "Actions".to_string(),
+            vec![
+                ("Verify".to_string(), Action::Verify),
+                ("Reannounce".to_string(), Action::Reannounce),
+                ("Remove".to_string(), Action::RemoveSelected),
+            ],
+        );
+        for size in SIZES {
+            assert_snapshot("menu", size, |frame| {
+                menu.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
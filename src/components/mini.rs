@@ -0,0 +1,129 @@
+use std::{cell::RefCell, rc::Rc};
+
+use color_eyre::Result;
+use futures::executor::block_on;
+use itertools::Itertools;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::Line,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::{
+    action::{Action, Failure, Level},
+    colors::Colors,
+    data::{map_torrent_list, Torrent},
+    rpc::Client,
+};
+
+use super::{footer_layout, Component};
+
+/// Compact list of the most active torrents, for `--mini`.
+pub struct Mini {
+    client: Rc<RefCell<Client>>,
+    items: Vec<Torrent>,
+    colors: Colors,
+}
+
+impl Mini {
+    pub fn new(client: Rc<RefCell<Client>>) -> Result<Self> {
+        let items = most_active(block_on(map_torrent_list(&client))?);
+        Ok(Self {
+            client,
+            items,
+            colors: Colors::new(),
+        })
+    }
+}
+
+/// Torrents that are transferring, busiest first.
+fn most_active(items: Vec<Torrent>) -> Vec<Torrent> {
+    items
+        .into_iter()
+        .filter(|t| t.rate_download + t.rate_upload > 0)
+        .sorted_by_key(|t| std::cmp::Reverse(t.rate_download + t.rate_upload))
+        .collect()
+}
+
+impl Component for Mini {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick | Action::Refresh => match block_on(map_torrent_list(&self.client)) {
+                Ok(items) => self.items = most_active(items),
+                Err(err) => {
+                    let failure = Failure::new("Mini", err).level(Level::Warning);
+                    return Ok(Some(Action::Error(failure)));
+                }
+            },
+            Action::Theme(_) => self.colors = Colors::new(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [body, _] = footer_layout(area);
+
+        let lines = match self.items.is_empty() {
+            true => vec![Line::from(" No active torrents")],
+            false => self
+                .items
+                .iter()
+                .take(body.height as usize)
+                .map(|t| {
+                    Line::from(format!(
+                        " {:>6} ↓{:>11} ↑{:>11}  {}",
+                        t.percent_done(),
+                        t.download_speed(),
+                        t.upload_speed(),
+                        t.name
+                    ))
+                })
+                .collect(),
+        };
+        let list = Paragraph::new(lines)
+            .style(Style::new().fg(self.colors.row_fg))
+            .block(Block::new().style(Style::new().bg(self.colors.buffer_bg)));
+        frame.render_widget(list, body);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        data::fixtures::torrent_json,
+        rpc::fixtures::{serve, success},
+    };
+
+    // Idle torrents are left out, the busiest come first.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_snapshot() {
+        let mut idle = torrent_json(3, false);
+        idle["rateDownload"] = json!(0);
+        idle["rateUpload"] = json!(0);
+        let mut busy = torrent_json(2, false);
+        busy["name"] = json!("debian-12.5.0-amd64-netinst.iso");
+        busy["rateDownload"] = json!(2_500_000);
+        let url = serve(vec![
+            (
+                "200 OK",
+                success(json!({ "torrents": [torrent_json(1, false), busy, idle] })),
+            ),
+            ("200 OK", success(json!({ "torrents": [] }))),
+        ]);
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let mut mini = Mini::new(client).unwrap();
+        for size in SIZES {
+            assert_snapshot("mini", size, |frame| {
+                mini.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
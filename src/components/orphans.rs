@@ -0,0 +1,196 @@
+use std::{cell::RefCell, rc::Rc};
+
+use color_eyre::Result;
+use futures::executor::block_on;
+use itertools::Itertools;
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, HighlightSpacing, List, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState,
+    },
+    Frame,
+};
+
+use crate::{
+    action::Action,
+    app::Mode,
+    colors::Colors,
+    config::{Config, PathMapping},
+    data::{fetch_download_dir, find_orphans, map_torrent_data, Orphan},
+    rpc::Client,
+    utils::convert_bytes,
+};
+
+use super::{main_layout, Component};
+
+/// Report of the data in the download directories that no torrent refers to, to reclaim space.
+pub struct Orphans {
+    client: Rc<RefCell<Client>>,
+    orphans: Vec<Orphan>,
+    /// Directories that couldn't be read from here.
+    unreadable: Vec<String>,
+    path_mappings: Vec<PathMapping>,
+    /// Torrent selected in Home, restored when going back.
+    home_id: i64,
+    state: ListState,
+    scroll_state: ScrollbarState,
+    colors: Colors,
+}
+
+impl Orphans {
+    pub fn new(client: Rc<RefCell<Client>>, home_id: i64) -> Self {
+        Self {
+            client,
+            orphans: Vec::new(),
+            unreadable: Vec::new(),
+            path_mappings: Vec::new(),
+            home_id,
+            state: ListState::default().with_selected(Some(0)),
+            scroll_state: ScrollbarState::default(),
+            colors: Colors::new(),
+        }
+    }
+
+    /// Scan the directories again, which reads every file under them.
+    fn refresh(&mut self) -> Result<()> {
+        let torrents = block_on(map_torrent_data(&self.client, None))?;
+        let download_dir = block_on(fetch_download_dir(&self.client))?;
+        (self.orphans, self.unreadable) =
+            find_orphans(&torrents, &[download_dir], &self.path_mappings);
+        self.scroll_state = self.scroll_state.content_length(self.orphans.len());
+        Ok(())
+    }
+
+    fn select(&mut self, index: usize) {
+        self.state.select(Some(index));
+        self.scroll_state = self.scroll_state.position(index);
+    }
+
+    fn title(&self) -> String {
+        let total = self.orphans.iter().map(|o| o.size).sum::<u64>();
+        let mut title = format!(
+            "Orphaned data: {} in {} entries",
+            size(total),
+            self.orphans.len()
+        );
+        if !self.unreadable.is_empty() {
+            title.push_str(&format!(
+                ", {} directories unreadable, see path_mappings",
+                self.unreadable.len()
+            ));
+        }
+        title
+    }
+}
+
+fn size(bytes: u64) -> String {
+    convert_bytes(i64::try_from(bytes).unwrap_or(i64::MAX))
+}
+
+impl Component for Orphans {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.path_mappings = config.path_mappings;
+        self.refresh()
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let last = self.orphans.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0);
+        match action {
+            Action::Refresh => {
+                if let Err(err) = self.refresh() {
+                    return Ok(Some(Action::error("Orphans", err)));
+                }
+            }
+            Action::Back => return Ok(Some(Action::Mode(Mode::Home, self.home_id))),
+            Action::Theme(_) => self.colors = Colors::new(),
+            Action::Down => self.select((selected + 1).min(last)),
+            Action::Up => self.select(selected.saturating_sub(1)),
+            Action::Top => self.select(0),
+            Action::Bottom => self.select(last),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let block = Block::bordered()
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title(self.title().bold().fg(self.colors.row_fg));
+
+        if self.orphans.is_empty() {
+            let empty = Paragraph::new("No orphaned data found")
+                .style(style)
+                .block(block);
+            frame.render_widget(empty, body);
+            return Ok(());
+        }
+
+        let items = self
+            .orphans
+            .iter()
+            .map(|orphan| Line::raw(format!("{:>10}  {}", size(orphan.size), orphan.path)))
+            .collect_vec();
+        let list = List::new(items)
+            .style(style)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.colors.selected_style_fg),
+            )
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(block);
+
+        frame.render_stateful_widget(list, body, &mut self.state);
+        frame.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            body.inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            &mut self.scroll_state,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    // Filled in by hand, scanning reads the directories on disk.
+    #[test]
+    fn test_snapshot() {
+        let url = "http://localhost:9091/transmission/rpc".parse().unwrap();
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let mut orphans = Orphans::new(client, 1);
+        orphans.orphans = vec![
+            Orphan {
+                path: "/downloads/ubuntu-22.04-desktop-amd64.iso".to_string(),
+                size: 4_700_000_000,
+            },
+            Orphan {
+                path: "/downloads/Old Show S01".to_string(),
+                size: 1_200_000,
+            },
+        ];
+        orphans.unreadable = vec!["/srv/seedbox".to_string()];
+        orphans.scroll_state = orphans.scroll_state.content_length(orphans.orphans.len());
+        for size in SIZES {
+            assert_snapshot("orphans", size, |frame| {
+                orphans.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
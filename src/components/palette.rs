@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use itertools::Itertools;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    widgets::{Block, BorderType, Cell, Clear, HighlightSpacing, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors, config::key_event_to_string};
+
+use super::{centered_rect, input::Input, Component};
+
+const PALETTE_WIDTH: u16 = 64;
+/// Rows of commands shown at once.
+const PALETTE_ROWS: u16 = 14;
+
+/// An action of the keymap of the current view, with the keys bound to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    label: String,
+    keys: String,
+    action: Action,
+}
+
+/// The commands of `keymap`, one per action, sorted by label.
+pub fn commands(keymap: &HashMap<Vec<KeyEvent>, Action>) -> Vec<Command> {
+    let mut grouped: Vec<(&Action, Vec<String>)> = Vec::new();
+    for (keys, action) in keymap {
+        // Opening the palette from itself does nothing.
+        if *action == Action::OpenPalette {
+            continue;
+        }
+        let keys = keys.iter().map(key_event_to_string).join(" ");
+        match grouped.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, all)) => all.push(keys),
+            None => grouped.push((action, vec![keys])),
+        }
+    }
+    grouped
+        .into_iter()
+        .map(|(action, keys)| Command {
+            label: describe(action),
+            keys: keys
+                .into_iter()
+                .sorted_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)))
+                .join(", "),
+            action: action.clone(),
+        })
+        .sorted_by(|a, b| a.label.cmp(&b.label))
+        .collect()
+}
+
+/// A readable name for `action`, like "Sort by: queue" for `SortBy(Queue)`.
+fn describe(action: &Action) -> String {
+    let debug = format!("{action:?}");
+    let (name, argument) = match debug.split_once('(') {
+        Some((name, argument)) => (name, Some(argument.trim_end_matches(')'))),
+        None => (debug.as_str(), None),
+    };
+    let mut label = String::with_capacity(debug.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if i > 0 && c.is_uppercase() {
+            label.push(' ');
+            label.extend(c.to_lowercase());
+        } else {
+            label.push(c);
+        }
+    }
+    if let Some(argument) = argument {
+        label.push_str(": ");
+        label.push_str(&argument.trim_matches('"').to_lowercase());
+    }
+    label
+}
+
+/// How well `query` matches `text` as a subsequence, ignoring case and spaces, if at all.
+/// Consecutive letters and letters starting words score higher, skipped letters lower.
+fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text = text.to_lowercase().chars().collect_vec();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous = None;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|&t| t == c)?;
+        if previous.is_some_and(|previous| found == previous + 1) {
+            score += 5;
+        }
+        if found == 0 || text[found - 1] == ' ' {
+            score += 3;
+        }
+        score -= i64::try_from(found - position).unwrap_or(i64::MAX);
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// A popup searching the commands of the current view, running the chosen one on `Enter`.
+pub struct Palette {
+    commands: Vec<Command>,
+    input: Input,
+    /// Indexes in `commands` of the ones matching the query, best first.
+    matches: Vec<usize>,
+    state: TableState,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Palette {
+    pub fn new(commands: Vec<Command>) -> Self {
+        let mut palette = Self {
+            commands,
+            input: Input::default(),
+            matches: Vec::new(),
+            state: TableState::default(),
+            colors: Colors::new(),
+            command_tx: None,
+        };
+        palette.filter();
+        palette
+    }
+
+    fn filter(&mut self) {
+        let query = self.input.value();
+        self.matches = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, command)| Some((i, fuzzy_score(query, &command.label)?)))
+            .sorted_by_key(|&(_, score)| std::cmp::Reverse(score))
+            .map(|(i, _)| i)
+            .collect();
+        self.state.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for Palette {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.close()?,
+            KeyCode::Enter => {
+                let action = self
+                    .state
+                    .selected()
+                    .and_then(|i| self.matches.get(i))
+                    .map(|&i| self.commands[i].action.clone());
+                self.close()?;
+                return Ok(action);
+            }
+            KeyCode::Down | KeyCode::Tab => self.state.select_next(),
+            KeyCode::Char('n') if ctrl => self.state.select_next(),
+            KeyCode::Up | KeyCode::BackTab => self.state.select_previous(),
+            KeyCode::Char('p') if ctrl => self.state.select_previous(),
+            _ => {
+                let before = self.input.value().to_string();
+                if self.input.handle_key(key) && self.input.value() != before {
+                    self.filter();
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_paste_event(&mut self, text: String) -> Result<Option<Action>> {
+        self.input.insert(&text);
+        self.filter();
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        #[allow(clippy::cast_possible_truncation)]
+        let rows = (self.matches.len() as u16).clamp(1, PALETTE_ROWS);
+        let rect = centered_rect(area, PALETTE_WIDTH, rows + 5);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let border_style = Style::default().fg(self.colors.footer_border_color);
+        let [search, list] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(rect);
+
+        frame.render_widget(Clear, rect);
+        self.input.draw(
+            frame,
+            search,
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .border_style(border_style)
+                .title("Commands".bold()),
+            style,
+            true,
+        );
+        let rows = self.matches.iter().map(|&i| {
+            let command = &self.commands[i];
+            Row::new([
+                Cell::from(command.label.as_str()),
+                Cell::from(command.keys.as_str()).fg(self.colors.footer_border_color),
+            ])
+        });
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(18)])
+            .style(style)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.colors.selected_style_fg),
+            )
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(border_style),
+            );
+        frame.render_stateful_widget(table, list, &mut self.state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config::parse_key_sequence, data::QueueMove, view::SortKey};
+
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(&Action::ToggleAltSpeed), "Toggle alt speed");
+        assert_eq!(describe(&Action::SortBy(SortKey::Queue)), "Sort by: queue");
+        assert_eq!(
+            describe(&Action::MoveInQueue(QueueMove::Top)),
+            "Move in queue: top"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("", "Verify").is_some());
+        assert!(fuzzy_score("vfy", "Verify").is_some());
+        assert!(fuzzy_score("xyz", "Verify").is_none());
+        assert!(fuzzy_score("yv", "Verify").is_none());
+        // Word starts and consecutive letters beat scattered ones.
+        let sort_by = fuzzy_score("sort", "Sort by: name").unwrap();
+        let scattered = fuzzy_score("sort", "Reverse sort").unwrap();
+        assert!(sort_by > scattered);
+    }
+
+    #[test]
+    fn test_commands() {
+        let key = |raw: &str| parse_key_sequence(raw).unwrap();
+        let keymap = HashMap::from([
+            (key("<j>"), Action::Down),
+            (key("<down>"), Action::Down),
+            (key("<v>"), Action::Verify),
+            (key("<Ctrl-p>"), Action::OpenPalette),
+        ]);
+        let commands = commands(&keymap);
+        assert_eq!(
+            commands
+                .iter()
+                .map(|c| (c.label.as_str(), c.keys.as_str()))
+                .collect_vec(),
+            [("Down", "j, down"), ("Verify", "v")]
+        );
+    }
+
+    // A popup of fixed width, as high as the commands found.
+    #[test]
+    fn test_snapshot() {
+        let key = |raw: &str| parse_key_sequence(raw).unwrap();
+        let keymap = HashMap::from([
+            (key("<v>"), Action::Verify),
+            (key("<s>"), Action::SortBy(SortKey::Queue)),
+            (key("<a>"), Action::ToggleAltSpeed),
+            (key("<Shift-A>"), Action::ToggleAltSpeed),
+        ]);
+        let mut palette = Palette::new(commands(&keymap));
+        palette.handle_paste_event("t".to_string()).unwrap();
+        assert_snapshot("palette", SIZES[0], |frame| {
+            palette.draw(frame, frame.area()).unwrap();
+        });
+    }
+}
@@ -0,0 +1,112 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors};
+
+use super::{centered_rect, input::Input, Component};
+
+const PROMPT_WIDTH: u16 = 64;
+const PROMPT_HEIGHT: u16 = 3;
+
+/// A popup asking for a single line of text, turned into an action on `Enter`.
+pub struct Prompt {
+    title: String,
+    input: Input,
+    on_submit: Box<dyn Fn(String) -> Action>,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Prompt {
+    pub fn new(
+        title: impl Into<String>,
+        value: impl Into<String>,
+        on_submit: impl Fn(String) -> Action + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            input: Input::new(value),
+            on_submit: Box::new(on_submit),
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for Prompt {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.close()?,
+            KeyCode::Enter => {
+                let value = self.input.value().trim();
+                if !value.is_empty() {
+                    self.close()?;
+                    return Ok(Some((self.on_submit)(value.to_string())));
+                }
+            }
+            _ => {
+                self.input.handle_key(key);
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_paste_event(&mut self, text: String) -> Result<Option<Action>> {
+        self.input.insert(&text);
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let rect = centered_rect(area, PROMPT_WIDTH, PROMPT_HEIGHT);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title(self.title.as_str().bold())
+            .title_bottom(Line::from(" Enter: confirm · Esc: cancel ").right_aligned());
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        frame.render_widget(Clear, rect);
+        self.input.draw(frame, rect, block, style, true);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    // A popup of fixed width, cut to the small terminal.
+    #[test]
+    fn test_snapshot() {
+        let mut prompt = Prompt::new("Rename to", "debian-12.5.0-amd64-netinst.iso", |name| {
+            Action::RenamePath(1, "debian-12.5.0-amd64-netinst.iso".to_string(), name)
+        });
+        for size in SIZES {
+            assert_snapshot("prompt", size, |frame| {
+                prompt.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
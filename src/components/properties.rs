@@ -1,28 +1,40 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, fmt::Display, path::PathBuf, rc::Rc};
 
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use futures::executor::block_on;
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
-    style::{palette::tailwind, Modifier, Style, Stylize},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Modifier, Style, Stylize},
     text::Line,
     widgets::Tabs,
     Frame,
 };
 use strum::{Display, EnumIter, FromRepr, IntoEnumIterator};
-use transmission_rpc::TransClient;
+use tokio::sync::mpsc::UnboundedSender;
+use transmission_rpc::types::{Id, TorrentAction, TorrentSetArgs};
 
+#[cfg(feature = "clipboard")]
+use crate::clipboard;
 use crate::{
-    action::Action,
+    action::{Action, Failure, Level},
     app::{self, Mode},
     colors::Colors,
-    data::{self, map_torrent_data},
+    config::{local_path, Config, PathMapping},
+    data::{
+        self, change_tracker, data_path, fetch_free_space, fetch_peers, map_torrent_data,
+        rename_path, set_labels, TrackerChange,
+    },
+    history::record_torrent_rates,
+    media::probe,
+    opener::open,
+    rpc::Client,
 };
 
-use super::{home::close_session, Component};
-
-const SCROLL_SIZE: usize = 4;
+use super::{
+    home::{confirm_location, set_location},
+    page_rows, report, Component,
+};
 
 pub mod files;
 pub mod info;
@@ -30,22 +42,33 @@ pub mod peers;
 pub mod trackers;
 
 pub struct Properties {
-    client: Rc<RefCell<TransClient>>,
+    client: Rc<RefCell<Client>>,
     data: data::Torrent,
     selected_tab: SelectedTab,
     info_tab: info::Tab,
+    peers_tab: peers::Tab,
     tracker_tab: trackers::Tab,
     files_tab: files::Tab,
+    /// Where the tab titles were last drawn, to find the one clicked.
+    tabs_area: Rect,
+    path_mappings: Vec<PathMapping>,
+    /// The `open.command` of the config.
+    open_command: Option<String>,
+    /// Show the exact numbers instead of formatted values.
+    raw: bool,
+    /// The torrent was removed, the view goes back to the list.
+    gone: bool,
     colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq, Display, FromRepr, EnumIter)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, FromRepr, EnumIter)]
 enum SelectedTab {
     #[default]
     #[strum(to_string = "Info")]
     Info,
-    // #[strum(to_string = "Peers")]
-    // Peers,
+    #[strum(to_string = "Peers")]
+    Peers,
     #[strum(to_string = "Tracker")]
     Tracker,
     #[strum(to_string = "Files")]
@@ -53,6 +76,43 @@ enum SelectedTab {
 }
 
 impl Component for Properties {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        self.send_breadcrumbs()
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.tracker_tab.show_full_urls(config.trackers.full_urls);
+        self.peers_tab.show_sparklines(config.peers.sparklines);
+        self.path_mappings = config.path_mappings;
+        self.open_command = config.open.command;
+        self.info_tab.set_free_space_config(config.free_space);
+        self.raw = config.table.raw;
+        self.info_tab.set_raw(self.raw);
+        Ok(())
+    }
+
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position::new(mouse.column, mouse.row);
+                if !self.tabs_area.contains(position) {
+                    return Ok(None);
+                }
+                match SelectedTab::at(position.x - self.tabs_area.x) {
+                    Some(tab) if tab != self.selected_tab => {
+                        self.selected_tab = tab;
+                        self.tab_changed()
+                    }
+                    _ => Ok(None),
+                }
+            }
+            MouseEventKind::ScrollDown => Ok(Some(Action::Down)),
+            MouseEventKind::ScrollUp => Ok(Some(Action::Up)),
+            _ => Ok(None),
+        }
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         self.render_tabs(frame, area);
         Ok(())
@@ -60,65 +120,201 @@ impl Component for Properties {
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
-            Action::Tick => {
+            Action::Tick | Action::Refresh => {
                 self.data = match block_on(map_torrent_data(&self.client, Some(self.data.id))) {
-                    Ok(d) => d.first().ok_or(app::Error::OutOfBound)?.clone(),
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
+                    Ok(mut torrents) if !torrents.is_empty() => torrents.swap_remove(0),
+                    Ok(_) => {
+                        self.gone = true;
+                        return Ok(Some(Action::Mode(Mode::Home, self.data.id)));
+                    }
+                    Err(err) => return Ok(Some(self.failure(err, Level::Warning))),
                 };
+                if action == Action::Tick {
+                    record_torrent_rates(std::slice::from_ref(&self.data), false);
+                }
+                // Older daemons can't tell, and the location may be gone.
+                let free_space = block_on(fetch_free_space(&self.client, &self.data.location)).ok();
+                self.info_tab.update(&self.data, free_space);
+                self.tracker_tab.update(&self.data);
+                self.files_tab.update(&self.data);
+                // Sparklines need samples even while another tab is shown.
+                if self.selected_tab == SelectedTab::Peers || self.peers_tab.sparklines() {
+                    return Ok(self.refresh_peers());
+                }
             }
-            Action::Render => {}
-            _ => {}
-        }
-        Ok(None)
-    }
-
-    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
-        match key.code {
-            KeyCode::Char('q') => {
-                return Ok(Some(Action::Quit));
+            Action::Verify | Action::Reannounce => {
+                let id = self.data.id;
+                let torrent_action = match action {
+                    Action::Verify => TorrentAction::Verify,
+                    _ => TorrentAction::Reannounce,
+                };
+                let res = {
+                    let mut client = self.client.borrow_mut();
+                    async move {
+                        client
+                            .torrent_action(torrent_action, vec![Id::Id(id)])
+                            .await
+                    }
+                };
+                let message = match torrent_action {
+                    TorrentAction::Verify => "Verifying",
+                    _ => "Reannounced",
+                };
+                return report(
+                    self.command_tx.as_ref(),
+                    "Properties",
+                    block_on(res).map(|_| ()),
+                    message,
+                );
             }
-            KeyCode::Char('Q') => {
-                match block_on(close_session(&self.client)) {
-                    Ok(status) => {
-                        if status {
-                            return Ok(Some(Action::Quit));
-                        }
+            Action::MoveSelected => {
+                return Ok(Some(Action::OpenSetLocation(
+                    vec![self.data.id],
+                    self.data.location.clone(),
+                    true,
+                )));
+            }
+            Action::AddTracker => {
+                return Ok(Some(Action::OpenTracker(self.data.id, None, String::new())));
+            }
+            // `e` edits the labels, except on the Tracker tab.
+            Action::EditTracker | Action::EditLabels
+                if self.selected_tab == SelectedTab::Tracker =>
+            {
+                if let Some(tracker) = self.tracker_tab.selected() {
+                    return Ok(Some(Action::OpenTracker(
+                        self.data.id,
+                        Some(tracker.id),
+                        tracker.announce.clone(),
+                    )));
+                }
+            }
+            Action::RemoveTracker if self.selected_tab == SelectedTab::Tracker => {
+                if let Some(tracker) = self.tracker_tab.selected() {
+                    return Ok(Some(Action::Confirm(
+                        format!("Remove the tracker {}?", tracker.host),
+                        Box::new(Action::ChangeTracker(
+                            self.data.id,
+                            TrackerChange::Remove(tracker.id),
+                        )),
+                    )));
+                }
+            }
+            Action::ChangeTracker(id, change) if id == self.data.id => {
+                let message = match change {
+                    TrackerChange::Add(_) => "Tracker added",
+                    TrackerChange::Replace(..) => "Tracker changed",
+                    TrackerChange::Remove(_) => "Tracker removed",
+                };
+                let result = block_on(change_tracker(&self.client, id, &change));
+                return report(self.command_tx.as_ref(), "Properties", result, message);
+            }
+            Action::EditLabels => {
+                return Ok(Some(Action::OpenLabels(
+                    vec![self.data.id],
+                    self.data.labels.clone(),
+                )));
+            }
+            Action::SetLabels(ids, labels) if ids == [self.data.id] => {
+                let result = block_on(set_labels(&self.client, ids, labels));
+                return report(self.command_tx.as_ref(), "Properties", result, "Labels set");
+            }
+            Action::EditSpeedLimits => {
+                return Ok(Some(Action::OpenSpeedLimits(vec![self.data.id])))
+            }
+            Action::EditSeedLimits => return Ok(Some(Action::OpenSeedLimits(vec![self.data.id]))),
+            Action::LocateSelected => {
+                return Ok(Some(Action::OpenSetLocation(
+                    vec![self.data.id],
+                    self.data.location.clone(),
+                    false,
+                )));
+            }
+            Action::SetLocation(ids, location, force) if ids == [self.data.id] => {
+                if !force {
+                    let torrents = match block_on(map_torrent_data(&self.client, None)) {
+                        Ok(torrents) => torrents,
+                        Err(err) => return Ok(Some(self.failure(err, Level::Error))),
+                    };
+                    let local = self.client.borrow().is_local();
+                    if let Some(action) = confirm_location(
+                        &[&self.data],
+                        &torrents,
+                        &location,
+                        &self.path_mappings,
+                        local,
+                    ) {
+                        return Ok(Some(action));
                     }
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
+                }
+                return self.set_location(location, true);
+            }
+            Action::Locate(ids, location) if ids == [self.data.id] => {
+                return self.set_location(location, false);
+            }
+            Action::Rename => {
+                let path = match self.selected_tab {
+                    SelectedTab::Files => self.files_tab.selected_path(),
+                    _ => None,
                 };
+                let path = path.unwrap_or_else(|| self.data.name.clone());
+                return Ok(Some(Action::OpenRename(self.data.id, path)));
             }
-            KeyCode::Esc | KeyCode::Backspace => {
-                return Ok(Some(Action::Mode(Mode::Home, self.data.id)));
+            Action::RenamePath(id, path, name) if id == self.data.id => {
+                return self.rename(path, name);
             }
-            KeyCode::Char('l') | KeyCode::Right => {
+            Action::Back => return Ok(Some(Action::Mode(Mode::Home, self.data.id))),
+            Action::Theme(_) => self.colors = Colors::new(),
+            Action::ToggleRaw => {
+                self.raw = !self.raw;
+                self.info_tab.set_raw(self.raw);
+            }
+            Action::NextTab => {
                 self.next_tab();
+                return self.tab_changed();
             }
-            KeyCode::Char('h') | KeyCode::Left => {
+            Action::PreviousTab => {
                 self.previous_tab();
+                return self.tab_changed();
             }
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.next();
+            Action::Down => self.next(),
+            Action::Up => self.previous(),
+            Action::Top => self.top(),
+            Action::Bottom => self.bottom(),
+            Action::PageUp => self.scroll_up(false),
+            Action::PageDown => self.scroll_down(false),
+            Action::HalfPageUp => self.scroll_up(true),
+            Action::HalfPageDown => self.scroll_down(true),
+            Action::Select if self.selected_tab == SelectedTab::Files => self.files_tab.toggle(),
+            Action::ToggleWanted if self.selected_tab == SelectedTab::Files => {
+                return self.toggle_wanted();
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.previous();
+            Action::RaisePriority if self.selected_tab == SelectedTab::Files => {
+                return self.step_priority(true);
             }
-            KeyCode::Char('g') | KeyCode::Home => {
-                self.top();
+            Action::Inspect if self.selected_tab == SelectedTab::Files => return Ok(self.inspect()),
+            Action::CopyPath if self.selected_tab == SelectedTab::Files => {
+                return Ok(self.copy_path());
             }
-            KeyCode::Char('G') | KeyCode::End => {
-                self.bottom();
+            Action::RevealPath if self.selected_tab == SelectedTab::Files => {
+                return Ok(self.reveal_path());
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.scroll_up(SCROLL_SIZE);
+            Action::Find if self.selected_tab == SelectedTab::Files => {
+                return Ok(Some(Action::OpenSearch(self.files_tab.query().to_string())));
             }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.scroll_down(SCROLL_SIZE);
+            Action::Search(query) if self.selected_tab == SelectedTab::Files => {
+                self.files_tab.search(query);
             }
-            KeyCode::Enter => {
-                if self.selected_tab == SelectedTab::Files {
-                    self.files_tab.toggle();
-                }
+            Action::NextMatch if self.selected_tab == SelectedTab::Files => {
+                self.files_tab.jump_to_match(true);
+            }
+            Action::PreviousMatch if self.selected_tab == SelectedTab::Files => {
+                self.files_tab.jump_to_match(false);
             }
+            Action::LowerPriority if self.selected_tab == SelectedTab::Files => {
+                return self.step_priority(false);
+            }
+            Action::Render => {}
             _ => {}
         }
         Ok(None)
@@ -126,7 +322,7 @@ impl Component for Properties {
 }
 
 impl Properties {
-    pub fn new(client: Rc<RefCell<TransClient>>, id: i64) -> Result<Self> {
+    pub fn new(client: Rc<RefCell<Client>>, id: i64) -> Result<Self> {
         let data = block_on(map_torrent_data(&client, Some(id)))?
             .first()
             .ok_or(app::Error::OutOfBound)?
@@ -134,14 +330,245 @@ impl Properties {
         Ok(Self {
             client,
             info_tab: info::Tab::new(&data),
+            peers_tab: peers::Tab::new(),
             tracker_tab: trackers::Tab::new(&data),
             files_tab: files::Tab::new(&data),
+            path_mappings: Vec::new(),
+            open_command: None,
+            raw: false,
+            gone: false,
             data,
             selected_tab: SelectedTab::Info,
+            tabs_area: Rect::default(),
             colors: Colors::new(),
+            command_tx: None,
+        })
+    }
+
+    /// Skip the selected files if all of them are wanted, download them otherwise.
+    fn toggle_wanted(&mut self) -> Result<Option<Action>> {
+        let indices = self.files_tab.selected_files();
+        if indices.is_empty() {
+            return Ok(None);
+        }
+        let wanted = !self.files_tab.all_wanted(&indices);
+        let files = indices
+            .iter()
+            .map(|&i| i32::try_from(i))
+            .collect::<Result<Vec<_>, _>>()?;
+        let args = match wanted {
+            true => TorrentSetArgs {
+                files_wanted: Some(files),
+                ..TorrentSetArgs::default()
+            },
+            false => TorrentSetArgs {
+                files_unwanted: Some(files),
+                ..TorrentSetArgs::default()
+            },
+        };
+        let id = self.data.id;
+        let res = {
+            let mut client = self.client.borrow_mut();
+            async move { client.torrent_set(args, Some(vec![Id::Id(id)])).await }
+        };
+        if let Err(err) = block_on(res) {
+            return Ok(Some(Action::error("Properties", err)));
+        }
+        self.files_tab.set_wanted(&indices, wanted);
+        Ok(None)
+    }
+
+    /// Raise or lower the priority of the selected files a step, showing it right away.
+    fn step_priority(&mut self, raise: bool) -> Result<Option<Action>> {
+        let indices = self.files_tab.selected_files();
+        if indices.is_empty() {
+            return Ok(None);
+        }
+        let priorities = self.files_tab.step_priorities(&indices, raise);
+        let files_with = |priority: &str| -> Result<Option<Vec<i32>>> {
+            let files = priorities
+                .iter()
+                .filter(|(_, p)| *p == priority)
+                .map(|&(i, _)| i32::try_from(i))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((!files.is_empty()).then_some(files))
+        };
+        let [low, normal, high] = files::PRIORITIES;
+        let args = TorrentSetArgs {
+            priority_low: files_with(low)?,
+            priority_normal: files_with(normal)?,
+            priority_high: files_with(high)?,
+            ..TorrentSetArgs::default()
+        };
+        let id = self.data.id;
+        let res = {
+            let mut client = self.client.borrow_mut();
+            async move { client.torrent_set(args, Some(vec![Id::Id(id)])).await }
+        };
+        if let Err(err) = block_on(res) {
+            // The next refresh shows the priorities the daemon kept.
+            return Ok(Some(Action::error("Properties", err)));
+        }
+        Ok(None)
+    }
+
+    /// Inspect the selected file, if its data is reachable from here.
+    fn inspect(&self) -> Option<Action> {
+        let [index] = self.files_tab.selected_files()[..] else {
+            return Some(Action::Notify(
+                Level::Warning,
+                "Select a file to inspect".to_string(),
+            ));
+        };
+        let file = self.data.files.get(index)?;
+        let remote = data_path(&self.data.location, &file.name);
+        let path = local_path(&self.path_mappings, &remote);
+        if !path.exists() {
+            return Some(Action::Notify(
+                Level::Warning,
+                format!("{} isn't reachable, see path_mappings", path.display()),
+            ));
+        }
+        Some(match probe(&path) {
+            Ok(info) => Action::ShowInfo(file.name.clone(), info.lines()),
+            Err(err) => Action::error("Properties", err),
+        })
+    }
+
+    /// Where the selected file or directory is on this machine, see `path_mappings`.
+    fn selected_local_path(&self) -> Option<PathBuf> {
+        let path = self.files_tab.selected_path()?;
+        let remote = data_path(&self.data.location, &path);
+        Some(local_path(&self.path_mappings, &remote))
+    }
+
+    #[cfg(feature = "clipboard")]
+    fn copy_path(&self) -> Option<Action> {
+        let path = self.selected_local_path()?;
+        let path = path.to_string_lossy();
+        Some(match clipboard::write(&path) {
+            Ok(()) => Action::Notify(Level::Info, format!("Copied {path}")),
+            Err(err) => Action::error("Properties", err),
         })
     }
 
+    #[cfg(not(feature = "clipboard"))]
+    fn copy_path(&self) -> Option<Action> {
+        Some(Action::Notify(
+            Level::Warning,
+            "sparrow was built without the clipboard feature".to_string(),
+        ))
+    }
+
+    /// Open the selected directory, or the one holding the selected file.
+    fn reveal_path(&self) -> Option<Action> {
+        let mut path = self.selected_local_path()?;
+        if !path.exists() {
+            return Some(Action::Notify(
+                Level::Warning,
+                format!("{} isn't reachable, see path_mappings", path.display()),
+            ));
+        }
+        if !path.is_dir() {
+            path.pop();
+        }
+        Some(match open(&path, self.open_command.as_deref()) {
+            Ok(()) => Action::Notify(Level::Info, format!("Opened {}", path.display())),
+            Err(err) => Action::error("Properties", err),
+        })
+    }
+
+    /// Point the torrent at `location`, reporting the outcome in the status line.
+    fn set_location(&mut self, location: String, move_data: bool) -> Result<Option<Action>> {
+        let res = block_on(set_location(
+            &self.client,
+            vec![self.data.id],
+            location.clone(),
+            move_data,
+        ));
+        let verb = if move_data {
+            "Moved data to"
+        } else {
+            "Location set to"
+        };
+        report(
+            self.command_tx.as_ref(),
+            "Properties",
+            res,
+            format!("{verb} {location}"),
+        )
+    }
+
+    /// Rename `path` of the torrent to `name`, showing the new name right away.
+    fn rename(&mut self, path: String, name: String) -> Result<Option<Action>> {
+        let message = format!("Renamed {path} to {name}");
+        let is_root = path == self.data.name;
+        if let Err(err) = block_on(rename_path(&self.client, self.data.id, path, name.clone())) {
+            return Ok(Some(Action::error("Properties", err)));
+        }
+        if is_root {
+            self.data.name = name;
+            self.send_breadcrumbs()?;
+        }
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Notify(Level::Success, message))?;
+        }
+        Ok(Some(Action::Refresh))
+    }
+
+    pub const fn id(&self) -> i64 {
+        self.data.id
+    }
+
+    /// Show torrent `id` instead, on the same tab.
+    /// Whether the torrent was removed from the daemon.
+    pub fn is_gone(&self) -> bool {
+        self.gone
+    }
+
+    pub fn follow(&mut self, id: i64) -> Result<Option<Action>> {
+        self.data.id = id;
+        self.gone = false;
+        self.update(Action::Refresh)
+    }
+
+    fn tab_changed(&mut self) -> Result<Option<Action>> {
+        self.send_breadcrumbs()?;
+        match self.selected_tab {
+            SelectedTab::Peers => Ok(self.refresh_peers()),
+            _ => Ok(None),
+        }
+    }
+
+    fn refresh_peers(&mut self) -> Option<Action> {
+        match block_on(fetch_peers(&self.client, self.data.id)) {
+            Ok(peers) => {
+                self.peers_tab.update(peers);
+                None
+            }
+            Err(err) => Some(self.failure(err, Level::Warning)),
+        }
+    }
+
+    fn failure(&self, err: impl Display, level: Level) -> Action {
+        Action::Error(
+            Failure::new("Properties", err)
+                .level(level)
+                .torrent(self.data.id),
+        )
+    }
+
+    /// Report the torrent and the active tab to the status bar.
+    fn send_breadcrumbs(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Breadcrumbs(vec![
+                self.data.name.clone(),
+                self.selected_tab.to_string(),
+            ]))?;
+        }
+        Ok(())
+    }
+
     fn next_tab(&mut self) {
         self.selected_tab = self.selected_tab.next();
     }
@@ -152,6 +579,7 @@ impl Properties {
 
     fn next(&mut self) {
         match self.selected_tab {
+            SelectedTab::Peers => self.peers_tab.next(),
             SelectedTab::Tracker => self.tracker_tab.next(),
             SelectedTab::Files => self.files_tab.down(),
             _ => {}
@@ -160,6 +588,7 @@ impl Properties {
 
     fn previous(&mut self) {
         match self.selected_tab {
+            SelectedTab::Peers => self.peers_tab.previous(),
             SelectedTab::Tracker => self.tracker_tab.previous(),
             SelectedTab::Files => self.files_tab.up(),
             _ => {}
@@ -168,6 +597,7 @@ impl Properties {
 
     fn top(&mut self) {
         match self.selected_tab {
+            SelectedTab::Peers => self.peers_tab.top(),
             SelectedTab::Tracker => self.tracker_tab.top(),
             SelectedTab::Files => self.files_tab.top(),
             _ => {}
@@ -176,30 +606,53 @@ impl Properties {
 
     fn bottom(&mut self) {
         match self.selected_tab {
+            SelectedTab::Peers => self.peers_tab.bottom(),
             SelectedTab::Tracker => self.tracker_tab.bottom(),
             SelectedTab::Files => self.files_tab.bottom(),
             _ => {}
         }
     }
 
-    fn scroll_down(&mut self, amount: usize) {
+    /// Move down a page of the list shown, or half of one.
+    fn scroll_down(&mut self, half: bool) {
         match self.selected_tab {
-            SelectedTab::Tracker => self.tracker_tab.scroll_down(amount),
-            SelectedTab::Files => self.files_tab.scroll_down(amount),
-            _ => {}
+            SelectedTab::Peers => {
+                let amount = page_rows(self.peers_tab.rows(), half);
+                self.peers_tab.scroll_down(amount);
+            }
+            SelectedTab::Tracker => {
+                let amount = page_rows(self.tracker_tab.rows(), half);
+                self.tracker_tab.scroll_down(amount);
+            }
+            SelectedTab::Files => {
+                let amount = page_rows(self.files_tab.rows(), half);
+                self.files_tab.scroll_down(amount);
+            }
+            SelectedTab::Info => {}
         }
     }
 
-    fn scroll_up(&mut self, amount: usize) {
+    /// Move up a page of the list shown, or half of one.
+    fn scroll_up(&mut self, half: bool) {
         match self.selected_tab {
-            SelectedTab::Tracker => self.tracker_tab.scroll_up(amount),
-            SelectedTab::Files => self.files_tab.scroll_up(amount),
-            _ => {}
+            SelectedTab::Peers => {
+                let amount = page_rows(self.peers_tab.rows(), half);
+                self.peers_tab.scroll_up(amount);
+            }
+            SelectedTab::Tracker => {
+                let amount = page_rows(self.tracker_tab.rows(), half);
+                self.tracker_tab.scroll_up(amount);
+            }
+            SelectedTab::Files => {
+                let amount = page_rows(self.files_tab.rows(), half);
+                self.files_tab.scroll_up(amount);
+            }
+            SelectedTab::Info => {}
         }
     }
 
     fn render_tabs(&mut self, frame: &mut Frame, area: Rect) {
-        let titles = SelectedTab::iter().map(SelectedTab::title);
+        let titles = SelectedTab::iter().map(|tab| tab.title(&self.colors));
         let highlight_style = Style::default()
             .add_modifier(Modifier::REVERSED)
             .fg(self.colors.tab_selected);
@@ -211,13 +664,20 @@ impl Properties {
             .padding("", "")
             .divider(" ");
 
-        let rects = Layout::vertical([Constraint::Min(1), Constraint::Percentage(100)]).split(area);
+        let [_, tabs_area, content] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Percentage(100),
+        ])
+        .areas(area);
 
-        frame.render_widget(tabs, rects[0]);
+        self.tabs_area = tabs_area;
+        frame.render_widget(tabs, tabs_area);
         match self.selected_tab {
-            SelectedTab::Info => self.info_tab.render(frame, rects[1]),
-            SelectedTab::Tracker => self.tracker_tab.render(frame, rects[1]),
-            SelectedTab::Files => self.files_tab.render(frame, rects[1]),
+            SelectedTab::Info => self.info_tab.render(frame, content, &self.colors),
+            SelectedTab::Peers => self.peers_tab.render(frame, content, &self.colors),
+            SelectedTab::Tracker => self.tracker_tab.render(frame, content, &self.colors),
+            SelectedTab::Files => self.files_tab.render(frame, content, &self.colors),
         }
     }
 }
@@ -237,15 +697,46 @@ impl SelectedTab {
         Self::from_repr(next_index).unwrap_or(self)
     }
 
-    /// Return tab's name as a styled `Line`
-    fn title(self) -> Line<'static> {
+    /// The tab whose title is `x` columns from the start of the tabs, if any.
+    fn at(x: u16) -> Option<Self> {
+        let mut start = 0;
+        for tab in Self::iter() {
+            let end = start + tab.label().len();
+            if (start..end).contains(&usize::from(x)) {
+                return Some(tab);
+            }
+            // Titles are separated by a one column divider.
+            start = end + 1;
+        }
+        None
+    }
+
+    fn label(self) -> String {
         format!("  {self}  ")
-            .fg(tailwind::SLATE.c200)
-            .bg(self.colors().tab_title_bg)
+    }
+
+    /// Return tab's name as a styled `Line`
+    fn title(self, colors: &Colors) -> Line<'static> {
+        self.label()
+            .fg(colors.header_fg)
+            .bg(colors.tab_title_bg)
             .into()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    const fn colors(self) -> Colors {
-        Colors::new()
+    #[test]
+    fn test_tab_at() {
+        // "  Info  " "  Peers  " "  Tracker  " "  Files  ", a column apart.
+        assert_eq!(SelectedTab::at(0), Some(SelectedTab::Info));
+        assert_eq!(SelectedTab::at(7), Some(SelectedTab::Info));
+        assert_eq!(SelectedTab::at(8), None);
+        assert_eq!(SelectedTab::at(9), Some(SelectedTab::Peers));
+        assert_eq!(SelectedTab::at(19), Some(SelectedTab::Tracker));
+        assert_eq!(SelectedTab::at(38), Some(SelectedTab::Files));
+        assert_eq!(SelectedTab::at(40), None);
     }
 }
@@ -1,18 +1,22 @@
 use color_eyre::Result;
 use ratatui::{
     layout::{Constraint, Layout, Rect},
-    style::{Modifier, Style},
-    widgets::Block,
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Paragraph},
     Frame,
 };
 use tui_tree_widget::{Tree, TreeItem, TreeState};
 
-use crate::{app, colors::Colors, data};
+use crate::{app, colors::Colors, data, utils::convert_bytes};
 
 pub struct Tab {
     data: data::Torrent,
     state: TreeState<String>,
-    colors: Colors,
+    /// Only the files whose name contains it are listed, all of them if empty.
+    query: String,
+    /// Rows shown at the last draw, to scroll by pages.
+    rows: usize,
 }
 
 impl Tab {
@@ -20,10 +24,61 @@ impl Tab {
         Self {
             data: data.clone(),
             state: TreeState::default(),
-            colors: Colors::new(),
+            query: String::new(),
+            rows: 0,
         }
     }
 
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// List only the files whose name contains `query`, opening their directories and selecting
+    /// the first one.
+    pub fn search(&mut self, query: String) {
+        self.query = query;
+        let matches = self.matches();
+        for path in &matches {
+            for depth in 1..path.len() {
+                self.state.open(path[..depth].to_vec());
+            }
+        }
+        if let Some(first) = matches.into_iter().next() {
+            self.state.select(first);
+        }
+    }
+
+    /// Select the match after the selected file, or before it, going round at the ends.
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let matches = self.matches();
+        if matches.is_empty() {
+            return;
+        }
+        let selected = self.state.selected();
+        let next = match (matches.iter().position(|path| *path == selected), forward) {
+            (Some(i), true) => (i + 1) % matches.len(),
+            (Some(i), false) => (i + matches.len() - 1) % matches.len(),
+            (None, true) => 0,
+            (None, false) => matches.len() - 1,
+        };
+        self.state.select(matches[next].clone());
+    }
+
+    /// Paths in the tree of the files matching the query, in the order they're listed.
+    fn matches(&self) -> Vec<Vec<String>> {
+        if self.query.is_empty() {
+            return Vec::new();
+        }
+        leaf_paths(
+            &parse_node(&matching_files(&self.data.files, &self.query)),
+            &[],
+        )
+    }
+
+    pub fn update(&mut self, data: &data::Torrent) {
+        self.data = data.clone();
+    }
+
     pub fn down(&mut self) {
         self.state.key_down();
     }
@@ -31,11 +86,17 @@ impl Tab {
         self.state.key_up();
     }
 
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
     pub fn scroll_up(&mut self, amount: usize) {
-        self.state.scroll_up(amount);
+        self.state
+            .select_relative(|current| current.map_or(0, |i| i.saturating_sub(amount)));
     }
     pub fn scroll_down(&mut self, amount: usize) {
-        self.state.scroll_up(amount);
+        self.state
+            .select_relative(|current| current.map_or(0, |i| i.saturating_add(amount)));
     }
 
     pub fn top(&mut self) {
@@ -49,39 +110,182 @@ impl Tab {
         self.state.toggle_selected();
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+    /// Path of the selected file or directory in the torrent.
+    pub fn selected_path(&self) -> Option<String> {
+        let path = self.state.selected().join("/");
+        (!path.is_empty()).then_some(path)
+    }
+
+    /// Indices of the selected file, or of every file in the selected directory.
+    pub fn selected_files(&self) -> Vec<usize> {
+        let path = self.selected_path().unwrap_or_default();
+        files_under(&self.data.files, &path)
+    }
+
+    /// Show the files at `indices` as wanted or not, until the next refresh confirms it.
+    pub fn set_wanted(&mut self, indices: &[usize], wanted: bool) {
+        for &i in indices {
+            if let Some(file) = self.data.files.get_mut(i) {
+                file.wanted = wanted;
+            }
+        }
+    }
+
+    /// Raise or lower the priority of the files at `indices` a step, returning the new ones.
+    pub fn step_priorities(
+        &mut self,
+        indices: &[usize],
+        raise: bool,
+    ) -> Vec<(usize, &'static str)> {
+        indices
+            .iter()
+            .filter_map(|&i| {
+                let file = self.data.files.get_mut(i)?;
+                let priority = step_priority(&file.priority, raise);
+                file.priority = priority.to_string();
+                Some((i, priority))
+            })
+            .collect()
+    }
+
+    pub fn all_wanted(&self, indices: &[usize]) -> bool {
+        indices
+            .iter()
+            .filter_map(|&i| self.data.files.get(i))
+            .all(|f| f.wanted)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, colors: &Colors) {
         let rects = Layout::vertical([Constraint::Min(5), Constraint::Length(3)]).split(area);
-        let file_style = Style::default()
-            .fg(self.colors.row_fg)
-            .bg(self.colors.buffer_bg);
-        let border_style = Style::default().fg(self.colors.footer_border_color);
+        let file_style = Style::default().fg(colors.row_fg).bg(colors.buffer_bg);
+        let border_style = Style::default().fg(colors.footer_border_color);
         let selected_style = Style::default()
             .add_modifier(Modifier::REVERSED)
-            .fg(self.colors.selected_style_fg);
-
-        let items = map_node(&parse_node(
-            self.data
-                .files
-                .iter()
-                .map(|f| {
-                    format!(
-                        "{}\n{}\n{}\n{}\n{}",
-                        f.name, f.downloaded, f.total_size, f.priority, f.wanted
-                    )
-                })
-                .collect(),
-        ));
+            .fg(colors.selected_style_fg);
+
+        let files = matching_files(&self.data.files, &self.query);
+        let items = map_node(&parse_node(&files));
+        let mut block = Block::bordered().border_style(border_style);
+        if !self.query.is_empty() {
+            let title = format!(
+                " {} matching \"{}\" · n/N: next/previous ",
+                files.len(),
+                self.query
+            );
+            block = block.title(Line::from(title).fg(colors.row_fg));
+        }
 
         let tree = Tree::new(&items)
             .expect("unique identifier")
             .style(file_style)
             .highlight_style(selected_style)
-            .block(Block::bordered().border_style(border_style));
+            .block(block);
 
+        self.rows = usize::from(rects[0].height.saturating_sub(2));
         frame.render_stateful_widget(tree, rects[0], &mut self.state);
+
+        let summary = WantedSummary::new(&self.data.files);
+        let text = format!(
+            "Wanted: {} of {} files · {} · {} left to download",
+            summary.files,
+            self.data.files.len(),
+            convert_bytes(summary.size),
+            convert_bytes(summary.left),
+        );
+        frame.render_widget(
+            Paragraph::new(text)
+                .style(file_style)
+                .block(Block::bordered().border_style(border_style)),
+            rects[1],
+        );
+    }
+}
+
+/// The size of the wanted files and how much of it is still to be downloaded.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct WantedSummary {
+    files: usize,
+    size: i64,
+    left: i64,
+}
+
+impl WantedSummary {
+    fn new(files: &[data::Files]) -> Self {
+        files
+            .iter()
+            .filter(|f| f.wanted)
+            .fold(Self::default(), |summary, f| Self {
+                files: summary.files + 1,
+                size: summary.size + f.length,
+                left: summary.left + (f.length - f.bytes_completed).max(0),
+            })
     }
 }
 
+/// Priorities from lowest to highest, as shown in the tree.
+pub const PRIORITIES: [&str; 3] = ["Low", "Normal", "High"];
+
+/// The priority a step above or below `priority`, staying within Low and High.
+fn step_priority(priority: &str, raise: bool) -> &'static str {
+    let index = PRIORITIES.iter().position(|p| *p == priority).unwrap_or(1);
+    let index = match raise {
+        true => (index + 1).min(PRIORITIES.len() - 1),
+        false => index.saturating_sub(1),
+    };
+    PRIORITIES[index]
+}
+
+/// The files whose name, without their directories, contains `query` ignoring case, all of
+/// them if it's empty.
+fn matching_files(files: &[data::Files], query: &str) -> Vec<data::Files> {
+    let query = query.to_lowercase();
+    files
+        .iter()
+        .filter(|file| {
+            let name = file.name.rsplit('/').next().unwrap_or_default();
+            name.to_lowercase().contains(&query)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Paths of the files of the tree under `parent`, from top to bottom.
+fn leaf_paths(nodes: &[Node], parent: &[String]) -> Vec<Vec<String>> {
+    nodes
+        .iter()
+        .flat_map(|node| {
+            let mut path = parent.to_vec();
+            match node {
+                Node::File(file) => {
+                    path.push(file.name.clone());
+                    vec![path]
+                }
+                Node::Directory(name, children) => {
+                    path.push(name.clone());
+                    leaf_paths(children, &path)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Indices of the files at `path` or below it.
+fn files_under(files: &[data::Files], path: &str) -> Vec<usize> {
+    if path.is_empty() {
+        return Vec::new();
+    }
+    files
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| {
+            f.name
+                .strip_prefix(path)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
 fn map_node(nodes: &[Node]) -> Vec<TreeItem<'static, String>> {
     nodes
         .iter()
@@ -92,6 +296,7 @@ fn map_node(nodes: &[Node]) -> Vec<TreeItem<'static, String>> {
                 total_size,
                 priority,
                 wanted,
+                ..
             }) => TreeItem::new_leaf(
                 name.to_string(),
                 format!(
@@ -113,21 +318,15 @@ enum Node {
     Directory(String, Vec<Node>),
 }
 
-fn parse_node(paths: Vec<String>) -> Vec<Node> {
+fn parse_node(files: &[data::Files]) -> Vec<Node> {
     let mut nodes: Vec<Node> = Vec::new();
-    for path in paths {
-        let vecs = path.lines().collect::<Vec<&str>>();
-        let parts = vecs.first().unwrap().split('/').collect::<Vec<&str>>();
+    for file in files {
+        let parts = file.name.split('/').collect::<Vec<&str>>();
         let data = data::Files {
-            name: parts.last().unwrap().to_string(),
-            downloaded: vecs.get(1).unwrap().parse().unwrap(),
-            total_size: vecs.get(2).unwrap().parse().unwrap(),
-            priority: vecs.get(3).unwrap().to_string(),
-            wanted: vecs.last().unwrap().parse().unwrap(),
+            name: parts.last().unwrap_or(&"").to_string(),
+            ..file.clone()
         };
-        if !parts.is_empty() {
-            let _ = insert_into_tree(&mut nodes, &parts, data);
-        }
+        let _ = insert_into_tree(&mut nodes, &parts, data);
     }
 
     nodes
@@ -160,3 +359,147 @@ fn insert_into_tree(children: &mut Vec<Node>, parts: &[&str], data: data::Files)
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        data::fixtures::torrent,
+    };
+
+    #[test]
+    fn test_files_under() {
+        let files = [
+            "show/s01/e01.mkv",
+            "show/s01/e02.mkv",
+            "show/s010.nfo",
+            "readme.txt",
+        ]
+        .map(|name| data::Files {
+            name: name.to_string(),
+            downloaded: String::new(),
+            total_size: String::new(),
+            priority: String::new(),
+            wanted: true,
+            bytes_completed: 0,
+            length: 0,
+        });
+        assert_eq!(files_under(&files, "show/s01"), [0, 1]);
+        assert_eq!(files_under(&files, "show"), [0, 1, 2]);
+        assert_eq!(files_under(&files, "readme.txt"), [3]);
+        assert!(files_under(&files, "").is_empty());
+    }
+
+    #[test]
+    fn test_search() {
+        let mut data = torrent(1002, true);
+        data.files = [
+            "show/s01/e01.mkv",
+            "extras/e01.nfo",
+            "show/s02/e01.mkv",
+            "show/notes.txt",
+        ]
+        .map(|name| data::Files {
+            name: name.to_string(),
+            downloaded: String::new(),
+            total_size: String::new(),
+            priority: String::new(),
+            wanted: true,
+            bytes_completed: 0,
+            length: 0,
+        })
+        .to_vec();
+        let mut tab = Tab::new(&data);
+        // Directories don't match, only file names do.
+        assert_eq!(matching_files(&data.files, "SHOW").len(), 0);
+        tab.search("E01".to_string());
+        assert_eq!(tab.selected_path().as_deref(), Some("show/s01/e01.mkv"));
+        // In the order of the tree, where directories group their files.
+        tab.jump_to_match(true);
+        assert_eq!(tab.selected_path().as_deref(), Some("show/s02/e01.mkv"));
+        tab.jump_to_match(true);
+        assert_eq!(tab.selected_path().as_deref(), Some("extras/e01.nfo"));
+        tab.jump_to_match(true);
+        assert_eq!(tab.selected_path().as_deref(), Some("show/s01/e01.mkv"));
+        tab.jump_to_match(false);
+        assert_eq!(tab.selected_path().as_deref(), Some("extras/e01.nfo"));
+        assert!(tab
+            .state
+            .opened()
+            .contains(&vec!["show".to_string(), "s02".to_string()]));
+    }
+
+    #[test]
+    fn test_page_scroll() {
+        let mut data = torrent(1003, true);
+        data.files = (0..30)
+            .map(|i| data::Files {
+                name: format!("f{i:02}"),
+                downloaded: String::new(),
+                total_size: String::new(),
+                priority: String::new(),
+                wanted: true,
+                bytes_completed: 0,
+                length: 0,
+            })
+            .collect();
+        let mut tab = Tab::new(&data);
+        let mut terminal = Terminal::new(TestBackend::new(40, 15)).unwrap();
+        terminal
+            .draw(|frame| tab.render(frame, frame.area(), &Colors::new()))
+            .unwrap();
+        // The 15 lines less the summary and the borders of the tree.
+        assert_eq!(tab.rows(), 10);
+        tab.top();
+        tab.scroll_down(tab.rows());
+        assert_eq!(tab.selected_path().as_deref(), Some("f10"));
+        tab.scroll_down(100);
+        assert_eq!(tab.selected_path().as_deref(), Some("f29"));
+        tab.scroll_up(5);
+        assert_eq!(tab.selected_path().as_deref(), Some("f24"));
+    }
+
+    #[test]
+    fn test_wanted_summary() {
+        let file = |length, bytes_completed, wanted| data::Files {
+            name: String::new(),
+            downloaded: String::new(),
+            total_size: String::new(),
+            priority: String::new(),
+            wanted,
+            bytes_completed,
+            length,
+        };
+        let files = [file(100, 40, true), file(50, 50, true), file(300, 0, false)];
+        assert_eq!(
+            WantedSummary::new(&files),
+            WantedSummary {
+                files: 2,
+                size: 150,
+                left: 60
+            }
+        );
+    }
+
+    #[test]
+    fn test_step_priority() {
+        assert_eq!(step_priority("Low", true), "Normal");
+        assert_eq!(step_priority("Normal", true), "High");
+        assert_eq!(step_priority("High", true), "High");
+        assert_eq!(step_priority("Normal", false), "Low");
+        assert_eq!(step_priority("Low", false), "Low");
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut tab = Tab::new(&torrent(1002, true));
+        for size in SIZES {
+            assert_snapshot("properties_files", size, |frame| {
+                tab.render(frame, frame.area(), &Colors::new());
+            });
+        }
+    }
+}
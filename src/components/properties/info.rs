@@ -1,74 +1,211 @@
+use chrono::{DateTime, Utc};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
-    style::{Style, Stylize},
-    text::{Line, Text},
-    widgets::{Block, Paragraph},
+    style::{Color, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, Paragraph, Sparkline},
     Frame,
 };
 
-use crate::{colors::Colors, data};
+use crate::{
+    colors::Colors,
+    config::FreeSpaceConfig,
+    data,
+    history::{torrent_rates, Rates},
+    utils::{convert_bytes, convert_status, locale},
+};
 
 pub struct Tab {
     data: data::Torrent,
-    colors: Colors,
+    /// Bytes available in the torrent's location, if the daemon tells.
+    free_space: Option<i64>,
+    free_space_config: FreeSpaceConfig,
+    /// Rates of the torrent over the last ticks.
+    rates: Rates,
+    /// Show the exact numbers instead of formatted values.
+    raw: bool,
 }
 
 impl Tab {
     pub fn new(data: &data::Torrent) -> Self {
         Self {
             data: data.clone(),
-            colors: Colors::new(),
+            free_space: None,
+            free_space_config: FreeSpaceConfig::default(),
+            rates: torrent_rates(data.id),
+            raw: false,
+        }
+    }
+
+    pub fn update(&mut self, data: &data::Torrent, free_space: Option<i64>) {
+        self.data = data.clone();
+        self.free_space = free_space;
+        self.rates = torrent_rates(data.id);
+    }
+
+    pub fn set_free_space_config(&mut self, config: FreeSpaceConfig) {
+        self.free_space_config = config;
+    }
+
+    pub fn set_raw(&mut self, raw: bool) {
+        self.raw = raw;
+    }
+
+    fn bytes(&self, bytes: i64) -> String {
+        match self.raw {
+            true => bytes.to_string(),
+            false => convert_bytes(bytes),
+        }
+    }
+
+    fn date(&self, date: DateTime<Utc>) -> String {
+        match self.raw {
+            true => date.timestamp().to_string(),
+            false => locale().date(date),
         }
     }
 
-    pub fn render(&self, frame: &mut Frame, area: Rect) {
+    fn location_line(&self, colors: &Colors) -> Line<'static> {
+        let mut line = Line::from(format!("Location: {}", self.data.location));
+        if let Some(free) = self.free_space {
+            let text = format!(" ({} free)", self.bytes(free));
+            line.push_span(match self.free_space_config.is_low(free) {
+                true => Span::styled(text, Style::default().fg(colors.error_fg)),
+                false => Span::from(text),
+            });
+        }
+        line
+    }
+
+    /// The rates of the last ticks, as many as fit, with the current and the peak one.
+    fn render_speeds(&self, frame: &mut Frame, area: Rect, colors: &Colors) {
+        let block = Block::bordered()
+            .border_style(Style::default().fg(colors.footer_border_color))
+            .title("Speed".bold().fg(colors.row_fg))
+            .style(Style::default().bg(colors.buffer_bg));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let [down_label, down, up_label, up] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        let width = usize::from(inner.width);
+        let mut graph = |label: &str, samples: Vec<i64>, color: Color, areas: [Rect; 2]| {
+            let current = samples.last().copied().unwrap_or_default();
+            let peak = samples.iter().copied().max().unwrap_or_default();
+            let text = format!(
+                "{label}: {}/s · peak {}/s",
+                convert_bytes(current),
+                convert_bytes(peak)
+            );
+            let samples = samples
+                .iter()
+                .map(|&rate| u64::try_from(rate).unwrap_or_default())
+                .collect::<Vec<_>>();
+            frame.render_widget(Line::from(text).fg(colors.row_fg), areas[0]);
+            frame.render_widget(
+                Sparkline::default()
+                    .data(&samples)
+                    .style(Style::default().fg(color)),
+                areas[1],
+            );
+        };
+        graph(
+            "Down",
+            self.rates.down(width),
+            colors.selected_style_fg,
+            [down_label, down],
+        );
+        graph(
+            "Up",
+            self.rates.up(width),
+            colors.warning_fg,
+            [up_label, up],
+        );
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, colors: &Colors) {
         let rect = Layout::vertical([
             Constraint::Min(5),
             Constraint::Min(5),
-            Constraint::Length(3),
+            Constraint::Length(8),
         ])
         .split(area);
 
+        let data = &self.data;
+        let (percent, ratio, eta) = match self.raw {
+            true => (
+                data.percent.to_string(),
+                data.upload_ratio.to_string(),
+                data.eta_secs.to_string(),
+            ),
+            false => (data.percent_done(), data.ratio(), data.eta()),
+        };
         let activity = vec![
             Line::from(format!(
-                "Have: {} of {} ({})",
-                self.data.downloaded, self.data.size_done, self.data.percent_done,
+                "Have: {} of {} ({percent})",
+                self.bytes(data.have_bytes),
+                self.bytes(data.have_bytes + data.left_bytes),
             )),
             Line::from(format!(
-                "Uploaded: {} (Ratio: {})",
-                self.data.uploaded, self.data.ratio
+                "Uploaded: {} (Ratio: {ratio})",
+                self.bytes(data.uploaded_bytes)
             )),
-            Line::from(format!("Downloaded: {}", self.data.downloaded,)),
-            Line::from(format!("Remaining Time: {}", self.data.eta)),
-            Line::from(format!("State: {}", self.data.status)),
+            Line::from(format!("Downloaded: {}", self.bytes(data.have_bytes))),
+            Line::from(format!("Remaining Time: {eta}")),
+            Line::from(format!("State: {}", convert_status(self.data.status))),
             Line::from(format!("Error: {}", self.data.error)),
         ];
         let details = vec![
             Line::from(format!("Name: {}", self.data.name)),
-            Line::from(format!("Size: {}", self.data.total_size)),
-            Line::from(format!("Location: {}", self.data.location)),
+            Line::from(format!("Size: {}", self.bytes(self.data.total_bytes))),
+            self.location_line(colors),
+            Line::from(format!("Labels: {}", self.data.labels.join(", "))),
             Line::from(format!("Hash: {}", self.data.hash)),
-            Line::from(format!("Added: {}", self.data.added_date)),
-            Line::from(format!("Done: {}", self.data.done_date)),
+            Line::from(format!("Added: {}", self.date(self.data.added_date))),
+            Line::from(format!("Done: {}", self.date(self.data.done_date))),
         ];
 
-        let par_style = Style::default()
-            .fg(self.colors.row_fg)
-            .bg(self.colors.buffer_bg);
-        let border_style = Style::default().fg(self.colors.footer_border_color);
+        let par_style = Style::default().fg(colors.row_fg).bg(colors.buffer_bg);
+        let border_style = Style::default().fg(colors.footer_border_color);
 
         let activity_par = Paragraph::new(Text::from(activity)).style(par_style).block(
             Block::bordered()
                 .border_style(border_style)
-                .title("Activity".bold().white()),
+                .title("Activity".bold().fg(colors.row_fg)),
         );
         let details_par = Paragraph::new(Text::from(details)).style(par_style).block(
             Block::bordered()
                 .border_style(border_style)
-                .title("Details".bold().white()),
+                .title("Details".bold().fg(colors.row_fg)),
         );
 
         frame.render_widget(activity_par, rect[0]);
         frame.render_widget(details_par, rect[1]);
+        self.render_speeds(frame, rect[2], colors);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        data::fixtures::torrent,
+    };
+
+    /// In exact numbers, which keeps dates out of the local time zone.
+    #[test]
+    fn test_snapshot() {
+        let mut tab = Tab::new(&torrent(1001, false));
+        tab.set_raw(true);
+        for size in SIZES {
+            assert_snapshot("properties_info", size, |frame| {
+                tab.render(frame, frame.area(), &Colors::new());
+            });
+        }
     }
 }
@@ -1,18 +1,269 @@
-#![allow(dead_code)]
-use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+use std::collections::{HashMap, VecDeque};
 
-use crate::data;
+use ratatui::{
+    layout::{Constraint, Margin, Rect},
+    style::{Modifier, Style, Stylize},
+    widgets::{
+        Block, HighlightSpacing, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table,
+        TableState,
+    },
+    Frame,
+};
+
+use crate::{
+    colors::Colors,
+    data::Peer,
+    utils::{convert_bytes, convert_percentage, sparkline},
+};
+
+/// Number of rate samples kept per peer.
+const SAMPLES: usize = 20;
+
+/// Recent download rates of each peer, oldest first.
+#[derive(Default)]
+struct RateHistory {
+    rates: HashMap<String, VecDeque<i64>>,
+}
+
+impl RateHistory {
+    /// Add the current rate of every peer, forgetting the ones that disconnected.
+    fn record(&mut self, peers: &[Peer]) {
+        let mut rates = HashMap::with_capacity(peers.len());
+        for peer in peers {
+            let endpoint = peer.endpoint();
+            let mut samples = self.rates.remove(&endpoint).unwrap_or_default();
+            if samples.len() == SAMPLES {
+                samples.pop_front();
+            }
+            samples.push_back(peer.rate_to_client);
+            rates.insert(endpoint, samples);
+        }
+        self.rates = rates;
+    }
+
+    fn get(&self, endpoint: &str) -> Vec<i64> {
+        self.rates
+            .get(endpoint)
+            .map(|samples| samples.iter().copied().collect())
+            .unwrap_or_default()
+    }
+}
 
 pub struct Tab {
-    data: data::Torrent,
+    peers: Vec<Peer>,
+    history: RateHistory,
+    /// Sample rates to draw a sparkline per peer.
+    sparklines: bool,
+    state: TableState,
+    scroll_state: ScrollbarState,
+    /// Peers shown at the last draw, to scroll by pages.
+    rows: usize,
 }
 
 impl Tab {
-    pub fn new(data: &data::Torrent) -> Self {
-        Self { data: data.clone() }
+    pub fn new() -> Self {
+        Self {
+            peers: Vec::new(),
+            history: RateHistory::default(),
+            sparklines: false,
+            state: TableState::default().with_selected(Some(0)),
+            scroll_state: ScrollbarState::new(0),
+            rows: 0,
+        }
+    }
+
+    pub fn show_sparklines(&mut self, sparklines: bool) {
+        self.sparklines = sparklines;
+    }
+
+    pub const fn sparklines(&self) -> bool {
+        self.sparklines
+    }
+
+    pub fn update(&mut self, peers: Vec<Peer>) {
+        if self.sparklines {
+            self.history.record(&peers);
+        }
+        // Keep the selection on the same peer as the list changes.
+        let selected = self
+            .state
+            .selected()
+            .and_then(|i| self.peers.get(i))
+            .map(Peer::endpoint);
+        self.peers = peers;
+        let index = selected
+            .and_then(|endpoint| self.peers.iter().position(|p| p.endpoint() == endpoint))
+            .unwrap_or(0)
+            .min(self.peers.len().saturating_sub(1));
+        self.select(index);
+        self.scroll_state = self.scroll_state.content_length(self.peers.len());
+    }
+
+    fn select(&mut self, index: usize) {
+        self.state.select(Some(index));
+        self.scroll_state = self.scroll_state.position(index);
+    }
+
+    pub fn next(&mut self) {
+        if self.peers.is_empty() {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(i) if i < self.peers.len() - 1 => i + 1,
+            _ => 0,
+        };
+        self.select(i);
+    }
+
+    pub fn previous(&mut self) {
+        if self.peers.is_empty() {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(0) | None => self.peers.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.select(i);
+    }
+
+    pub fn top(&mut self) {
+        self.select(0);
+    }
+
+    pub fn bottom(&mut self) {
+        self.select(self.peers.len().saturating_sub(1));
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.select(self.state.selected().unwrap_or(0).saturating_sub(amount));
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        let last = self.peers.len().saturating_sub(1);
+        self.select((self.state.selected().unwrap_or(0) + amount).min(last));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, colors: &Colors) {
+        let border_style = Style::default().fg(colors.footer_border_color);
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(colors.selected_style_fg);
+        let header_style = Style::default().fg(colors.header_fg).bg(colors.header_bg);
+
+        let mut header = vec!["Address", "Client", "Flags", "Progress", "Down", "Up"];
+        let mut widths = vec![
+            Constraint::Min(22),
+            Constraint::Min(16),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ];
+        if self.sparklines {
+            header.push("Activity");
+            #[allow(clippy::cast_possible_truncation)]
+            widths.push(Constraint::Length(SAMPLES as u16));
+        }
+
+        let rows = self.peers.iter().enumerate().map(|(i, peer)| {
+            let color = match i % 2 {
+                0 => colors.normal_row_color,
+                _ => colors.alt_row_color,
+            };
+            let mut cells = vec![
+                peer.endpoint(),
+                peer.client_name.clone(),
+                peer.flag_str.clone(),
+                convert_percentage(peer.progress),
+                format!("{}/s", convert_bytes(peer.rate_to_client)),
+                format!("{}/s", convert_bytes(peer.rate_to_peer)),
+            ];
+            if self.sparklines {
+                cells.push(sparkline(&self.history.get(&peer.endpoint())));
+            }
+            Row::new(cells).style(Style::new().fg(colors.row_fg).bg(color))
+        });
+        let table = Table::new(rows, widths)
+            .header(Row::new(header).style(header_style))
+            .highlight_style(selected_style)
+            .highlight_spacing(HighlightSpacing::Always)
+            .bg(colors.buffer_bg)
+            .block(Block::bordered().border_style(border_style));
+
+        // Borders and header.
+        self.rows = usize::from(area.height.saturating_sub(3));
+        frame.render_stateful_widget(table, area, &mut self.state);
+        frame.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            &mut self.scroll_state,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    fn peer(address: &str, rate: i64) -> Peer {
+        Peer {
+            address: address.to_string(),
+            port: 51413,
+            client_name: String::new(),
+            flag_str: String::new(),
+            progress: 0.0,
+            rate_to_client: rate,
+            rate_to_peer: 0,
+        }
+    }
+
+    #[test]
+    fn test_rate_history() {
+        let mut history = RateHistory::default();
+        for rate in 0..25 {
+            history.record(&[peer("a", rate), peer("b", rate)]);
+        }
+        history.record(&[peer("a", 100)]);
+        let a = history.get("a:51413");
+        assert_eq!(a.len(), SAMPLES);
+        assert_eq!(a.last(), Some(&100));
+        assert!(history.get("b:51413").is_empty());
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
-        frame.render_widget(Paragraph::new("Under Construction"), area);
+    // Only the address and the client fit on the small terminal.
+    #[test]
+    fn test_snapshot() {
+        let mut tab = Tab::new();
+        tab.update(vec![
+            Peer {
+                client_name: "Transmission 4.0.5".to_string(),
+                flag_str: "TDEI".to_string(),
+                progress: 1.0,
+                ..peer("203.0.113.7", 1_258_291)
+            },
+            Peer {
+                client_name: "qBittorrent 4.6.2".to_string(),
+                flag_str: "UXI".to_string(),
+                progress: 0.42,
+                rate_to_peer: 65_536,
+                ..peer("2001:db8::1", 0)
+            },
+        ]);
+        for size in SIZES {
+            assert_snapshot("properties_peers", size, |frame| {
+                tab.render(frame, frame.area(), &Colors::new());
+            });
+        }
     }
 }
@@ -9,28 +9,56 @@ use ratatui::{
     Frame,
 };
 
-use crate::{colors::Colors, data};
+use crate::{
+    colors::Colors,
+    data,
+    utils::{redact_url, redact_urls},
+};
 
-const ITEM_HEIGHT: usize = 4;
+const ITEM_HEIGHT: usize = 5;
 
 pub struct Tab {
     data: data::Torrent,
-    colors: Colors,
     state: ListState,
     scroll_state: ScrollbarState,
+    /// Show announce URLs with their passkeys.
+    full_urls: bool,
+    /// Trackers shown at the last draw, to scroll by pages.
+    rows: usize,
 }
 
 impl Tab {
     pub fn new(data: &data::Torrent) -> Self {
         Self {
             data: data.clone(),
-            colors: Colors::new(),
             state: ListState::default().with_selected(Some(0)),
             scroll_state: ScrollbarState::new((data.trackers.len()) * ITEM_HEIGHT),
+            full_urls: false,
+            rows: 0,
         }
     }
 
+    pub fn show_full_urls(&mut self, full_urls: bool) {
+        self.full_urls = full_urls;
+    }
+
+    pub fn update(&mut self, data: &data::Torrent) {
+        self.data = data.clone();
+        self.scroll_state = self
+            .scroll_state
+            .content_length(self.data.trackers.len() * ITEM_HEIGHT);
+    }
+
+    pub fn selected(&self) -> Option<&data::Tracker> {
+        self.state
+            .selected()
+            .and_then(|i| self.data.trackers.get(i))
+    }
+
     pub fn next(&mut self) {
+        if self.data.trackers.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.data.trackers.len() - 1 {
@@ -46,6 +74,9 @@ impl Tab {
     }
 
     pub fn previous(&mut self) {
+        if self.data.trackers.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -70,31 +101,35 @@ impl Tab {
         self.scroll_state.last();
     }
 
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
     pub fn scroll_up(&mut self, amount: usize) {
         self.state
-            .scroll_up_by(u16::try_from(amount).expect("failed to parse"));
+            .scroll_up_by(u16::try_from(amount).unwrap_or(u16::MAX));
         self.scroll_state = self
             .scroll_state
-            .position(self.state.selected().unwrap_or(0) * amount);
+            .position(self.state.selected().unwrap_or(0) * ITEM_HEIGHT);
     }
 
     pub fn scroll_down(&mut self, amount: usize) {
+        let last = self.data.trackers.len().saturating_sub(1);
         self.state
-            .scroll_down_by(u16::try_from(amount).expect("failed to parse"));
-        self.scroll_state = self
-            .scroll_state
-            .position(self.state.selected().unwrap_or(0) * amount);
+            .scroll_down_by(u16::try_from(amount).unwrap_or(u16::MAX));
+        // The list only clamps the selection once drawn.
+        let selected = self.state.selected().unwrap_or(0).min(last);
+        self.state.select(Some(selected));
+        self.scroll_state = self.scroll_state.position(selected * ITEM_HEIGHT);
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, colors: &Colors) {
         let rects = Layout::vertical([Constraint::Min(5), Constraint::Length(3)]).split(area);
-        let list_style = Style::default()
-            .fg(self.colors.row_fg)
-            .bg(self.colors.buffer_bg);
-        let border_style = Style::default().fg(self.colors.footer_border_color);
+        let list_style = Style::default().fg(colors.row_fg).bg(colors.buffer_bg);
+        let border_style = Style::default().fg(colors.footer_border_color);
         let selected_style = Style::default()
             .add_modifier(Modifier::REVERSED)
-            .fg(self.colors.selected_style_fg);
+            .fg(colors.selected_style_fg);
 
         let items = self
             .data
@@ -102,20 +137,46 @@ impl Tab {
             .iter()
             .enumerate()
             .map(|(i, tracker)| {
-                let host = Line::raw(tracker.host.to_string());
-                let update = Line::raw(tracker.next_announce.to_string());
+                let url = match self.full_urls {
+                    true => tracker.announce.clone(),
+                    false => redact_url(&tracker.announce),
+                };
+                let host = Line::from(vec![
+                    tracker.host.as_str().bold(),
+                    format!("  {url}").gray(),
+                ]);
+                let update = Line::raw(format!(
+                    "Next announce: {}  Next scrape: {}",
+                    tracker.next_announce, tracker.next_scrape
+                ));
+                let mut scrape = format!(
+                    "Seeders: {}  Leechers: {}  Completed: {}",
+                    scrape_count(tracker.seeders),
+                    scrape_count(tracker.leechers),
+                    scrape_count(tracker.downloads)
+                );
+                if !tracker.last_scrape_result.is_empty() {
+                    let result = redact_urls(&tracker.last_scrape_result);
+                    scrape.push_str(&format!("  ({result})"));
+                }
 
                 let color = match i % 2 {
-                    0 => self.colors.normal_row_color,
-                    _ => self.colors.alt_row_color,
+                    0 => colors.normal_row_color,
+                    _ => colors.alt_row_color,
                 };
 
                 if tracker.is_backup {
-                    Text::from(vec![host.gray(), Line::raw("")])
+                    Text::from(vec![host.patch_style(Style::new().gray()), Line::raw("")])
                 } else {
-                    Text::from(vec![Line::raw(""), host.bold(), update, Line::raw("")])
+                    Text::from(vec![
+                        Line::raw(""),
+                        host,
+                        update,
+                        Line::raw(scrape),
+                        Line::raw(""),
+                    ])
                 }
-                .style(Style::new().fg(self.colors.row_fg).bg(color))
+                .style(Style::new().fg(colors.row_fg).bg(color))
             })
             .collect_vec();
         let list = List::new(items)
@@ -124,6 +185,7 @@ impl Tab {
             .style(list_style)
             .block(Block::bordered().border_style(border_style));
 
+        self.rows = usize::from(rects[0].height.saturating_sub(2)) / ITEM_HEIGHT;
         frame.render_stateful_widget(list, rects[0], &mut self.state);
         frame.render_stateful_widget(
             Scrollbar::default()
@@ -138,3 +200,54 @@ impl Tab {
         );
     }
 }
+
+/// Trackers report -1 for counts they haven't scraped.
+fn scrape_count(count: i64) -> String {
+    if count < 0 {
+        "?".to_string()
+    } else {
+        count.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        data::fixtures::torrent,
+    };
+
+    fn tracker(id: i64, host: &str, is_backup: bool) -> data::Tracker {
+        data::Tracker {
+            id,
+            host: host.to_string(),
+            announce: format!("https://{host}/announce/0123456789abcdef0123"),
+            is_backup,
+            next_announce: DateTime::from_timestamp(1_700_001_800, 0).unwrap(),
+            next_scrape: DateTime::from_timestamp(1_700_000_900, 0).unwrap(),
+            last_scrape_result: String::new(),
+            seeders: 12,
+            leechers: -1,
+            downloads: 340,
+        }
+    }
+
+    /// The passkeys stay redacted, as they're shown by default.
+    #[test]
+    fn test_snapshot() {
+        let mut data = torrent(1, false);
+        data.trackers = vec![
+            tracker(0, "tracker.example", false),
+            tracker(1, "backup.example", true),
+        ];
+        let mut tab = Tab::new(&data);
+        for size in SIZES {
+            assert_snapshot("properties_trackers", size, |frame| {
+                tab.render(frame, frame.area(), &Colors::new());
+            });
+        }
+    }
+}
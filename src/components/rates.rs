@@ -0,0 +1,190 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors};
+
+use super::{centered_rect, input::Input, Component};
+
+const DIALOG_WIDTH: u16 = 44;
+const FIELD_HEIGHT: u16 = 3;
+
+/// Shortest refresh interval and highest frame rate accepted, to keep the daemon and the
+/// terminal responsive.
+const MIN_INTERVAL_MS: u64 = 250;
+const MAX_FRAME_RATE: u32 = 120;
+
+/// A popup changing how often the torrents are refreshed and the screen drawn.
+pub struct RatesDialog {
+    /// The refresh interval in milliseconds, then the frames per second.
+    inputs: [Input; 2],
+    focus: usize,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl RatesDialog {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn new(tick_rate: f64, frame_rate: f64) -> Self {
+        let interval = (1000.0 / tick_rate).round() as u64;
+        Self {
+            inputs: [
+                Input::new(interval.to_string()),
+                Input::new((frame_rate.round() as u32).to_string()),
+            ],
+            focus: 0,
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+
+    /// The interval and frame rate as entered, or the index of the first one out of bounds.
+    fn rates(&self) -> Result<(u64, u32), usize> {
+        let [interval, frames] = &self.inputs;
+        let interval = interval
+            .value()
+            .parse()
+            .ok()
+            .filter(|&ms| ms >= MIN_INTERVAL_MS)
+            .ok_or(0_usize)?;
+        let frames = frames
+            .value()
+            .parse()
+            .ok()
+            .filter(|fps| (1..=MAX_FRAME_RATE).contains(fps))
+            .ok_or(1_usize)?;
+        Ok((interval, frames))
+    }
+}
+
+impl Component for RatesDialog {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.close()?,
+            KeyCode::Tab | KeyCode::Down | KeyCode::BackTab | KeyCode::Up => {
+                self.focus = 1 - self.focus;
+            }
+            KeyCode::Enter => match self.rates() {
+                Ok((interval, frames)) => {
+                    self.close()?;
+                    return Ok(Some(Action::SetRefreshRates(interval, frames)));
+                }
+                Err(invalid) => self.focus = invalid,
+            },
+            KeyCode::Char('r') => {
+                self.close()?;
+                return Ok(Some(Action::ResetRefreshRates));
+            }
+            KeyCode::Char(c) if !c.is_ascii_digit() => {}
+            _ => {
+                self.inputs[self.focus].handle_key(key);
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let rect = centered_rect(area, DIALOG_WIDTH, 2 * FIELD_HEIGHT + 2);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title("Refresh rates".bold())
+            .title_bottom(Line::from(" Enter: save · r: reset · Esc: cancel ").right_aligned())
+            .style(style);
+        let rows = Layout::vertical([Constraint::Length(FIELD_HEIGHT); 2]).split(block.inner(rect));
+        let titles = [
+            format!("Refresh every (ms, from {MIN_INTERVAL_MS})"),
+            format!("Frames per second (1-{MAX_FRAME_RATE})"),
+        ];
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(block, rect);
+        for (i, ((input, title), row)) in
+            self.inputs.iter().zip(titles).zip(rows.iter()).enumerate()
+        {
+            let color = match self.focus == i {
+                true => self.colors.selected_style_fg,
+                false => self.colors.footer_border_color,
+            };
+            input.draw(
+                frame,
+                *row,
+                Block::bordered()
+                    .border_style(Style::default().fg(color))
+                    .title(title),
+                style,
+                self.focus == i,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    fn press(dialog: &mut RatesDialog, code: KeyCode) -> Option<Action> {
+        dialog
+            .handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rates() {
+        let mut dialog = RatesDialog::new(0.5, 24.0);
+        assert_eq!(dialog.rates(), Ok((2000, 24)));
+        for _ in 0..3 {
+            press(&mut dialog, KeyCode::Backspace);
+        }
+        assert_eq!(press(&mut dialog, KeyCode::Enter), None);
+        assert_eq!(dialog.focus, 0);
+        press(&mut dialog, KeyCode::Char('5'));
+        press(&mut dialog, KeyCode::Char('0'));
+        press(&mut dialog, KeyCode::Char('0'));
+        press(&mut dialog, KeyCode::Char('x'));
+        assert_eq!(
+            press(&mut dialog, KeyCode::Enter),
+            Some(Action::SetRefreshRates(2500, 24))
+        );
+        assert_eq!(
+            press(&mut dialog, KeyCode::Char('r')),
+            Some(Action::ResetRefreshRates)
+        );
+    }
+
+    // A dialog of fixed size, drawn the same on the small terminal.
+    #[test]
+    fn test_snapshot() {
+        assert_snapshot("rates", SIZES[0], |frame| {
+            RatesDialog::new(0.5, 24.0)
+                .draw(frame, frame.area())
+                .unwrap();
+        });
+    }
+}
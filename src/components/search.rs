@@ -0,0 +1,102 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Span,
+    widgets::{Block, Clear},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, colors::Colors};
+
+use super::{input::Input, main_layout, Component};
+
+/// A line at the bottom of the list filtering it by name as the query is typed.
+pub struct SearchBar {
+    input: Input,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl SearchBar {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            input: Input::new(query),
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for SearchBar {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => {
+                self.close()?;
+                return Ok(Some(Action::Search(String::new())));
+            }
+            KeyCode::Enter => self.close()?,
+            _ => {
+                let before = self.input.value().to_string();
+                if self.input.handle_key(key) && self.input.value() != before {
+                    return Ok(Some(Action::Search(self.input.value().to_string())));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_paste_event(&mut self, text: String) -> Result<Option<Action>> {
+        self.input.insert(&text);
+        Ok(Some(Action::Search(self.input.value().to_string())))
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let line = Rect {
+            y: body.bottom().saturating_sub(1),
+            height: 1.min(body.height),
+            ..body
+        };
+        let [prefix, field] =
+            Layout::horizontal([Constraint::Length(1), Constraint::Min(0)]).areas(line);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+
+        frame.render_widget(Clear, line);
+        frame.render_widget(Span::styled("/", style.bold()), prefix);
+        self.input.draw(frame, field, Block::new(), style, true);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_snapshot() {
+        let mut search = SearchBar::new("debian");
+        for size in SIZES {
+            assert_snapshot("search", size, |frame| {
+                search.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
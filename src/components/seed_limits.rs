@@ -0,0 +1,250 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    colors::Colors,
+    data::{SeedLimits, SeedMode, SessionSeedLimits},
+    utils::handle_ratio,
+};
+
+use super::{centered_rect, input::Input, Component};
+
+const DIALOG_WIDTH: u16 = 52;
+const FIELD_HEIGHT: u16 = 3;
+const MODE_WIDTH: u16 = 10;
+
+/// A limit being edited, with the mode saying whether it applies.
+struct Field {
+    title: String,
+    input: Input,
+    mode: SeedMode,
+}
+
+impl Field {
+    fn mode_label(&self) -> &'static str {
+        match self.mode {
+            SeedMode::Global => " session",
+            SeedMode::Single => " own",
+            SeedMode::Unlimited => " none",
+        }
+    }
+}
+
+/// A popup editing when one or more torrents stop seeding, by ratio and by idle time.
+pub struct SeedLimitsDialog {
+    /// The ratio, then the idle minutes.
+    fields: [Field; 2],
+    ids: Vec<i64>,
+    focus: usize,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl SeedLimitsDialog {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(ids: Vec<i64>, limits: SeedLimits, session: &SessionSeedLimits) -> Self {
+        let ratio = match session.seed_ratio_limited {
+            true => handle_ratio(session.seed_ratio_limit),
+            false => "off".to_string(),
+        };
+        let idle = match session.idle_seeding_limit_enabled {
+            true => session.idle_seeding_limit.to_string(),
+            false => "off".to_string(),
+        };
+        Self {
+            fields: [
+                Field {
+                    title: format!("Stop at ratio (session: {ratio})"),
+                    input: Input::new(format!("{:.2}", limits.ratio_hundredths as f64 / 100.0)),
+                    mode: limits.ratio_mode,
+                },
+                Field {
+                    title: format!("Stop after idle minutes (session: {idle})"),
+                    input: Input::new(limits.idle_minutes.to_string()),
+                    mode: limits.idle_mode,
+                },
+            ],
+            ids,
+            focus: 0,
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+
+    /// The limits as entered, or the index of the first field that isn't a number.
+    fn limits(&self) -> Result<SeedLimits, usize> {
+        let [ratio, idle] = &self.fields;
+        Ok(SeedLimits {
+            ratio_mode: ratio.mode,
+            ratio_hundredths: parse_ratio(ratio.input.value()).ok_or(0_usize)?,
+            idle_mode: idle.mode,
+            idle_minutes: idle.input.value().trim().parse().map_err(|_| 1_usize)?,
+        })
+    }
+}
+
+/// The ratio typed, in hundredths.
+#[allow(clippy::cast_possible_truncation)]
+fn parse_ratio(value: &str) -> Option<i64> {
+    let ratio: f64 = value.trim().parse().ok()?;
+    (ratio.is_finite() && ratio >= 0.0).then(|| (ratio * 100.0).round() as i64)
+}
+
+impl Component for SeedLimitsDialog {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.close()?,
+            KeyCode::Tab | KeyCode::Down | KeyCode::BackTab | KeyCode::Up => {
+                self.focus = 1 - self.focus;
+            }
+            KeyCode::Char(' ') => {
+                let field = &mut self.fields[self.focus];
+                field.mode = field.mode.next();
+            }
+            KeyCode::Enter => match self.limits() {
+                Ok(limits) => {
+                    self.close()?;
+                    return Ok(Some(Action::SetSeedLimits(self.ids.clone(), limits)));
+                }
+                Err(invalid) => self.focus = invalid,
+            },
+            KeyCode::Char('.') if self.focus == 1 => {}
+            KeyCode::Char(c) if !c.is_ascii_digit() && c != '.' => {}
+            _ => {
+                let field = &mut self.fields[self.focus];
+                let before = field.input.value().to_string();
+                // Typing a limit is asking for it to be applied.
+                if field.input.handle_key(key) && field.input.value() != before {
+                    field.mode = SeedMode::Single;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let rect = centered_rect(area, DIALOG_WIDTH, 2 * FIELD_HEIGHT + 2);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let title = match self.ids.len() {
+            1 => "Seeding limits".to_string(),
+            n => format!("Seeding limits of {n} torrents"),
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title(title.bold())
+            .title_bottom(Line::from(" Space: mode · Enter: save · Esc: cancel ").right_aligned())
+            .style(style);
+        let rows = Layout::vertical([Constraint::Length(FIELD_HEIGHT); 2]).split(block.inner(rect));
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(block, rect);
+        for (i, (field, row)) in self.fields.iter().zip(rows.iter()).enumerate() {
+            let [mode, input] =
+                Layout::horizontal([Constraint::Length(MODE_WIDTH), Constraint::Min(0)])
+                    .areas(*row);
+            let color = match self.focus == i {
+                true => self.colors.selected_style_fg,
+                false => self.colors.footer_border_color,
+            };
+            frame.render_widget(
+                Line::from(field.mode_label()).fg(color),
+                Rect {
+                    y: mode.y + 1.min(mode.height.saturating_sub(1)),
+                    height: 1.min(mode.height),
+                    ..mode
+                },
+            );
+            field.input.draw(
+                frame,
+                input,
+                Block::bordered()
+                    .border_style(Style::default().fg(color))
+                    .title(field.title.as_str()),
+                style,
+                self.focus == i,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    fn dialog() -> SeedLimitsDialog {
+        let session = SessionSeedLimits {
+            seed_ratio_limit: 2.0,
+            seed_ratio_limited: true,
+            idle_seeding_limit: 30,
+            idle_seeding_limit_enabled: false,
+        };
+        SeedLimitsDialog::new(vec![1], SeedLimits::default(), &session)
+    }
+
+    fn press(dialog: &mut SeedLimitsDialog, code: KeyCode) -> Option<Action> {
+        dialog
+            .handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_typing_a_limit_applies_it() {
+        let mut dialog = dialog();
+        for _ in 0..4 {
+            press(&mut dialog, KeyCode::Backspace);
+        }
+        press(&mut dialog, KeyCode::Char('2'));
+        press(&mut dialog, KeyCode::Char('x'));
+        press(&mut dialog, KeyCode::Down);
+        press(&mut dialog, KeyCode::Char(' '));
+        press(&mut dialog, KeyCode::Char(' '));
+        let limits = SeedLimits {
+            ratio_mode: SeedMode::Single,
+            ratio_hundredths: 200,
+            idle_mode: SeedMode::Unlimited,
+            idle_minutes: 0,
+        };
+        assert_eq!(
+            press(&mut dialog, KeyCode::Enter),
+            Some(Action::SetSeedLimits(vec![1], limits))
+        );
+        assert_eq!(parse_ratio("-1"), None);
+        assert_eq!(parse_ratio(" 1.5 "), Some(150));
+    }
+
+    // A dialog of fixed size, which only gets its titles cut on the small terminal.
+    #[test]
+    fn test_snapshot() {
+        assert_snapshot("seed_limits", SIZES[0], |frame| {
+            dialog().draw(frame, frame.area()).unwrap();
+        });
+    }
+}
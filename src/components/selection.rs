@@ -0,0 +1,245 @@
+use std::{cell::RefCell, rc::Rc};
+
+use color_eyre::Result;
+use futures::executor::block_on;
+use itertools::Itertools;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, HighlightSpacing, List, ListState, Paragraph},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::{Action, Failure, Level},
+    app::Mode,
+    colors::Colors,
+    data::{edit_labels, map_torrent_list, set_labels, Aggregate, Torrent},
+    rpc::Client,
+    utils::{convert_bytes, convert_status},
+};
+
+use super::{main_layout, Component};
+
+/// Several torrents marked in Home, what they add up to and edits applied to all of them.
+pub struct Selection {
+    client: Rc<RefCell<Client>>,
+    ids: Vec<i64>,
+    torrents: Vec<Torrent>,
+    aggregate: Aggregate,
+    state: ListState,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Selection {
+    pub fn new(client: Rc<RefCell<Client>>, ids: Vec<i64>) -> Result<Self> {
+        let mut selection = Self {
+            client,
+            ids,
+            torrents: Vec::new(),
+            aggregate: Aggregate::default(),
+            state: ListState::default().with_selected(Some(0)),
+            colors: Colors::new(),
+            command_tx: None,
+        };
+        selection.refresh()?;
+        Ok(selection)
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        let torrents = block_on(map_torrent_list(&self.client))?;
+        self.torrents = torrents
+            .into_iter()
+            .filter(|t| self.ids.contains(&t.id))
+            .collect();
+        // Torrents removed meanwhile are left out of the edits too.
+        self.ids = self.torrents.iter().map(|t| t.id).collect();
+        self.aggregate = Aggregate::new(&self.torrents);
+        Ok(())
+    }
+
+    /// Apply the labels added to and removed from the shared ones, keeping the others.
+    fn set_labels(&mut self, edited: Vec<String>) -> Result<Option<Action>> {
+        let labels = self.torrents.iter().map(|t| t.labels.clone()).collect_vec();
+        // Torrents keep their own labels, each needs a call of its own.
+        let result = self
+            .torrents
+            .iter()
+            .zip(edit_labels(&labels, &edited))
+            .try_for_each(|(torrent, labels)| {
+                block_on(set_labels(&self.client, vec![torrent.id], labels))
+            });
+        if let Err(err) = result {
+            return Ok(Some(Action::error("Selection", err)));
+        }
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Notify(
+                Level::Success,
+                format!("Labels set on {} torrents", self.ids.len()),
+            ))?;
+        }
+        Ok(Some(Action::Refresh))
+    }
+
+    fn send_breadcrumbs(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Breadcrumbs(vec![format!(
+                "{} torrents",
+                self.torrents.len()
+            )]))?;
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> Vec<Line<'static>> {
+        let aggregate = &self.aggregate;
+        let done = aggregate.total_bytes - aggregate.left_bytes;
+        let list = |items: &[String]| match items.is_empty() {
+            true => "none".to_string(),
+            false => items.join(", "),
+        };
+        vec![
+            Line::from(format!(
+                "Size: {} · Have: {} · Left: {}",
+                convert_bytes(aggregate.total_bytes),
+                convert_bytes(done),
+                convert_bytes(aggregate.left_bytes),
+            )),
+            Line::from(format!(
+                "Down: {}/s · Up: {}/s",
+                convert_bytes(aggregate.rate_download),
+                convert_bytes(aggregate.rate_upload),
+            )),
+            Line::from(format!("Shared trackers: {}", list(&aggregate.trackers))),
+            Line::from(format!("Shared labels: {}", list(&aggregate.labels))),
+        ]
+    }
+}
+
+impl Component for Selection {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        self.send_breadcrumbs()
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let last = self.torrents.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0);
+        match action {
+            Action::Tick | Action::Refresh => {
+                if let Err(err) = self.refresh() {
+                    let failure = Failure::new("Selection", err).level(Level::Warning);
+                    return Ok(Some(Action::Error(failure)));
+                }
+                self.send_breadcrumbs()?;
+            }
+            Action::Back => {
+                let id = self.ids.first().copied().unwrap_or_default();
+                return Ok(Some(Action::Mode(Mode::Home, id)));
+            }
+            Action::Theme(_) => self.colors = Colors::new(),
+            Action::Down => self.state.select(Some((selected + 1).min(last))),
+            Action::Up => self.state.select(Some(selected.saturating_sub(1))),
+            Action::Top => self.state.select(Some(0)),
+            Action::Bottom => self.state.select(Some(last)),
+            Action::EditSpeedLimits => return Ok(Some(Action::OpenSpeedLimits(self.ids.clone()))),
+            Action::EditSeedLimits => return Ok(Some(Action::OpenSeedLimits(self.ids.clone()))),
+            Action::EditLabels => {
+                return Ok(Some(Action::OpenLabels(
+                    self.ids.clone(),
+                    self.aggregate.labels.clone(),
+                )));
+            }
+            Action::SetLabels(ids, labels) if ids == self.ids => return self.set_labels(labels),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let [summary, list] =
+            Layout::vertical([Constraint::Length(6), Constraint::Min(0)]).areas(body);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let border_style = Style::default().fg(self.colors.footer_border_color);
+
+        let title = format!("{} torrents", self.torrents.len());
+        frame.render_widget(
+            Paragraph::new(self.summary()).style(style).block(
+                Block::bordered()
+                    .border_style(border_style)
+                    .title(title.bold().fg(self.colors.row_fg)),
+            ),
+            summary,
+        );
+
+        let items = self
+            .torrents
+            .iter()
+            .map(|t| {
+                format!(
+                    "{}  {} · {} · {}",
+                    t.name,
+                    convert_status(t.status),
+                    t.total_size(),
+                    t.percent_done()
+                )
+            })
+            .collect_vec();
+        let list_widget = List::new(items)
+            .style(style)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.colors.selected_style_fg),
+            )
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(Block::bordered().border_style(border_style));
+        frame.render_stateful_widget(list_widget, list, &mut self.state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        data::fixtures::torrent_json,
+        rpc::fixtures::{serve, success},
+    };
+
+    // Torrents that weren't marked are left out.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_snapshot() {
+        let mut seeding = torrent_json(2, false);
+        seeding["name"] = json!("debian-12.5.0-amd64-netinst.iso");
+        seeding["status"] = json!(6);
+        seeding["percentDone"] = json!(1.0);
+        seeding["leftUntilDone"] = json!(0);
+        seeding["labels"] = json!(["linux", "tv"]);
+        let url = serve(vec![
+            (
+                "200 OK",
+                success(
+                    json!({ "torrents": [torrent_json(1, false), seeding, torrent_json(3, false)] }),
+                ),
+            ),
+            ("200 OK", success(json!({ "torrents": [] }))),
+        ]);
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let mut selection = Selection::new(client, vec![1, 2]).unwrap();
+        for size in SIZES {
+            assert_snapshot("selection", size, |frame| {
+                selection.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
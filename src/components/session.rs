@@ -0,0 +1,184 @@
+use std::{cell::RefCell, rc::Rc};
+
+use color_eyre::Result;
+use futures::executor::block_on;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, HighlightSpacing, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::{
+    action::{Action, Failure, Level},
+    app::Mode,
+    colors::Colors,
+    data::{fetch_session_settings, set_session_setting, Setting},
+    rpc::Client,
+};
+
+use super::{main_layout, Component};
+
+/// The daemon's settings, edited in place.
+pub struct Session {
+    client: Rc<RefCell<Client>>,
+    settings: Vec<(Setting, serde_json::Value)>,
+    /// Torrent selected in Home, restored when going back.
+    home_id: i64,
+    state: TableState,
+    colors: Colors,
+}
+
+impl Session {
+    pub fn new(client: Rc<RefCell<Client>>, home_id: i64) -> Result<Self> {
+        let settings = block_on(fetch_session_settings(&client))?;
+        Ok(Self {
+            client,
+            settings,
+            home_id,
+            state: TableState::default().with_selected(Some(0)),
+            colors: Colors::new(),
+        })
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        self.settings = block_on(fetch_session_settings(&self.client))?;
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&(Setting, serde_json::Value)> {
+        self.settings.get(self.state.selected()?)
+    }
+
+    fn set(&mut self, setting: Setting, value: serde_json::Value) -> Result<Option<Action>> {
+        let shown = setting.display(&value);
+        if let Err(err) = block_on(set_session_setting(&self.client, setting, value)) {
+            return Ok(Some(Action::error("Session", err)));
+        }
+        if let Err(err) = self.refresh() {
+            return Ok(Some(Action::error("Session", err)));
+        }
+        Ok(Some(Action::Notify(
+            Level::Success,
+            format!("{} set to {shown}", setting.title()),
+        )))
+    }
+}
+
+impl Component for Session {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let last = self.settings.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0);
+        match action {
+            Action::Tick | Action::Refresh => {
+                if let Err(err) = self.refresh() {
+                    let failure = Failure::new("Session", err).level(Level::Warning);
+                    return Ok(Some(Action::Error(failure)));
+                }
+            }
+            Action::Back => return Ok(Some(Action::Mode(Mode::Home, self.home_id))),
+            Action::Theme(_) => self.colors = Colors::new(),
+            Action::Down => self.state.select(Some((selected + 1).min(last))),
+            Action::Up => self.state.select(Some(selected.saturating_sub(1))),
+            Action::Top => self.state.select(Some(0)),
+            Action::Bottom => self.state.select(Some(last)),
+            Action::Select => {
+                let Some((setting, value)) = self.selected().cloned() else {
+                    return Ok(None);
+                };
+                // Switches and choices change at once, the rest is typed in.
+                return match setting.next(&value) {
+                    Some(next) => self.set(setting, next),
+                    None => Ok(Some(Action::OpenSessionSetting(
+                        setting,
+                        setting.display(&value),
+                    ))),
+                };
+            }
+            Action::SetSessionSetting(setting, input) => {
+                return match setting.parse(&input) {
+                    Ok(value) => self.set(setting, value),
+                    Err(err) => Ok(Some(Action::Notify(
+                        Level::Warning,
+                        format!("{} {err}", setting.title()),
+                    ))),
+                };
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let block = Block::bordered()
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title("Session".bold().fg(self.colors.row_fg))
+            .title_bottom(Line::from(" Enter: edit or toggle · Esc: back ").right_aligned());
+
+        if self.settings.is_empty() {
+            let empty = Paragraph::new("No settings").style(style).block(block);
+            frame.render_widget(empty, body);
+            return Ok(());
+        }
+
+        let rows = self.settings.iter().map(|(setting, value)| {
+            Row::new([setting.title().to_string(), setting.display(value)])
+        });
+        let table = Table::new(rows, [Constraint::Length(32), Constraint::Fill(1)])
+            .block(block)
+            .style(style)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.colors.selected_style_fg),
+            )
+            .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(table, body, &mut self.state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        rpc::fixtures::{serve, success},
+    };
+
+    // Settings the daemon doesn't send are left out.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_snapshot() {
+        let url = serve(vec![(
+            "200 OK",
+            success(json!({
+                "download-dir": "/downloads",
+                "incomplete-dir-enabled": false,
+                "incomplete-dir": "/downloads/incomplete",
+                "peer-port": 51413,
+                "peer-limit-global": 200,
+                "peer-limit-per-torrent": 50,
+                "encryption": "preferred",
+                "dht-enabled": true,
+                "pex-enabled": true,
+                "utp-enabled": false,
+                "seedRatioLimited": true,
+                "seedRatioLimit": 2.0,
+            })),
+        )]);
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let mut session = Session::new(client, 1).unwrap();
+        for size in SIZES {
+            assert_snapshot("session", size, |frame| {
+                session.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
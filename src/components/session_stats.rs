@@ -1,84 +1,246 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, time::Instant};
 
+use chrono::Utc;
 use color_eyre::Result;
 use futures::executor::block_on;
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
-    style::Style,
-    text::Line,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, BorderType, Paragraph},
     Frame,
 };
-use transmission_rpc::{types::SessionStats, TransClient};
+use transmission_rpc::types::SessionStats;
 
-use crate::{action::Action, app, colors::Colors, utils::convert_bytes};
+use crate::{
+    action::{Action, Failure, Level},
+    app::{self, Mode},
+    colors::Colors,
+    config::{Config, FreeSpaceConfig},
+    data::{
+        fetch_free_space, fetch_session_status, set_alt_speed_enabled, SpeedLimit, SpeedLimits,
+    },
+    history::{record_run, History, Rates, Sample},
+    rpc::Client,
+    utils::{convert_bytes, format_age, locale, sparkline},
+    view::StatusFilter,
+};
 
-use super::Component;
+use super::{footer_layout, Component};
 
 pub struct SessionStat {
-    client: Rc<RefCell<TransClient>>,
+    client: Rc<RefCell<Client>>,
     stats: SessionStats,
     colors: Colors,
+    last_update: Instant,
+    failures: u32,
+    history: History,
+    /// The filter of the torrent list and how many torrents match each filter, if it is shown.
+    filter: StatusFilter,
+    counts: Vec<(StatusFilter, usize)>,
+    /// Whether the alternative speed limits are applied.
+    alt_speed: bool,
+    /// The session's download and upload limits, shown next to the speeds while enabled.
+    limits: SpeedLimits,
+    /// Bytes available in the download directory, if the daemon tells.
+    free_space: Option<i64>,
+    free_space_config: FreeSpaceConfig,
+    /// Session rates over the last ticks, drawn next to the current ones.
+    rates: Rates,
 }
 
+/// Rates drawn in the footer.
+const FOOTER_SAMPLES: usize = 8;
+
+/// Consecutive failed refreshes after which the data is considered lost.
+const MAX_FAILURES: u32 = 3;
+
 impl Component for SessionStat {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.free_space_config = config.free_space;
+        Ok(())
+    }
+
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
-            Action::Tick => {
-                self.stats = match block_on(get_stats(self.client.clone())) {
-                    Ok(stats) => stats,
-                    Err(err) => return Ok(Some(Action::Error(err.to_string()))),
+            Action::Tick | Action::Refresh => {
+                match block_on(get_stats(self.client.clone())) {
+                    Ok(stats) => {
+                        let sample = Sample {
+                            time: Utc::now().timestamp(),
+                            downloaded: stats.cumulative_stats.downloaded_bytes,
+                            uploaded: stats.cumulative_stats.uploaded_bytes,
+                        };
+                        record_run(sample);
+                        if action == Action::Tick {
+                            self.rates.push(stats.download_speed, stats.upload_speed);
+                        }
+                        self.stats = stats;
+                        self.last_update = Instant::now();
+                        self.failures = 0;
+                        if let Err(err) = self.history.record(sample) {
+                            return Ok(Some(Action::error(
+                                "SessionStat",
+                                format!("Failed to save history: {err}"),
+                            )));
+                        }
+                    }
+                    Err(err) => {
+                        self.failures += 1;
+                        let failure = Failure::new("SessionStat", err).level(Level::Warning);
+                        return Ok(Some(Action::Error(failure)));
+                    }
                 };
+                // The stats above already report a lost connection.
+                match block_on(fetch_session_status(&self.client)) {
+                    Ok(status) => {
+                        self.alt_speed = status.alt_speed;
+                        self.limits = status.limits;
+                        self.free_space =
+                            block_on(fetch_free_space(&self.client, &status.download_dir)).ok();
+                    }
+                    Err(_) => self.free_space = None,
+                }
+            }
+            Action::ToggleAltSpeed => {
+                let enabled = !self.alt_speed;
+                if let Err(err) = block_on(set_alt_speed_enabled(&self.client, enabled)) {
+                    return Ok(Some(Action::error("SessionStat", err)));
+                }
+                self.alt_speed = enabled;
+                let state = if enabled { "on" } else { "off" };
+                return Ok(Some(Action::Notify(
+                    Level::Success,
+                    format!("Turtle mode {state}"),
+                )));
+            }
+            Action::StatusCounts(filter, counts) => {
+                self.filter = filter;
+                self.counts = counts;
             }
+            Action::Mode(mode, _) if mode != Mode::Home => self.counts.clear(),
+            Action::Theme(_) => self.colors = Colors::new(),
             Action::Render => {}
             _ => {}
         }
         Ok(None)
     }
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(3)]);
-        let rects = vertical.split(area);
+        let [_, footer] = footer_layout(area);
 
-        self.render_stats(frame, rects[1]);
+        self.render_stats(frame, footer);
         Ok(())
     }
 }
 
 impl SessionStat {
-    pub fn new(client: Rc<RefCell<TransClient>>) -> Result<Self> {
+    pub fn new(client: Rc<RefCell<Client>>) -> Result<Self> {
         let stats = block_on(get_stats(client.clone()))?;
+        let status = block_on(fetch_session_status(&client)).ok();
         Ok(Self {
-            client,
             stats,
             colors: Colors::new(),
+            last_update: Instant::now(),
+            failures: 0,
+            history: History::load()?,
+            filter: StatusFilter::default(),
+            counts: Vec::new(),
+            alt_speed: status.as_ref().is_some_and(|status| status.alt_speed),
+            limits: status.map(|status| status.limits).unwrap_or_default(),
+            free_space: None,
+            free_space_config: FreeSpaceConfig::default(),
+            rates: Rates::default(),
+            client,
         })
     }
 
     fn render_stats(&self, frame: &mut Frame, area: Rect) {
         let stats = &self.stats;
         let stats_text = format!(
-            "Down: {}/s Up: {}/s Torrents: {} ",
+            "Down: {}/s{} {} Up: {}/s{} {} Torrents: {} ",
             convert_bytes(stats.download_speed),
+            cap_text(self.limits.down),
+            sparkline(&self.rates.down(FOOTER_SAMPLES)),
             convert_bytes(stats.upload_speed),
-            stats.torrent_count
+            cap_text(self.limits.up),
+            sparkline(&self.rates.up(FOOTER_SAMPLES)),
+            locale().number(i64::from(stats.torrent_count))
         );
-        let info_footer = Paragraph::new(Line::from(stats_text))
-            .style(
-                Style::new()
-                    .fg(self.colors.row_fg)
-                    .bg(self.colors.buffer_bg),
-            )
-            .right_aligned()
-            .block(
-                Block::bordered()
-                    .border_type(BorderType::Double)
-                    .border_style(Style::new().fg(self.colors.footer_border_color)),
-            );
-        frame.render_widget(info_footer, area);
+        let age_color = match self.failures {
+            0 => self.colors.row_fg,
+            n if n < MAX_FAILURES => self.colors.warning_fg,
+            _ => self.colors.error_fg,
+        };
+        let age_text = format!(
+            " updated {}",
+            format_age(self.last_update.elapsed().as_secs())
+        );
+
+        let block = Block::bordered()
+            .border_type(BorderType::Double)
+            .border_style(Style::new().fg(self.colors.footer_border_color))
+            .style(Style::new().bg(self.colors.buffer_bg));
+        let inner = block.inner(area);
+        let counts = self.counts_line();
+        let mut stats_line = match self.alt_speed {
+            true => Line::from(vec![
+                Span::styled("🐢 ", Style::new().fg(self.colors.warning_fg)),
+                Span::from(stats_text),
+            ]),
+            false => Line::from(stats_text),
+        };
+        if let Some(free) = self.free_space {
+            let free_text = format!("Free: {} ", convert_bytes(free));
+            stats_line.push_span(match self.free_space_config.is_low(free) {
+                true => Span::styled(free_text, Style::new().fg(self.colors.error_fg)),
+                false => Span::from(free_text),
+            });
+        }
+        // Leave the counts out rather than overlap the other stats.
+        let fits =
+            counts.width() + 2 * age_text.len().max(stats_line.width()) <= usize::from(inner.width);
+        let age = Paragraph::new(Line::from(age_text)).style(Style::new().fg(age_color));
+        let info_footer = Paragraph::new(stats_line)
+            .style(Style::new().fg(self.colors.row_fg))
+            .right_aligned();
+        frame.render_widget(block, area);
+        frame.render_widget(age, inner);
+        frame.render_widget(info_footer, inner);
+        if fits {
+            frame.render_widget(counts.centered(), inner);
+        }
+    }
+
+    /// The number of torrents per status, highlighting the active filter.
+    fn counts_line(&self) -> Line<'static> {
+        let spans = self.counts.iter().map(|&(filter, count)| {
+            let text = format!(" {filter} {} ", locale().number(count as i64));
+            match filter == self.filter {
+                true => Span::styled(
+                    text,
+                    Style::new()
+                        .fg(self.colors.selected_style_fg)
+                        .add_modifier(Modifier::REVERSED),
+                ),
+                false => Span::styled(text, Style::new().fg(self.colors.row_fg)),
+            }
+        });
+        Line::from(spans.collect::<Vec<_>>())
+    }
+}
+
+/// The limit of a speed, when it is applied.
+fn cap_text(limit: SpeedLimit) -> String {
+    match limit.enabled {
+        true => format!(
+            " (cap {}/s)",
+            convert_bytes(limit.limit.saturating_mul(1024))
+        ),
+        false => String::new(),
     }
 }
 
-async fn get_stats(client: Rc<RefCell<TransClient>>) -> Result<SessionStats, app::Error> {
+pub(super) async fn get_stats(client: Rc<RefCell<Client>>) -> Result<SessionStats, app::Error> {
     let res = {
         let mut client = client.borrow_mut();
         async move { client.session_stats().await }
@@ -90,3 +252,118 @@ async fn get_stats(client: Rc<RefCell<TransClient>>) -> Result<SessionStats, app
         Err(err) => Err(app::Error::WithMessage(err.to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        rpc::fixtures::{serve, success},
+    };
+
+    fn stats_json() -> serde_json::Value {
+        json!({
+            "torrentCount": 12,
+            "activeTorrentCount": 3,
+            "pausedTorrentCount": 9,
+            "downloadSpeed": 2_097_152,
+            "uploadSpeed": 524_288,
+            "current-stats": {
+                "filesAdded": 2, "downloadedBytes": 0, "uploadedBytes": 0,
+                "secondsActive": 0, "sessionCount": 1,
+            },
+            "cumulative-stats": {
+                "filesAdded": 2, "downloadedBytes": 0, "uploadedBytes": 0,
+                "secondsActive": 0, "sessionCount": 1,
+            },
+        })
+    }
+
+    #[test]
+    fn test_cap_text() {
+        let limit = SpeedLimit {
+            limit: 5 * 1024,
+            enabled: true,
+        };
+        assert_eq!(cap_text(limit), " (cap 5.0 MB/s)");
+        let disabled = SpeedLimit {
+            enabled: false,
+            ..limit
+        };
+        assert_eq!(cap_text(disabled), "");
+    }
+
+    /// A footer calling the daemon at `url`, without touching the history on disk.
+    fn footer(url: Url) -> SessionStat {
+        SessionStat {
+            client: Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new()))),
+            stats: serde_json::from_value(stats_json()).unwrap(),
+            colors: Colors::new(),
+            last_update: Instant::now(),
+            failures: 0,
+            history: History::empty().unwrap(),
+            filter: StatusFilter::default(),
+            counts: Vec::new(),
+            alt_speed: false,
+            limits: SpeedLimits::default(),
+            free_space: None,
+            free_space_config: FreeSpaceConfig::default(),
+            rates: Rates::default(),
+        }
+    }
+
+    // The stats, the settings shown in one call, then the free space of the download directory.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refresh_gets_the_settings_in_one_call() {
+        let settings = json!({
+            "alt-speed-enabled": true,
+            "download-dir": "/downloads",
+            "speed-limit-down": 500,
+            "speed-limit-down-enabled": true,
+            "speed-limit-up": 50,
+            "speed-limit-up-enabled": false,
+        });
+        let url = serve(vec![
+            ("200 OK", success(stats_json())),
+            ("200 OK", success(settings)),
+            (
+                "200 OK",
+                success(json!({ "path": "/downloads", "size-bytes": 1024 })),
+            ),
+        ]);
+        let mut footer = footer(url);
+        assert_eq!(footer.update(Action::Refresh).unwrap(), None);
+        assert!(footer.alt_speed);
+        assert_eq!(
+            footer.limits.down,
+            SpeedLimit {
+                limit: 500,
+                enabled: true
+            }
+        );
+        assert_eq!(footer.free_space, Some(1024));
+    }
+
+    // The counts only fit wider terminals, and the speeds cover the age on the small one.
+    #[test]
+    fn test_snapshot() {
+        let mut footer = footer(Url::parse("http://localhost:9091/transmission/rpc").unwrap());
+        footer.filter = StatusFilter::Downloading;
+        footer.counts = vec![
+            (StatusFilter::All, 12),
+            (StatusFilter::Downloading, 3),
+            (StatusFilter::Paused, 9),
+        ];
+        for i in 0..FOOTER_SAMPLES as i64 {
+            footer.rates.push(i * 300_000, 524_288);
+        }
+        for size in SIZES {
+            assert_snapshot("footer", size, |frame| {
+                footer.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
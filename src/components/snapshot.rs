@@ -0,0 +1,59 @@
+//! Snapshot tests of what the views draw, compared as text with the files in `snapshots`.
+//!
+//! A new or changed snapshot fails the test; run the tests with `UPDATE_SNAPSHOTS=1` to write
+//! them, then review the diff of the files like any other change.
+//!
+//! This does what `insta` would for these tests, plain text compared whole, without another
+//! dev-dependency or `cargo insta` to review the changes: they're files in the diff.
+//!
+//! Views left out on purpose:
+//! - `stats`, which shows the RPC calls recorded for the whole process, that the tests running
+//!   alongside add to;
+//! - `history`, whose bars are bucketed and labelled by the current time;
+//! - `watch_dir`, which draws nothing.
+
+use std::{env, fs, path::PathBuf};
+
+use ratatui::{backend::TestBackend, buffer::Buffer, Frame, Terminal};
+
+/// A common terminal and a small one, where layouts tend to break first.
+pub const SIZES: [(u16, u16); 2] = [(80, 24), (44, 14)];
+
+/// Compare what `draw` renders on a terminal of `width` by `height` with the snapshot `name`.
+pub fn assert_snapshot(name: &str, (width, height): (u16, u16), draw: impl FnOnce(&mut Frame)) {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(draw).unwrap();
+    let text = buffer_text(terminal.backend().buffer());
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/components/snapshots")
+        .join(format!("{name}@{width}x{height}.snap"));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        return;
+    }
+    let Ok(expected) = fs::read_to_string(&path) else {
+        panic!(
+            "No snapshot {}, run with UPDATE_SNAPSHOTS=1 to write it",
+            path.display()
+        );
+    };
+    pretty_assertions::assert_eq!(
+        expected,
+        text,
+        "{name} at {width}x{height} changed, run with UPDATE_SNAPSHOTS=1 if that's intended"
+    );
+}
+
+/// The symbols of `buffer`, a line per row without the trailing blanks.
+fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let line = (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>();
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
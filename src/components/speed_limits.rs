@@ -0,0 +1,212 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    colors::Colors,
+    data::{SpeedLimit, SpeedLimits},
+};
+
+use super::{centered_rect, input::Input, Component};
+
+const DIALOG_WIDTH: u16 = 48;
+const FIELD_HEIGHT: u16 = 3;
+
+/// A limit being edited, applied if `enabled`.
+struct Field {
+    title: &'static str,
+    input: Input,
+    enabled: bool,
+}
+
+impl Field {
+    fn new(title: &'static str, limit: SpeedLimit) -> Self {
+        Self {
+            title,
+            input: Input::new(limit.limit.to_string()),
+            enabled: limit.enabled,
+        }
+    }
+
+    fn limit(&self) -> Option<SpeedLimit> {
+        let limit = self.input.value().trim().parse().ok()?;
+        Some(SpeedLimit {
+            limit,
+            enabled: self.enabled,
+        })
+    }
+}
+
+/// A popup editing the speed limits of the session and of one or more torrents.
+pub struct SpeedLimitsDialog {
+    /// Download and upload of the session, then of the torrent if any.
+    fields: Vec<Field>,
+    torrents: Option<Vec<i64>>,
+    focus: usize,
+    colors: Colors,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl SpeedLimitsDialog {
+    pub fn new(session: SpeedLimits, torrents: Option<(Vec<i64>, SpeedLimits)>) -> Self {
+        let mut fields = vec![
+            Field::new("Session download (KB/s)", session.down),
+            Field::new("Session upload (KB/s)", session.up),
+        ];
+        if let Some((ids, limits)) = &torrents {
+            let (down, up) = match ids.len() {
+                1 => ("Torrent download (KB/s)", "Torrent upload (KB/s)"),
+                _ => ("Selected download (KB/s)", "Selected upload (KB/s)"),
+            };
+            fields.push(Field::new(down, limits.down));
+            fields.push(Field::new(up, limits.up));
+        }
+        Self {
+            fields,
+            torrents: torrents.map(|(ids, _)| ids),
+            focus: 0,
+            colors: Colors::new(),
+            command_tx: None,
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::ClosePopup)?;
+        }
+        Ok(())
+    }
+
+    /// The limits as entered, or the index of the first field that isn't a number.
+    fn limits(&self) -> Result<Vec<SpeedLimit>, usize> {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| field.limit().ok_or(i))
+            .collect()
+    }
+}
+
+impl Component for SpeedLimitsDialog {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.close()?,
+            KeyCode::Tab | KeyCode::Down => self.focus = (self.focus + 1) % self.fields.len(),
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus = (self.focus + self.fields.len() - 1) % self.fields.len();
+            }
+            KeyCode::Char(' ') => {
+                let field = &mut self.fields[self.focus];
+                field.enabled = !field.enabled;
+            }
+            KeyCode::Enter => match self.limits() {
+                Ok(limits) => {
+                    let pair = |i: usize| SpeedLimits {
+                        down: limits[i],
+                        up: limits[i + 1],
+                    };
+                    self.close()?;
+                    return Ok(Some(Action::SetSpeedLimits(
+                        pair(0),
+                        self.torrents.clone().map(|ids| (ids, pair(2))),
+                    )));
+                }
+                Err(invalid) => self.focus = invalid,
+            },
+            KeyCode::Char(c) if !c.is_ascii_digit() => {}
+            _ => {
+                let field = &mut self.fields[self.focus];
+                let before = field.input.value().to_string();
+                // Typing a limit is asking for it to be applied.
+                if field.input.handle_key(key) && field.input.value() != before {
+                    field.enabled = true;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        #[allow(clippy::cast_possible_truncation)]
+        let height = self.fields.len() as u16 * FIELD_HEIGHT + 2;
+        let rect = centered_rect(area, DIALOG_WIDTH, height);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title("Speed limits".bold())
+            .title_bottom(Line::from(" Space: toggle · Enter: save · Esc: cancel ").right_aligned())
+            .style(style);
+        let rows = Layout::vertical(self.fields.iter().map(|_| Constraint::Length(FIELD_HEIGHT)))
+            .split(block.inner(rect));
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(block, rect);
+        for (i, (field, row)) in self.fields.iter().zip(rows.iter()).enumerate() {
+            let [checkbox, input] =
+                Layout::horizontal([Constraint::Length(5), Constraint::Min(0)]).areas(*row);
+            let color = match self.focus == i {
+                true => self.colors.selected_style_fg,
+                false => self.colors.footer_border_color,
+            };
+            let mark = if field.enabled { "x" } else { " " };
+            frame.render_widget(
+                Line::from(format!(" [{mark}]")).fg(color),
+                Rect {
+                    y: checkbox.y + 1.min(checkbox.height.saturating_sub(1)),
+                    height: 1.min(checkbox.height),
+                    ..checkbox
+                },
+            );
+            field.input.draw(
+                frame,
+                input,
+                Block::bordered()
+                    .border_style(Style::default().fg(color))
+                    .title(field.title),
+                style,
+                self.focus == i,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    // A dialog of fixed size, with the fields of the selected torrents below the session's.
+    #[test]
+    fn test_snapshot() {
+        let limit = |limit, enabled| SpeedLimit { limit, enabled };
+        let session = SpeedLimits {
+            down: limit(1000, true),
+            up: limit(100, false),
+        };
+        let torrents = SpeedLimits {
+            down: limit(0, false),
+            up: limit(50, true),
+        };
+        let mut dialog = SpeedLimitsDialog::new(session, Some((vec![1, 2], torrents)));
+        assert_snapshot("speed_limits", SIZES[0], |frame| {
+            dialog.draw(frame, frame.area()).unwrap();
+        });
+    }
+}
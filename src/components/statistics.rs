@@ -0,0 +1,296 @@
+use std::{cell::RefCell, rc::Rc};
+
+use color_eyre::Result;
+use futures::executor::block_on;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Sparkline, Table},
+    Frame,
+};
+use transmission_rpc::types::SessionStats;
+
+use crate::{
+    action::{Action, Failure, Level},
+    app::Mode,
+    colors::Colors,
+    history::Rates,
+    rpc::Client,
+    utils::{convert_bytes, format_eta, handle_ratio, locale, EtaFormat, EtaStyle},
+};
+
+use super::{main_layout, session_stats::get_stats, Component};
+
+/// Height of the table of numbers, header and borders included.
+const TABLE_HEIGHT: u16 = 9;
+
+/// What the daemon counted since it was installed and since it was started, with the session
+/// rates since the view was opened.
+pub struct Statistics {
+    client: Rc<RefCell<Client>>,
+    stats: SessionStats,
+    rates: Rates,
+    /// Torrent selected in Home, restored when going back.
+    home_id: i64,
+    colors: Colors,
+}
+
+impl Statistics {
+    pub fn new(client: Rc<RefCell<Client>>, home_id: i64) -> Result<Self> {
+        let stats = block_on(get_stats(client.clone()))?;
+        let mut rates = Rates::default();
+        rates.push(stats.download_speed, stats.upload_speed);
+        Ok(Self {
+            client,
+            stats,
+            rates,
+            home_id,
+            colors: Colors::new(),
+        })
+    }
+
+    /// The totals of all time next to the ones of the daemon's session.
+    fn table(&self) -> Table<'static> {
+        let [all_time, current] = totals(&self.stats);
+        let rows = all_time.rows().into_iter().zip(current.rows()).map(
+            |((name, all_time), (_, current))| Row::new([name.to_string(), all_time, current]),
+        );
+        let widths = [
+            Constraint::Length(13),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ];
+        Table::new(rows, widths)
+            .header(Row::new(["", "All time", "This session"]).bold())
+            .block(
+                Block::bordered()
+                    .border_style(Style::default().fg(self.colors.footer_border_color))
+                    .title("Totals".bold().fg(self.colors.row_fg)),
+            )
+            .style(
+                Style::default()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn draw_graph(&self, frame: &mut Frame, area: Rect, title: &str, rate: i64, samples: &[i64]) {
+        let data = samples
+            .iter()
+            .map(|&sample| sample.max(0) as u64)
+            .collect::<Vec<_>>();
+        let sparkline = Sparkline::default()
+            .block(
+                Block::bordered()
+                    .border_style(Style::default().fg(self.colors.footer_border_color))
+                    .title(format!("{title} · {}/s", convert_bytes(rate)).bold()),
+            )
+            .data(&data)
+            .style(
+                Style::default()
+                    .fg(self.colors.selected_style_fg)
+                    .bg(self.colors.buffer_bg),
+            );
+        frame.render_widget(sparkline, area);
+    }
+}
+
+/// The counters of a period; the RPC crate doesn't export its own type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Totals {
+    downloaded: i64,
+    uploaded: i64,
+    seconds_active: i64,
+    /// Missing from old daemons for the current session.
+    session_count: Option<i32>,
+    files_added: i32,
+}
+
+/// The totals of all time, then of the daemon's session.
+fn totals(stats: &SessionStats) -> [Totals; 2] {
+    let cumulative = &stats.cumulative_stats;
+    let current = &stats.current_stats;
+    [
+        Totals {
+            downloaded: cumulative.downloaded_bytes,
+            uploaded: cumulative.uploaded_bytes,
+            seconds_active: cumulative.seconds_active,
+            session_count: cumulative.session_count,
+            files_added: cumulative.files_added,
+        },
+        Totals {
+            downloaded: current.downloaded_bytes,
+            uploaded: current.uploaded_bytes,
+            seconds_active: current.seconds_active,
+            session_count: current.session_count,
+            files_added: current.files_added,
+        },
+    ]
+}
+
+impl Totals {
+    /// The numbers with their names.
+    #[allow(clippy::cast_precision_loss)]
+    fn rows(&self) -> Vec<(&'static str, String)> {
+        let ratio = match self.downloaded {
+            0 => handle_ratio(-1.0),
+            downloaded => handle_ratio((self.uploaded as f64 / downloaded as f64) as f32),
+        };
+        let active = match self.seconds_active {
+            0 => "0s".to_string(),
+            seconds => format_eta(
+                seconds,
+                EtaFormat {
+                    cap_days: i64::MAX / 86400,
+                    style: EtaStyle::Compact,
+                },
+            ),
+        };
+        let started = self.session_count.map_or("Unknown".to_string(), |count| {
+            locale().number(i64::from(count))
+        });
+        vec![
+            ("Downloaded", convert_bytes(self.downloaded)),
+            ("Uploaded", convert_bytes(self.uploaded)),
+            ("Ratio", ratio),
+            ("Active", active),
+            ("Times started", started),
+            ("Files added", locale().number(i64::from(self.files_added))),
+        ]
+    }
+}
+
+impl Component for Statistics {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick | Action::Refresh => match block_on(get_stats(self.client.clone())) {
+                Ok(stats) => {
+                    if action == Action::Tick {
+                        self.rates.push(stats.download_speed, stats.upload_speed);
+                    }
+                    self.stats = stats;
+                }
+                Err(err) => {
+                    let failure = Failure::new("Statistics", err).level(Level::Warning);
+                    return Ok(Some(Action::Error(failure)));
+                }
+            },
+            Action::Back => return Ok(Some(Action::Mode(Mode::Home, self.home_id))),
+            Action::Theme(_) => self.colors = Colors::new(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let [title, table, down, up] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(TABLE_HEIGHT),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ])
+        .areas(body);
+
+        let stats = &self.stats;
+        let heading = format!(
+            " Torrents: {} · Active: {} · Paused: {}",
+            stats.torrent_count, stats.active_torrent_count, stats.paused_torrent_count
+        );
+        frame.render_widget(
+            Paragraph::new(Line::from(heading))
+                .bold()
+                .fg(self.colors.header_fg)
+                .bg(self.colors.buffer_bg),
+            title,
+        );
+        frame.render_widget(self.table(), table);
+        // One sample per column inside the borders, the oldest on the left.
+        let width = usize::from(down.width.saturating_sub(2));
+        let samples = self.rates.down(width);
+        self.draw_graph(frame, down, "Download", stats.download_speed, &samples);
+        let samples = self.rates.up(width);
+        self.draw_graph(frame, up, "Upload", stats.upload_speed, &samples);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    fn stats() -> SessionStats {
+        serde_json::from_value(serde_json::json!({
+            "torrentCount": 12,
+            "activeTorrentCount": 3,
+            "pausedTorrentCount": 9,
+            "downloadSpeed": 2_097_152,
+            "uploadSpeed": 524_288,
+            "current-stats": {
+                "filesAdded": 2,
+                "downloadedBytes": 1_073_741_824_i64,
+                "uploadedBytes": 536_870_912,
+                "secondsActive": 5400,
+                "sessionCount": 1,
+            },
+            "cumulative-stats": {
+                "filesAdded": 340,
+                "downloadedBytes": 2_199_023_255_552_i64,
+                "uploadedBytes": 3_298_534_883_328_i64,
+                "secondsActive": 31_536_000,
+                "sessionCount": 87,
+            },
+        }))
+        .unwrap()
+    }
+
+    fn value(totals: &Totals, name: &str) -> String {
+        let rows = totals.rows();
+        rows.into_iter().find(|(n, _)| *n == name).unwrap().1
+    }
+
+    #[test]
+    fn test_rows() {
+        let [all_time, mut current] = totals(&stats());
+        assert_eq!(value(&all_time, "Downloaded"), "2.0 TB");
+        assert_eq!(value(&all_time, "Ratio"), "1.50");
+        assert_eq!(value(&all_time, "Active"), "365d");
+        assert_eq!(value(&all_time, "Times started"), "87");
+        assert_eq!(value(&current, "Ratio"), "0.50");
+        current.downloaded = 0;
+        current.seconds_active = 0;
+        current.session_count = None;
+        let value = |name| value(&current, name);
+        assert_eq!(value("Ratio"), "None");
+        assert_eq!(value("Active"), "0s");
+        assert_eq!(value("Times started"), "Unknown");
+    }
+
+    // The rate graphs only fit the common terminal, the small one shows the totals alone.
+    #[test]
+    fn test_snapshot() {
+        let url = Url::parse("http://localhost:9091/transmission/rpc").unwrap();
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        for size in SIZES {
+            assert_snapshot("statistics", size, |frame| {
+                let mut rates = Rates::default();
+                for i in 0..20 {
+                    rates.push(i * 100_000, (20 - i) * 20_000);
+                }
+                let mut view = Statistics {
+                    client: client.clone(),
+                    stats: stats(),
+                    rates,
+                    home_id: 0,
+                    colors: Colors::new(),
+                };
+                view.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+use color_eyre::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    colors::Colors,
+    metrics::{metrics, Intervals},
+    utils::convert_bytes,
+};
+
+use super::Component;
+
+const STATS_WIDTH: u16 = 46;
+/// Latest RPC calls listed.
+const CALLS_SHOWN: usize = 8;
+
+/// An overlay of internal measurements, drawn over the top right corner of the screen.
+pub struct Stats {
+    /// The tick and frame rates asked for, per second.
+    tick_rate: f64,
+    frame_rate: f64,
+    ticks: Intervals,
+    frames: Intervals,
+    /// Actions waiting when they were last handled, and the most seen.
+    queued: usize,
+    peak_queued: usize,
+    colors: Colors,
+}
+
+impl Stats {
+    pub fn new(tick_rate: f64, frame_rate: f64) -> Self {
+        Self {
+            tick_rate,
+            frame_rate,
+            ticks: Intervals::default(),
+            frames: Intervals::default(),
+            queued: 0,
+            peak_queued: 0,
+            colors: Colors::new(),
+        }
+    }
+
+    pub fn set_rates(&mut self, tick_rate: f64, frame_rate: f64) {
+        self.tick_rate = tick_rate;
+        self.frame_rate = frame_rate;
+    }
+
+    pub fn tick(&mut self, now: Instant) {
+        self.ticks.record(now);
+    }
+
+    pub fn frame(&mut self, now: Instant) {
+        self.frames.record(now);
+    }
+
+    pub fn queued(&mut self, depth: usize) {
+        self.queued = depth;
+        self.peak_queued = self.peak_queued.max(depth);
+    }
+}
+
+impl Component for Stats {
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let metrics = metrics();
+        let millis = |duration: Duration| format!("{} ms", duration.as_millis());
+        let target_tick =
+            Duration::try_from_secs_f64(1.0 / self.tick_rate).map_or("-".to_string(), millis);
+        let mut lines = vec![
+            Line::from(format!(
+                "Frames: {:.1}/s of {:.1}/s",
+                self.frames.rate().unwrap_or(0.0),
+                self.frame_rate
+            )),
+            Line::from(format!(
+                "Ticks: every {} of {}",
+                self.ticks.mean().map_or("-".to_string(), millis),
+                target_tick
+            )),
+            Line::from(format!(
+                "Action queue: {} (peak {})",
+                self.queued, self.peak_queued
+            )),
+            Line::from(format!(
+                "Cached: {} torrents, ~{}",
+                metrics.cached_torrents,
+                convert_bytes(i64::try_from(metrics.cached_bytes).unwrap_or(i64::MAX))
+            )),
+            Line::from("Latest calls:".bold()),
+        ];
+        lines.extend(metrics.calls.iter().rev().take(CALLS_SHOWN).map(|call| {
+            let line = Line::from(format!("{:<28} {:>8}", call.method, millis(call.elapsed)));
+            match call.ok {
+                true => line,
+                false => line.fg(self.colors.error_fg),
+            }
+        }));
+
+        #[allow(clippy::cast_possible_truncation)]
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let width = STATS_WIDTH.min(area.width);
+        let rect = Rect::new(area.right() - width, area.y, width, height);
+        let paragraph = Paragraph::new(lines)
+            .style(
+                Style::default()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(self.colors.footer_border_color))
+                    .title("Stats".bold()),
+            );
+        frame.render_widget(Clear, rect);
+        frame.render_widget(paragraph, rect);
+        Ok(())
+    }
+}
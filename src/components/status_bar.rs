@@ -0,0 +1,113 @@
+use std::time::Instant;
+
+use color_eyre::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{action::Action, app::Mode, colors::Colors};
+
+use super::{main_layout, Component};
+
+pub struct StatusBar {
+    mode: Mode,
+    crumbs: Vec<String>,
+    /// When the daemon is checked again, while it doesn't answer.
+    retry_at: Option<Instant>,
+    colors: Colors,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self {
+            mode: Mode::Home,
+            crumbs: Vec::new(),
+            retry_at: None,
+            colors: Colors::new(),
+        }
+    }
+}
+
+impl Component for StatusBar {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Mode(mode, _) => {
+                self.mode = mode;
+                self.crumbs.clear();
+            }
+            Action::Breadcrumbs(crumbs) => self.crumbs = crumbs,
+            Action::Disconnected(delay) => self.retry_at = Some(Instant::now() + delay),
+            Action::Reconnected => self.retry_at = None,
+            Action::Theme(_) => self.colors = Colors::new(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [header, _, _] = main_layout(area);
+        let mode_style = Style::default()
+            .fg(self.colors.tab_selected)
+            .add_modifier(Modifier::REVERSED | Modifier::BOLD);
+
+        let mut spans = vec![Span::styled(format!(" {} ", self.mode), mode_style)];
+        for crumb in &self.crumbs {
+            spans.push(Span::raw(" › "));
+            spans.push(Span::raw(crumb.clone()));
+        }
+
+        if let Some(retry_at) = self.retry_at {
+            let retry = match retry_at.saturating_duration_since(Instant::now()).as_secs() {
+                0 => "retrying now".to_string(),
+                secs => format!("retrying in {secs}s"),
+            };
+            let banner = Paragraph::new(format!(" Disconnected — {retry} "))
+                .centered()
+                .style(
+                    Style::default()
+                        .fg(self.colors.buffer_bg)
+                        .bg(self.colors.error_fg)
+                        .add_modifier(Modifier::BOLD),
+                );
+            frame.render_widget(banner, header);
+            return Ok(());
+        }
+
+        let bar = Paragraph::new(Line::from(spans)).style(
+            Style::default()
+                .fg(self.colors.header_fg)
+                .bg(self.colors.header_bg),
+        );
+        frame.render_widget(bar, header);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_snapshot() {
+        let mut status_bar = StatusBar::new();
+        status_bar
+            .update(Action::Mode(Mode::Properties, 1))
+            .unwrap();
+        status_bar
+            .update(Action::Breadcrumbs(vec![
+                "torrent 1".to_string(),
+                "Files".to_string(),
+            ]))
+            .unwrap();
+        for size in SIZES {
+            assert_snapshot("status_bar", size, |frame| {
+                status_bar.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
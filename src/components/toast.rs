@@ -0,0 +1,224 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use color_eyre::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    action::{Action, Failure, Level},
+    colors::Colors,
+    utils::redact_urls,
+};
+
+use super::{main_layout, Component};
+
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+/// Errors stay longer, there's more to read and they matter more.
+const ERROR_DURATION: Duration = Duration::from_secs(10);
+const TOAST_WIDTH: u16 = 50;
+const TOAST_HEIGHT: u16 = 4;
+const MAX_TOASTS: usize = 3;
+
+/// A message shown, with what it's about for errors.
+#[derive(Debug)]
+struct Entry {
+    created: Instant,
+    level: Level,
+    source: Option<String>,
+    torrent: Option<i64>,
+    message: String,
+    /// How many times it was sent while shown.
+    count: usize,
+}
+
+impl Entry {
+    fn new(level: Level, message: &str) -> Self {
+        Self {
+            created: Instant::now(),
+            level,
+            source: None,
+            torrent: None,
+            message: redact_urls(message),
+            count: 1,
+        }
+    }
+
+    fn from_failure(failure: Failure) -> Self {
+        Self {
+            source: Some(failure.source),
+            torrent: failure.torrent,
+            ..Self::new(failure.level, &failure.message)
+        }
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        self.level == other.level
+            && self.source == other.source
+            && self.torrent == other.torrent
+            && self.message == other.message
+    }
+
+    fn title(&self) -> String {
+        let mut parts = Vec::new();
+        match self.level {
+            Level::Info => {}
+            Level::Success => parts.push("✓".to_string()),
+            Level::Warning => parts.push("Warning".to_string()),
+            Level::Error => parts.push("Error".to_string()),
+        }
+        parts.extend(self.source.clone());
+        parts.extend(self.torrent.map(|id| format!("torrent {id}")));
+        match parts.is_empty() {
+            true => String::new(),
+            false => format!(" {} ", parts.join(" · ")),
+        }
+    }
+
+    fn text(&self) -> String {
+        match self.count {
+            1 => self.message.clone(),
+            count => format!("{} (×{count})", self.message),
+        }
+    }
+}
+
+/// Transient messages stacked above the footer in the bottom-right corner.
+pub struct Toast {
+    messages: VecDeque<Entry>,
+    colors: Colors,
+}
+
+impl Toast {
+    pub fn new() -> Self {
+        Self {
+            messages: VecDeque::new(),
+            colors: Colors::new(),
+        }
+    }
+
+    /// Show `entry`, counting it on the toast of the same message if still shown.
+    fn push(&mut self, entry: Entry) {
+        if let Some(index) = self.messages.iter().position(|e| e.same_as(&entry)) {
+            let mut shown = self.messages.remove(index).expect("index is in bounds");
+            shown.count += 1;
+            shown.created = entry.created;
+            self.messages.push_back(shown);
+            return;
+        }
+        self.messages.push_back(entry);
+        if self.messages.len() > MAX_TOASTS {
+            self.messages.pop_front();
+        }
+    }
+}
+
+impl Component for Toast {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Notify(level, message) => self.push(Entry::new(level, &message)),
+            Action::Error(failure) => self.push(Entry::from_failure(failure)),
+            Action::Tick | Action::Render => {
+                self.messages
+                    .retain(|entry| entry.created.elapsed() < duration(entry.level));
+            }
+            Action::Theme(_) => self.colors = Colors::new(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let width = TOAST_WIDTH.min(body.width);
+        let mut bottom = body.bottom();
+        for entry in self.messages.iter().rev() {
+            if bottom < body.y + TOAST_HEIGHT {
+                break;
+            }
+            bottom -= TOAST_HEIGHT;
+            let rect = Rect::new(body.right() - width, bottom, width, TOAST_HEIGHT);
+            let border_color = match entry.level {
+                Level::Info | Level::Success => self.colors.footer_border_color,
+                Level::Warning => self.colors.warning_fg,
+                Level::Error => self.colors.error_fg,
+            };
+            let toast = Paragraph::new(entry.text())
+                .wrap(Wrap { trim: true })
+                .style(
+                    Style::default()
+                        .fg(self.colors.row_fg)
+                        .bg(self.colors.buffer_bg),
+                )
+                .block(
+                    Block::bordered()
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(border_color))
+                        .title(entry.title().bold().fg(border_color)),
+                );
+            frame.render_widget(Clear, rect);
+            frame.render_widget(toast, rect);
+        }
+        Ok(())
+    }
+}
+
+fn duration(level: Level) -> Duration {
+    match level {
+        Level::Error => ERROR_DURATION,
+        _ => TOAST_DURATION,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::snapshot::{assert_snapshot, SIZES};
+
+    #[test]
+    fn test_repeated_errors_are_grouped() {
+        let mut toast = Toast::new();
+        let failure = Failure::new("Home", "Connection refused").torrent(4);
+        toast.update(Action::Error(failure.clone())).unwrap();
+        toast
+            .update(Action::Notify(Level::Info, "Refreshed".to_string()))
+            .unwrap();
+        toast.update(Action::Error(failure.clone())).unwrap();
+        toast
+            .update(Action::Error(failure.level(Level::Warning)))
+            .unwrap();
+
+        assert_eq!(toast.messages.len(), 3);
+        let grouped = &toast.messages[1];
+        assert_eq!(grouped.text(), "Connection refused (×2)");
+        assert_eq!(grouped.title(), " Error · Home · torrent 4 ");
+        assert_eq!(toast.messages[0].title(), "");
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut toast = Toast::new();
+        toast
+            .update(Action::Notify(
+                Level::Success,
+                "Started ubuntu.iso".to_string(),
+            ))
+            .unwrap();
+        toast
+            .update(Action::Error(
+                Failure::new("Properties", "Torrent not found").torrent(7),
+            ))
+            .unwrap();
+        for size in SIZES {
+            assert_snapshot("toast", size, |frame| {
+                toast.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use std::{cell::RefCell, cmp::Ordering, fmt::Write as _, fs, path::PathBuf, rc::Rc};
+
+use chrono::Local;
+use color_eyre::Result;
+use futures::executor::block_on;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style, Stylize},
+    widgets::{Block, Cell, HighlightSpacing, Paragraph, Row, Table, TableState},
+    Frame,
+};
+use strum::{EnumIter, FromRepr, IntoEnumIterator};
+
+use crate::{
+    action::{Action, Failure, Level},
+    app::Mode,
+    colors::Colors,
+    config::get_data_dir,
+    data::{map_torrent_list, traffic_by_tracker, Traffic},
+    rpc::Client,
+    utils::{convert_bytes, locale, LocaleFormat},
+};
+
+use super::{main_layout, Component};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, FromRepr)]
+enum Column {
+    Host,
+    Torrents,
+    Downloaded,
+    #[default]
+    Uploaded,
+    Ratio,
+    Down,
+    Up,
+}
+
+impl Column {
+    fn title(self) -> &'static str {
+        match self {
+            Self::Host => "HOST",
+            Self::Torrents => "TORRENTS",
+            Self::Downloaded => "DOWNLOADED",
+            Self::Uploaded => "UPLOADED",
+            Self::Ratio => "RATIO",
+            Self::Down => "DOWN",
+            Self::Up => "UP",
+        }
+    }
+
+    /// The next column, wrapping around to the first.
+    fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or(Self::Host)
+    }
+
+    fn compare(self, (a_host, a): &(String, Traffic), (b_host, b): &(String, Traffic)) -> Ordering {
+        match self {
+            Self::Host => a_host.cmp(b_host),
+            Self::Torrents => a.torrents.cmp(&b.torrents),
+            Self::Downloaded => a.downloaded.cmp(&b.downloaded),
+            Self::Uploaded => a.uploaded.cmp(&b.uploaded),
+            Self::Ratio => a.ratio().total_cmp(&b.ratio()),
+            Self::Down => a.rate_download.cmp(&b.rate_download),
+            Self::Up => a.rate_upload.cmp(&b.rate_upload),
+        }
+    }
+}
+
+/// Report of the traffic of the torrents per tracker host, to keep an eye on ratios.
+pub struct Trackers {
+    client: Rc<RefCell<Client>>,
+    rows: Vec<(String, Traffic)>,
+    column: Column,
+    descending: bool,
+    /// Torrent selected in Home, restored when going back.
+    home_id: i64,
+    state: TableState,
+    colors: Colors,
+}
+
+impl Trackers {
+    pub fn new(client: Rc<RefCell<Client>>, home_id: i64) -> Result<Self> {
+        let mut trackers = Self {
+            client,
+            rows: Vec::new(),
+            column: Column::default(),
+            descending: true,
+            home_id,
+            state: TableState::default().with_selected(Some(0)),
+            colors: Colors::new(),
+        };
+        trackers.refresh()?;
+        Ok(trackers)
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        let torrents = block_on(map_torrent_list(&self.client))?;
+        self.rows = traffic_by_tracker(&torrents);
+        self.sort();
+        Ok(())
+    }
+
+    fn sort(&mut self) {
+        let (column, descending) = (self.column, self.descending);
+        self.rows.sort_by(|a, b| match descending {
+            true => column.compare(b, a),
+            false => column.compare(a, b),
+        });
+    }
+
+    /// Write the report to a timestamped file in the data directory.
+    fn export(&self) -> Result<PathBuf> {
+        let directory = get_data_dir();
+        fs::create_dir_all(&directory)?;
+        let path = directory.join(format!(
+            "trackers-{}.csv",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        fs::write(&path, to_csv(&self.rows, locale()))?;
+        Ok(path)
+    }
+}
+
+impl Component for Trackers {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let last = self.rows.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0);
+        match action {
+            Action::Tick | Action::Refresh => {
+                if let Err(err) = self.refresh() {
+                    let failure = Failure::new("Trackers", err).level(Level::Warning);
+                    return Ok(Some(Action::Error(failure)));
+                }
+            }
+            Action::Back => return Ok(Some(Action::Mode(Mode::Home, self.home_id))),
+            Action::Theme(_) => self.colors = Colors::new(),
+            Action::Down => self.state.select(Some((selected + 1).min(last))),
+            Action::Up => self.state.select(Some(selected.saturating_sub(1))),
+            Action::Top => self.state.select(Some(0)),
+            Action::Bottom => self.state.select(Some(last)),
+            Action::NextSort => {
+                self.column = self.column.next();
+                // Hosts read best A to Z, the numbers largest first.
+                self.descending = self.column != Column::Host;
+                self.sort();
+            }
+            Action::ReverseSort => {
+                self.descending = !self.descending;
+                self.sort();
+            }
+            Action::Export => {
+                return Ok(Some(match self.export() {
+                    Ok(path) => {
+                        Action::Notify(Level::Success, format!("Exported to {}", path.display()))
+                    }
+                    Err(err) => Action::error("Trackers", format!("Failed to export: {err}")),
+                }));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [_, body, _] = main_layout(area);
+        let style = Style::default()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let block = Block::bordered()
+            .border_style(Style::default().fg(self.colors.footer_border_color))
+            .title("Trackers".bold().fg(self.colors.row_fg));
+
+        if self.rows.is_empty() {
+            let empty = Paragraph::new("No torrents").style(style).block(block);
+            frame.render_widget(empty, body);
+            return Ok(());
+        }
+
+        let arrow = if self.descending { "▼" } else { "▲" };
+        let header = Column::iter()
+            .map(|column| match column == self.column {
+                true => format!("{} {arrow}", column.title()),
+                false => column.title().to_string(),
+            })
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(
+                Style::default()
+                    .fg(self.colors.header_fg)
+                    .bg(self.colors.header_bg),
+            );
+        let rows = self.rows.iter().map(|(host, traffic)| {
+            Row::new([
+                host.clone(),
+                locale().number(traffic.torrents as i64),
+                convert_bytes(traffic.downloaded),
+                convert_bytes(traffic.uploaded),
+                locale().decimal(traffic.ratio(), 2),
+                format!("{}/s", convert_bytes(traffic.rate_download)),
+                format!("{}/s", convert_bytes(traffic.rate_upload)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(10),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(8),
+                Constraint::Length(12),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .style(style)
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::REVERSED)
+                .fg(self.colors.selected_style_fg),
+        )
+        .highlight_spacing(HighlightSpacing::Always);
+        frame.render_stateful_widget(table, body, &mut self.state);
+        Ok(())
+    }
+}
+
+/// The report as CSV, with raw byte counts so it can be summed up elsewhere.
+///
+/// Ratios use the decimal separator of `format`, and fields are separated by semicolons when
+/// that is a comma, as spreadsheets of such locales expect.
+fn to_csv(rows: &[(String, Traffic)], format: &LocaleFormat) -> String {
+    let separator = match format.decimal_separator.as_str() {
+        "," => ';',
+        _ => ',',
+    };
+    let mut csv = [
+        "host",
+        "torrents",
+        "downloaded_bytes",
+        "uploaded_bytes",
+        "ratio",
+        "download_rate",
+        "upload_rate",
+    ]
+    .join(&separator.to_string());
+    csv.push('\n');
+    for (host, traffic) in rows {
+        let host = match host.contains([separator, '"']) {
+            true => format!("\"{}\"", host.replace('"', "\"\"")),
+            false => host.clone(),
+        };
+        let ratio = match format.decimal_separator.as_str() {
+            "," => format!("{:.3}", traffic.ratio()).replace('.', ","),
+            _ => format!("{:.3}", traffic.ratio()),
+        };
+        let _ = writeln!(
+            csv,
+            "{host}{separator}{}{separator}{}{separator}{}{separator}{ratio}{separator}{}{separator}{}",
+            traffic.torrents,
+            traffic.downloaded,
+            traffic.uploaded,
+            traffic.rate_download,
+            traffic.rate_upload,
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        components::snapshot::{assert_snapshot, SIZES},
+        data::fixtures::torrent_json,
+        rpc::fixtures::{serve, success},
+    };
+
+    #[test]
+    fn test_to_csv() {
+        let traffic = Traffic {
+            torrents: 2,
+            downloaded: 1000,
+            uploaded: 1500,
+            rate_download: 0,
+            rate_upload: 20,
+        };
+        let rows = [
+            ("tracker.example.org".to_string(), traffic),
+            ("a,\"b\"".to_string(), Traffic::default()),
+        ];
+        assert_eq!(
+            to_csv(&rows, &LocaleFormat::default()),
+            "host,torrents,downloaded_bytes,uploaded_bytes,ratio,download_rate,upload_rate\n\
+             tracker.example.org,2,1000,1500,1.500,0,20\n\
+             \"a,\"\"b\"\"\",0,0,0,0.000,0,0\n"
+        );
+        let format = LocaleFormat {
+            decimal_separator: ",".to_string(),
+            ..LocaleFormat::default()
+        };
+        assert_eq!(
+            to_csv(&rows[..1], &format),
+            "host;torrents;downloaded_bytes;uploaded_bytes;ratio;download_rate;upload_rate\n\
+             tracker.example.org;2;1000;1500;1,500;0;20\n"
+        );
+    }
+
+    #[test]
+    fn test_column_next() {
+        assert_eq!(Column::Host.next(), Column::Torrents);
+        assert_eq!(Column::Up.next(), Column::Host);
+    }
+
+    // The torrents without trackers add up under a row of their own.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_snapshot() {
+        let url = serve(vec![
+            (
+                "200 OK",
+                success(json!({ "torrents": [torrent_json(1, false), torrent_json(2, false)] })),
+            ),
+            ("200 OK", success(json!({ "torrents": [] }))),
+        ]);
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let mut trackers = Trackers::new(client, 1).unwrap();
+        for size in SIZES {
+            assert_snapshot("trackers", size, |frame| {
+                trackers.draw(frame, frame.area()).unwrap();
+            });
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use color_eyre::Result;
+use futures::executor::block_on;
+use ratatui::{layout::Rect, Frame};
+
+use crate::{
+    action::{Action, Failure, Level},
+    app,
+    config::{Config, SiteConfig},
+    data::add_torrent,
+    rpc::Client,
+};
+
+use super::Component;
+
+/// Files modified more recently than this may still be being written.
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// Adds the `.torrent` files dropped in `watch_dir` to the daemon, for daemons on another host
+/// that can't see the directory themselves.
+///
+/// The directory is listed at every tick rather than watched for filesystem events: it's often
+/// a network mount, where inotify and the like miss the files written from other machines, and
+/// the settle time needs the modification times anyway.
+pub struct WatchDir {
+    client: Rc<RefCell<Client>>,
+    dir: Option<PathBuf>,
+    /// Delete the added files rather than renaming them to `.torrent.added`.
+    delete: bool,
+    sites: HashMap<String, SiteConfig>,
+    /// Files the daemon refused, not tried again until they're modified. Files that failed for
+    /// want of the daemon are tried again at the next tick.
+    failed: HashMap<PathBuf, SystemTime>,
+    /// Whether the directory couldn't be read at the previous tick, to report it once.
+    unreadable: bool,
+}
+
+impl WatchDir {
+    pub fn new(client: Rc<RefCell<Client>>) -> Self {
+        Self {
+            client,
+            dir: None,
+            delete: false,
+            sites: HashMap::new(),
+            failed: HashMap::new(),
+            unreadable: false,
+        }
+    }
+
+    /// Add the new files of the directory, reporting the first outcome worth telling.
+    fn check(&mut self, dir: &Path) -> Option<Action> {
+        let files = match pending(dir, SystemTime::now()) {
+            Ok(files) => {
+                self.unreadable = false;
+                files
+            }
+            Err(err) if !self.unreadable => {
+                self.unreadable = true;
+                let message = format!("Can't read {}: {err}", dir.display());
+                return Some(Action::Error(Failure::new("Watch", message)));
+            }
+            Err(_) => return None,
+        };
+        // Forget the files that went away.
+        let current = files.iter().map(|(path, _)| path).collect::<HashSet<_>>();
+        self.failed.retain(|path, _| current.contains(path));
+        let mut outcome = None;
+        for (path, modified) in files {
+            if self.failed.get(&path) == Some(&modified) {
+                continue;
+            }
+            let source = path.to_string_lossy().into_owned();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let added = block_on(add_torrent(
+                &self.client,
+                &self.sites,
+                source,
+                String::new(),
+                false,
+            ));
+            let action = match added {
+                Ok((_, duplicate)) => match self.finish(&path) {
+                    Ok(()) if duplicate => {
+                        Action::Notify(Level::Info, format!("{name} was already added"))
+                    }
+                    Ok(()) => Action::Notify(Level::Success, format!("Added {name}")),
+                    Err(err) => {
+                        // Left in place, it would be added again at every tick.
+                        self.failed.insert(path.clone(), modified);
+                        let message = format!("Added {name} but can't move it away: {err}");
+                        Action::Error(Failure::new("Watch", message).level(Level::Warning))
+                    }
+                },
+                Err(err) if refused(&*err) => {
+                    self.failed.insert(path.clone(), modified);
+                    Action::Error(Failure::new("Watch", format!("Can't add {name}: {err}")))
+                }
+                Err(err) => {
+                    let message = format!("Can't add {name} yet: {err}");
+                    Action::Error(Failure::new("Watch", message).level(Level::Warning))
+                }
+            };
+            outcome.get_or_insert(action);
+        }
+        outcome
+    }
+
+    /// Get the added file out of the way.
+    fn finish(&self, path: &Path) -> std::io::Result<()> {
+        match self.delete {
+            true => fs::remove_file(path),
+            false => fs::rename(path, added_path(path)),
+        }
+    }
+}
+
+/// The `.torrent` files of `dir` that stopped changing by `now`, with their modification time.
+fn pending(dir: &Path, now: SystemTime) -> std::io::Result<Vec<(PathBuf, SystemTime)>> {
+    let mut files = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let is_torrent = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("torrent"));
+            let metadata = fs::metadata(&path).ok()?;
+            let modified = metadata.modified().ok()?;
+            let settled = now
+                .duration_since(modified)
+                .is_ok_and(|age| age >= SETTLE_TIME);
+            (is_torrent && metadata.is_file() && settled).then_some((path, modified))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+/// Whether adding failed because the daemon refused the file, as opposed to not being reached
+/// or not answering properly.
+fn refused(err: &(dyn Error + 'static)) -> bool {
+    err.downcast_ref::<app::Error>().is_some()
+}
+
+/// Where an added file is renamed to, as Transmission does with its own watch directory.
+fn added_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".added");
+    name.into()
+}
+
+impl Component for WatchDir {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.dir = config.watch_dir;
+        self.delete = config.watch_delete;
+        self.sites = config.sites;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match (action, self.dir.clone()) {
+            (Action::Tick, Some(dir)) => Ok(self.check(&dir)),
+            _ => Ok(None),
+        }
+    }
+
+    fn draw(&mut self, _frame: &mut Frame, _area: Rect) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending() {
+        let dir = std::env::temp_dir().join("sparrow-test-watch-dir");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested.torrent")).unwrap();
+        for name in ["b.torrent", "a.TORRENT", "notes.txt", "c.torrent.added"] {
+            fs::write(dir.join(name), b"d8:announce0:e").unwrap();
+        }
+        let later = SystemTime::now() + SETTLE_TIME;
+        let names = |now| {
+            pending(&dir, now)
+                .unwrap()
+                .into_iter()
+                .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(later), ["a.TORRENT", "b.torrent"]);
+        assert!(names(SystemTime::now()).is_empty());
+        assert_eq!(
+            added_path(&dir.join("b.torrent")),
+            dir.join("b.torrent.added")
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_refused() {
+        let answer: Box<dyn Error> =
+            app::Error::WithMessage("invalid or corrupt torrent".into()).into();
+        assert!(refused(&*answer));
+        let io = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        let transport: Box<dyn Error> = io.into();
+        assert!(!refused(&*transport));
+    }
+}
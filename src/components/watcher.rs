@@ -0,0 +1,429 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
+
+use color_eyre::Result;
+use futures::executor::block_on;
+use ratatui::{layout::Rect, Frame};
+use tokio::sync::mpsc::UnboundedSender;
+use transmission_rpc::types::{Id, TorrentAction};
+
+use crate::{
+    action::{Action, Activity, Failure, Level},
+    config::{in_domains, Config, FreeSpaceConfig, LabelRule},
+    data::{
+        data_path, fetch_free_space, fetch_session_seed_limits, fetch_snapshots, rule_labels,
+        set_honors_session_limits, set_labels, Snapshot,
+    },
+    notify::{Event, Notifier},
+    rpc::Client,
+    utils::{count, redact_urls},
+};
+
+use super::Component;
+
+/// Compares torrents between ticks and reports noteworthy transitions.
+pub struct Watcher {
+    client: Rc<RefCell<Client>>,
+    previous: Option<HashMap<i64, Snapshot>>,
+    notifier: Notifier,
+    label_rules: Vec<LabelRule>,
+    /// Tracker hosts whose torrents are made to ignore the speed limits of the session.
+    alt_speed_exempt: Vec<String>,
+    free_space: FreeSpaceConfig,
+    /// Downloads paused for lack of space, by download directory, to resume once it's freed.
+    space_paused: HashMap<String, Vec<i64>>,
+    command_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Watcher {
+    pub fn new(client: Rc<RefCell<Client>>) -> Self {
+        Self {
+            client,
+            previous: None,
+            notifier: Notifier::default(),
+            label_rules: Vec::new(),
+            alt_speed_exempt: Vec::new(),
+            free_space: FreeSpaceConfig::default(),
+            space_paused: HashMap::new(),
+            command_tx: None,
+        }
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // Connection errors are already reported by the visible components.
+        let Ok(snapshots) = block_on(fetch_snapshots(&self.client)) else {
+            return Ok(());
+        };
+        let current: HashMap<i64, Snapshot> = snapshots.into_iter().map(|s| (s.id, s)).collect();
+
+        if let Some(previous) = &self.previous {
+            for (activity, text) in activity(previous, &current) {
+                self.send(Action::Activity(activity, text))?;
+            }
+
+            let completed = current
+                .values()
+                .filter(|t| {
+                    t.percent_done >= 1.0
+                        && previous.get(&t.id).is_some_and(|p| p.percent_done < 1.0)
+                })
+                .collect::<Vec<_>>();
+            if !completed.is_empty() {
+                let session = block_on(fetch_session_seed_limits(&self.client));
+                for torrent in completed {
+                    let mut message = format!("Completed: {}", torrent.name);
+                    if let Ok(session) = &session {
+                        message.push_str(&format!("\n{}", torrent.seeding_policy(session)));
+                    }
+                    self.notify(Event::Completed, "Torrent completed", &torrent.name);
+                    self.send(Action::Notify(Level::Success, message))?;
+                }
+            }
+
+            let errored = current
+                .values()
+                .filter(|t| t.error != 0 && previous.get(&t.id).is_some_and(|p| p.error == 0));
+            for torrent in errored {
+                let message = format!("{}: {}", torrent.name, redact_urls(&torrent.error_string));
+                self.notify(Event::Error, "Torrent error", &message);
+                let failure = Failure::new("Watcher", message).torrent(torrent.id);
+                self.send(Action::Error(failure))?;
+            }
+
+            let added = current
+                .values()
+                .filter(|t| t.labels.is_empty() && !previous.contains_key(&t.id))
+                .map(|t| (t.id, data_path(&t.download_dir, &t.name)));
+            for (label, ids) in rule_labels(&self.label_rules, added) {
+                let count = count(ids.len(), "new torrent");
+                match block_on(set_labels(&self.client, ids, vec![label.clone()])) {
+                    Ok(()) => self.send(Action::Notify(
+                        Level::Info,
+                        format!("Labeled {count} as {label}"),
+                    ))?,
+                    Err(err) => {
+                        self.send(Action::error("Watcher", format!("Failed to label: {err}")))?
+                    }
+                }
+            }
+        }
+        self.exempt_from_limits(&current)?;
+        self.guard_free_space(&current)?;
+        self.previous = Some(current);
+        Ok(())
+    }
+
+    /// Make the torrents of `alt_speed_exempt` trackers seen for the first time, or all of them
+    /// on the first check, ignore the speed limits of the session.
+    fn exempt_from_limits(&self, current: &HashMap<i64, Snapshot>) -> Result<()> {
+        let ids = current
+            .values()
+            .filter(|t| t.honors_session_limits)
+            .filter(|t| {
+                self.previous
+                    .as_ref()
+                    .is_none_or(|p| !p.contains_key(&t.id))
+            })
+            .filter(|t| {
+                t.tracker_hosts()
+                    .iter()
+                    .any(|host| in_domains(&self.alt_speed_exempt, host))
+            })
+            .map(|t| t.id)
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let count = count(ids.len(), "torrent");
+        match block_on(set_honors_session_limits(&self.client, ids, false)) {
+            Ok(()) => self.send(Action::Notify(
+                Level::Info,
+                format!("Exempted {count} from the speed limits"),
+            )),
+            Err(err) => self.send(Action::error("Watcher", format!("Failed to exempt: {err}"))),
+        }
+    }
+
+    /// Pause the downloads of directories running out of space, see `pause_below_gb`, and
+    /// resume the ones paused this way once enough space is freed. Downloads started in a
+    /// paused directory are paused too, on every tick until it has space again.
+    fn guard_free_space(&mut self, current: &HashMap<i64, Snapshot>) -> Result<()> {
+        if self.free_space.pause_below_gb.is_none() {
+            self.space_paused.clear();
+            return Ok(());
+        }
+        let mut downloading: HashMap<&str, Vec<i64>> = HashMap::new();
+        for torrent in current.values().filter(|t| t.is_downloading()) {
+            downloading
+                .entry(torrent.download_dir.as_str())
+                .or_default()
+                .push(torrent.id);
+        }
+        let directories = downloading
+            .keys()
+            .map(|dir| (*dir).to_string())
+            .chain(self.space_paused.keys().cloned())
+            .collect::<BTreeSet<_>>();
+        for directory in directories {
+            let Ok(free) = block_on(fetch_free_space(&self.client, &directory)) else {
+                continue;
+            };
+            let paused = self.space_paused.contains_key(&directory);
+            match (paused, self.free_space.should_pause(free, paused)) {
+                (false, true) => {
+                    let ids = downloading.remove(directory.as_str()).unwrap_or_default();
+                    let message = format!(
+                        "Paused {} for lack of space in {directory}",
+                        count(ids.len(), "download")
+                    );
+                    self.space_paused.insert(directory, ids.clone());
+                    self.act(TorrentAction::Stop, ids, Level::Warning, message)?;
+                }
+                (true, false) => {
+                    let ids = self
+                        .space_paused
+                        .remove(&directory)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|id| current.contains_key(id))
+                        .collect::<Vec<_>>();
+                    let message = format!(
+                        "Resumed {} as {directory} has space again",
+                        count(ids.len(), "download")
+                    );
+                    self.act(TorrentAction::Start, ids, Level::Info, message)?;
+                }
+                (true, true) => {
+                    // Downloads started or added since, they resume with the others.
+                    let ids = downloading.remove(directory.as_str()).unwrap_or_default();
+                    let paused = self.space_paused.entry(directory.clone()).or_default();
+                    for id in &ids {
+                        if !paused.contains(id) {
+                            paused.push(*id);
+                        }
+                    }
+                    let message = format!(
+                        "Paused {} started in {directory} while it lacks space",
+                        count(ids.len(), "download")
+                    );
+                    self.act(TorrentAction::Stop, ids, Level::Warning, message)?;
+                }
+                (false, false) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply `action` to the torrents with the given ids, notifying with `message` if it worked.
+    fn act(
+        &self,
+        action: TorrentAction,
+        ids: Vec<i64>,
+        level: Level,
+        message: String,
+    ) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let res = {
+            let mut client = self.client.borrow_mut();
+            let ids = ids.into_iter().map(Id::Id).collect();
+            async move { client.torrent_action(action, ids).await }
+        };
+        match block_on(res) {
+            Ok(_) => self.send(Action::Notify(level, message)),
+            Err(err) => {
+                let verb = match action {
+                    TorrentAction::Stop => "pause",
+                    _ => "resume",
+                };
+                self.send(Action::error(
+                    "Watcher",
+                    format!("Failed to {verb} downloads: {err}"),
+                ))
+            }
+        }
+    }
+
+    fn notify(&self, event: Event, title: &str, message: &str) {
+        if let Some(tx) = &self.command_tx {
+            self.notifier.notify(event, title, message, tx);
+        }
+    }
+
+    fn send(&self, action: Action) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(action)?;
+        }
+        Ok(())
+    }
+}
+
+/// What happened to the torrents from `previous` to `current`, by id, with the torrent's name
+/// or its name and error.
+fn activity(
+    previous: &HashMap<i64, Snapshot>,
+    current: &HashMap<i64, Snapshot>,
+) -> Vec<(Activity, String)> {
+    let mut events = Vec::new();
+    let mut ids = current.keys().chain(previous.keys()).collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+    for id in ids {
+        let (before, torrent) = match (previous.get(id), current.get(id)) {
+            (Some(before), Some(torrent)) => (before, torrent),
+            (None, Some(torrent)) => {
+                events.push((Activity::Added, torrent.name.clone()));
+                continue;
+            }
+            (Some(torrent), None) => {
+                events.push((Activity::Removed, torrent.name.clone()));
+                continue;
+            }
+            (None, None) => continue,
+        };
+        if torrent.percent_done >= 1.0 && before.percent_done < 1.0 {
+            events.push((Activity::Completed, torrent.name.clone()));
+        }
+        if torrent.is_stalled && !before.is_stalled && torrent.percent_done < 1.0 {
+            events.push((Activity::Stalled, torrent.name.clone()));
+        }
+        if torrent.error != before.error {
+            let text = format!("{}: {}", torrent.name, redact_urls(&torrent.error_string));
+            // Transmission's codes: 1 and 2 for trackers, 3 for the torrent itself.
+            match torrent.error {
+                1 | 2 => events.push((Activity::TrackerError, text)),
+                3 => events.push((Activity::Error, text)),
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+impl Component for Watcher {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        let unknown = config.notifications.unknown_backends();
+        if !unknown.is_empty() {
+            self.send(Action::Notify(
+                Level::Warning,
+                format!("Unknown notification backends: {}", unknown.join(", ")),
+            ))?;
+        }
+        self.notifier = Notifier::new(config.notifications);
+        self.label_rules = config.label_rules;
+        self.alt_speed_exempt = config.alt_speed_exempt;
+        self.free_space = config.free_space;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::Tick {
+            self.check()?;
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, _frame: &mut Frame, _area: Rect) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::rpc::fixtures::{serve, success};
+
+    fn snapshot(id: i64, percent_done: f32, is_stalled: bool, error: i64) -> (i64, Snapshot) {
+        let snapshot = serde_json::from_value(json!({
+            "id": id,
+            "name": format!("torrent {id}"),
+            "percentDone": percent_done,
+            "isStalled": is_stalled,
+            "seedRatioMode": 0,
+            "seedRatioLimit": 2.0,
+            "seedIdleMode": 0,
+            "seedIdleLimit": 30,
+            "error": error,
+            "errorString": match error {
+                0 => "",
+                _ => "Tracker gave HTTP response code 404",
+            },
+            "downloadDir": "/data",
+        }))
+        .unwrap();
+        (id, snapshot)
+    }
+
+    #[test]
+    fn test_activity() {
+        let previous = HashMap::from([
+            snapshot(1, 0.5, false, 0),
+            snapshot(2, 0.5, false, 0),
+            snapshot(3, 1.0, false, 0),
+            snapshot(4, 0.2, false, 0),
+        ]);
+        let current = HashMap::from([
+            snapshot(1, 1.0, false, 0),
+            snapshot(2, 0.5, true, 0),
+            snapshot(3, 1.0, true, 2),
+            snapshot(5, 0.0, false, 0),
+        ]);
+        assert_eq!(
+            activity(&previous, &current),
+            [
+                (Activity::Completed, "torrent 1".to_string()),
+                (Activity::Stalled, "torrent 2".to_string()),
+                (
+                    Activity::TrackerError,
+                    "torrent 3: Tracker gave HTTP response code 404".to_string()
+                ),
+                (Activity::Removed, "torrent 4".to_string()),
+                (Activity::Added, "torrent 5".to_string()),
+            ]
+        );
+        assert!(activity(&current, &current).is_empty());
+    }
+
+    // The calls block on the runtime's reactor, which needs another thread to run.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_downloads_started_in_a_paused_directory_are_paused() {
+        // The free space of `/data`, then the stop.
+        let url = serve(vec![
+            (
+                "200 OK",
+                success(json!({ "path": "/data", "size-bytes": 0 })),
+            ),
+            ("200 OK", success(json!({}))),
+        ]);
+        let client = Rc::new(RefCell::new(Client::new(url, None, reqwest::Client::new())));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher = Watcher::new(client);
+        watcher.command_tx = Some(tx);
+        watcher.free_space.pause_below_gb = Some(5.0);
+        watcher.space_paused.insert("/data".to_string(), vec![1]);
+        let (id, mut started) = snapshot(2, 0.5, false, 0);
+        started.status = 4;
+        watcher
+            .guard_free_space(&HashMap::from([snapshot(1, 0.5, false, 0), (id, started)]))
+            .unwrap();
+        assert_eq!(watcher.space_paused["/data"], [1, 2]);
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            Action::Notify(
+                Level::Warning,
+                "Paused a download started in /data while it lacks space".to_string()
+            )
+        );
+    }
+}
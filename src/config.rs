@@ -1,6 +1,11 @@
 #![allow(dead_code)] // Remove this once you start using the code
 
-use std::{collections::HashMap, env, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    path::PathBuf,
+    time::Duration,
+};
 
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -11,7 +16,15 @@ use ratatui::style::{Color, Modifier, Style};
 use serde::{de::Deserializer, Deserialize};
 use tracing::error;
 
-use crate::{action::Action, app::Mode};
+use crate::{
+    action::Action,
+    app::Mode,
+    colors::{Colors, CustomTheme, BUILTIN_THEMES},
+    notify::NotificationsConfig,
+    opener::OpenConfig,
+    utils::{EtaFormat, LocaleFormat, PercentFormat},
+    view::{Column, Group, Sort, StatusFilter, View, DEFAULT_COLUMNS},
+};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
@@ -31,6 +44,387 @@ pub struct Config {
     pub keybindings: KeyBindings,
     #[serde(default)]
     pub styles: Styles,
+    #[serde(default)]
+    pub table: TableConfig,
+    #[serde(default)]
+    pub split: SplitConfig,
+    #[serde(default)]
+    pub startup: StartupConfig,
+    #[serde(default)]
+    pub trackers: TrackersConfig,
+    #[serde(default)]
+    pub peers: PeersConfig,
+    #[serde(default)]
+    pub eta: EtaFormat,
+    #[serde(default)]
+    pub percent: PercentFormat,
+    #[serde(default)]
+    pub locale: LocaleFormat,
+    #[serde(default)]
+    pub free_space: FreeSpaceConfig,
+    /// Name of the theme, `dark` if not set.
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub themes: HashMap<String, CustomTheme>,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub open: OpenConfig,
+    /// Cookies and headers sent when downloading `.torrent` files, by host.
+    #[serde(default)]
+    pub sites: HashMap<String, SiteConfig>,
+    /// Labels given to unlabeled torrents by where their data is, the first matching rule wins.
+    #[serde(default)]
+    pub label_rules: Vec<LabelRule>,
+    /// Colors tinting the torrents with a label, by label.
+    #[serde(default)]
+    pub label_colors: HashMap<String, Color>,
+    /// Label toggled with a single key, e.g. `keep` for torrents to spare from cleanups.
+    #[serde(default)]
+    pub favorite_label: Option<String>,
+    /// Where the daemon's directories are mounted locally, to read the data of its torrents.
+    #[serde(default)]
+    pub path_mappings: Vec<PathMapping>,
+    /// Local directory whose `.torrent` files are added to the daemon, then renamed to
+    /// `.torrent.added`.
+    #[serde(default)]
+    pub watch_dir: Option<PathBuf>,
+    /// Delete the files added from `watch_dir` instead of renaming them.
+    #[serde(default)]
+    pub watch_delete: bool,
+    /// Token `sparrow proxy` requires in an `Authorization: Bearer` header, if set.
+    #[serde(default)]
+    pub proxy_token: Option<String>,
+    /// Tracker hosts whose torrents ignore the speed limits of the session, turtle mode included.
+    #[serde(default)]
+    pub alt_speed_exempt: Vec<String>,
+    /// Daemons to connect to by name, with `--profile` or from the server switcher.
+    #[serde(default)]
+    pub servers: BTreeMap<String, ServerProfile>,
+    /// Name of the server in `servers` to connect to, the `--url` given otherwise.
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Ticks per second, each refreshing the torrents; `--tick-rate` wins over it.
+    #[serde(default)]
+    pub tick_rate: Option<f64>,
+    /// Frames drawn per second; `--frame-rate` wins over it.
+    #[serde(default)]
+    pub frame_rate: Option<f64>,
+}
+
+pub const DEFAULT_TICK_RATE: f64 = 0.5;
+pub const DEFAULT_FRAME_RATE: f64 = 24.0;
+
+/// Settings changed while running, in the config directory. Read after the config files, it
+/// wins over them.
+const RUNTIME_FILE: &str = "runtime.json";
+
+/// Keep the tick and frame rates picked while running for the next runs.
+pub fn save_rates(tick_rate: f64, frame_rate: f64) -> std::io::Result<()> {
+    let config_dir = get_config_dir();
+    std::fs::create_dir_all(&config_dir)?;
+    let rates = serde_json::json!({ "tick_rate": tick_rate, "frame_rate": frame_rate });
+    std::fs::write(config_dir.join(RUNTIME_FILE), format!("{rates:#}\n"))
+}
+
+/// Forget the rates kept by [`save_rates`], returning the tick and frame rates of the config
+/// files instead.
+pub fn reset_rates() -> Result<(f64, f64)> {
+    match std::fs::remove_file(get_config_dir().join(RUNTIME_FILE)) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let config = Config::new()?;
+    Ok((
+        config.tick_rate.unwrap_or(DEFAULT_TICK_RATE),
+        config.frame_rate.unwrap_or(DEFAULT_FRAME_RATE),
+    ))
+}
+
+/// How calls changing something on the daemon are retried when it can't be reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Tries per call, the first one included; 1 never retries.
+    pub attempts: u32,
+    /// Delay before the first retry, doubled before each next one.
+    pub delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            delay_ms: 250,
+            max_delay_ms: 4000,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// The delay after failed attempt `attempt`, counting from 1, with `jitter` between 0 and 1
+    /// taking up to half of it off so that clients don't retry in lockstep.
+    pub fn delay(self, attempt: u32, jitter: f64) -> Duration {
+        let delay = Duration::from_millis(self.delay_ms)
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(Duration::from_millis(self.max_delay_ms));
+        delay.mul_f64(1.0 - jitter.clamp(0.0, 1.0) / 2.0)
+    }
+}
+
+/// A Transmission daemon and how to log in to it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct ServerProfile {
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Suggested when adding torrents instead of the daemon's download directory.
+    #[serde(default)]
+    pub download_dir: Option<String>,
+    /// PEM certificate presented to a proxy in front of the daemon that requires mutual TLS.
+    #[serde(default)]
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key of `client_cert`, unless it's in the same file.
+    #[serde(default)]
+    pub client_key: Option<PathBuf>,
+    /// PEM certificates trusted on top of the system's, e.g. a self-signed one.
+    #[serde(default)]
+    pub ca_cert: Option<PathBuf>,
+    /// Accept any certificate, trading away the protection of TLS against impersonation.
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PeersConfig {
+    /// Sample the download rate of each peer to draw a sparkline in the Peers tab.
+    pub sparklines: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct FreeSpaceConfig {
+    /// Free space is shown as an error below this many gigabytes.
+    pub low_gb: f64,
+    /// Downloads are paused below this many gigabytes free in their directory, if set.
+    pub pause_below_gb: Option<f64>,
+}
+
+impl Default for FreeSpaceConfig {
+    fn default() -> Self {
+        Self {
+            low_gb: 10.0,
+            pause_below_gb: None,
+        }
+    }
+}
+
+/// Gigabytes freed above `pause_below_gb` before paused downloads resume, so that they don't
+/// stop and start on every tick.
+const RESUME_MARGIN_GB: f64 = 1.0;
+
+impl FreeSpaceConfig {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn is_low(self, bytes: i64) -> bool {
+        (bytes as f64) < self.low_gb * 1024_f64.powi(3)
+    }
+
+    /// Whether downloads should be paused with `bytes` free, given whether they already are.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn should_pause(self, bytes: i64, paused: bool) -> bool {
+        let Some(threshold) = self.pause_below_gb else {
+            return false;
+        };
+        let threshold = match paused {
+            true => threshold + RESUME_MARGIN_GB,
+            false => threshold,
+        };
+        (bytes as f64) < threshold * 1024_f64.powi(3)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TrackersConfig {
+    /// Show announce URLs with their passkeys in the Trackers tab, they're redacted elsewhere.
+    pub full_urls: bool,
+}
+
+/// What to send along when downloading a `.torrent` file from a site, e.g. a private tracker.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SiteConfig {
+    /// Sent as the `Cookie` header.
+    pub cookies: Option<String>,
+    pub headers: HashMap<String, String>,
+}
+
+/// Label `label` the torrents whose data path matches `path`, where `*` stands for anything.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct LabelRule {
+    pub path: String,
+    pub label: String,
+}
+
+/// The label of the first rule matching `path`, the download directory joined with the name.
+pub fn label_for<'a>(rules: &'a [LabelRule], path: &str) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|rule| matches_pattern(&rule.path, path))
+        .map(|rule| rule.label.as_str())
+}
+
+/// The color of the first of `labels` having one, ignoring case.
+pub fn label_color(colors: &HashMap<String, Color>, labels: &[String]) -> Option<Color> {
+    labels.iter().find_map(|label| {
+        colors
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(label))
+            .map(|(_, &color)| color)
+    })
+}
+
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        // No `*`, the whole text has to match.
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// The daemon's directory `remote` is reachable here as `local`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PathMapping {
+    pub remote: String,
+    pub local: String,
+}
+
+/// Where the daemon's `path` is reachable locally, through the first mapping of a directory
+/// above it, or unchanged for a daemon running here.
+pub fn local_path(mappings: &[PathMapping], path: &str) -> PathBuf {
+    mapped_path(mappings, path).unwrap_or_else(|| path.into())
+}
+
+/// Where the daemon's `path` is reachable locally through the first mapping of a directory
+/// above it, if one is.
+pub fn mapped_path(mappings: &[PathMapping], path: &str) -> Option<PathBuf> {
+    mappings.iter().find_map(|mapping| {
+        let remote = mapping.remote.trim_end_matches('/');
+        let rest = path.strip_prefix(remote)?;
+        (rest.is_empty() || rest.starts_with('/'))
+            .then(|| format!("{}{rest}", mapping.local.trim_end_matches('/')).into())
+    })
+}
+
+/// The site configured for `host` or the closest domain above it.
+pub fn site_for<'a>(sites: &'a HashMap<String, SiteConfig>, host: &str) -> Option<&'a SiteConfig> {
+    let host = host.to_ascii_lowercase();
+    let mut domain = host.as_str();
+    loop {
+        if let Some(site) = sites.get(domain) {
+            return Some(site);
+        }
+        domain = domain.split_once('.')?.1;
+    }
+}
+
+/// Whether `host` is one of `domains` or a subdomain of one.
+pub fn in_domains(domains: &[String], host: &str) -> bool {
+    let mut domain = host;
+    loop {
+        if domains.iter().any(|d| d.eq_ignore_ascii_case(domain)) {
+            return true;
+        }
+        match domain.split_once('.') {
+            Some((_, parent)) => domain = parent,
+            None => return false,
+        }
+    }
+}
+
+/// Where sparrow starts and how the torrent list is initially shown.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    pub mode: Mode,
+    pub filter: StatusFilter,
+    pub sort: Sort,
+    pub group: Group,
+}
+
+impl StartupConfig {
+    pub fn view(&self) -> View {
+        View {
+            filter: self.filter,
+            sort: self.sort,
+            group: self.group,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct TableConfig {
+    /// Lines per torrent row: 1, 2 or 4.
+    pub row_height: u16,
+    /// Alternate the background color of rows.
+    pub zebra: bool,
+    /// Columns of the table, in order.
+    pub columns: Vec<Column>,
+    /// Show exact bytes, seconds and ratios instead of formatted values.
+    pub raw: bool,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self {
+            row_height: 4,
+            zebra: true,
+            columns: DEFAULT_COLUMNS.to_vec(),
+            raw: false,
+        }
+    }
+}
+
+/// The torrent list with the properties of the selected torrent beside it.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(default)]
+pub struct SplitConfig {
+    /// Show the properties beside the list from the start.
+    pub enabled: bool,
+    /// Narrower terminals show the list alone.
+    pub min_width: u16,
+    /// Share of the width taken by the list.
+    pub list_percent: u16,
+}
+
+impl Default for SplitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_width: 140,
+            list_percent: 55,
+        }
+    }
 }
 
 lazy_static! {
@@ -74,6 +468,11 @@ impl Config {
         if !found_config {
             error!("No configuration file found. Application may not behave as expected");
         }
+        builder = builder.add_source(
+            config::File::from(config_dir.join(RUNTIME_FILE))
+                .format(config::FileFormat::Json)
+                .required(false),
+        );
 
         let mut cfg: Self = builder.build()?.try_deserialize()?;
 
@@ -96,6 +495,41 @@ impl Config {
     }
 }
 
+impl Config {
+    /// The profile of the server connected to, if it's one of `servers`.
+    pub fn server(&self) -> Option<&ServerProfile> {
+        self.servers.get(self.profile.as_ref()?)
+    }
+
+    pub fn theme_name(&self) -> &str {
+        self.theme.as_deref().unwrap_or("dark")
+    }
+
+    /// Names of the themes to pick from, the built-in ones first.
+    pub fn theme_names(&self) -> Vec<String> {
+        let mut custom = self
+            .themes
+            .keys()
+            .filter(|name| Colors::builtin(name).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        custom.sort();
+        BUILTIN_THEMES
+            .iter()
+            .map(ToString::to_string)
+            .chain(custom)
+            .collect()
+    }
+
+    /// Colors of the theme `name`, the user's themes taking precedence over the built-in ones.
+    pub fn theme_colors(&self, name: &str) -> Result<Colors, String> {
+        match self.themes.get(name) {
+            Some(theme) => theme.colors(),
+            None => Colors::builtin(name).ok_or(format!("Unknown theme `{name}`")),
+        }
+    }
+}
+
 pub fn get_data_dir() -> PathBuf {
     let directory = if let Some(s) = DATA_FOLDER.clone() {
         s
@@ -137,11 +571,12 @@ impl<'de> Deserialize<'de> for KeyBindings {
             .map(|(mode, inner_map)| {
                 let converted_inner_map = inner_map
                     .into_iter()
-                    .map(|(key_str, cmd)| (parse_key_sequence(&key_str).unwrap(), cmd))
-                    .collect();
-                (mode, converted_inner_map)
+                    .map(|(key_str, cmd)| Ok((parse_key_sequence(&key_str)?, cmd)))
+                    .collect::<Result<_, String>>()?;
+                Ok((mode, converted_inner_map))
             })
-            .collect();
+            .collect::<Result<_, String>>()
+            .map_err(serde::de::Error::custom)?;
 
         Ok(KeyBindings(keybindings))
     }
@@ -150,6 +585,11 @@ impl<'de> Deserialize<'de> for KeyBindings {
 fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
     let raw_lower = raw.to_ascii_lowercase();
     let (remaining, modifiers) = extract_modifiers(&raw_lower);
+    // Keep the case of single characters, `G` is bound apart from `g`.
+    let remaining = match remaining.len() {
+        1 => &raw[raw.len() - 1..],
+        _ => remaining,
+    };
     parse_key_code_with_modifiers(remaining, modifiers)
 }
 
@@ -511,6 +951,15 @@ mod tests {
                 .unwrap(),
             &Action::Quit
         );
+        let home = c.keybindings.get(&Mode::Home).unwrap();
+        assert_eq!(
+            home.get(&parse_key_sequence("<G>").unwrap()),
+            Some(&Action::Bottom)
+        );
+        assert_eq!(
+            home.get(&parse_key_sequence("<1>").unwrap()),
+            Some(&Action::SortBy(crate::view::SortKey::Name))
+        );
         Ok(())
     }
 
@@ -577,6 +1026,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_uppercase_keys() {
+        assert_eq!(
+            parse_key_event("G").unwrap(),
+            KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            parse_key_sequence("<Ctrl-d>").unwrap(),
+            [KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)]
+        );
+        assert_ne!(parse_key_event("g"), parse_key_event("G"));
+    }
+
     #[test]
     fn test_invalid_keys() {
         assert!(parse_key_event("invalid-key").is_err());
@@ -595,4 +1057,113 @@ mod tests {
             KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT)
         );
     }
+
+    #[test]
+    fn test_site_for() {
+        let source = r#"{ "sites": { "tracker.example": { "cookies": "uid=1; pass=2" } } }"#;
+        let config: Config = config::Config::builder()
+            .add_source(config::File::from_str(source, config::FileFormat::Json5))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+        let site = site_for(&config.sites, "dl.Tracker.example").unwrap();
+        assert_eq!(site.cookies.as_deref(), Some("uid=1; pass=2"));
+        assert!(site_for(&config.sites, "example").is_none());
+        assert!(site_for(&config.sites, "other.example").is_none());
+    }
+
+    #[test]
+    fn test_in_domains() {
+        let domains = vec!["tracker.example".to_string()];
+        assert!(in_domains(&domains, "tracker.example"));
+        assert!(in_domains(&domains, "announce.Tracker.Example"));
+        assert!(!in_domains(&domains, "example"));
+        assert!(!in_domains(&domains, "othertracker.example"));
+        assert!(!in_domains(&[], "tracker.example"));
+    }
+
+    #[test]
+    fn test_local_path() {
+        let mappings = [PathMapping {
+            remote: "/downloads/".to_string(),
+            local: "/mnt/seedbox".to_string(),
+        }];
+        assert_eq!(
+            local_path(&mappings, "/downloads/tv/Show.mkv"),
+            PathBuf::from("/mnt/seedbox/tv/Show.mkv")
+        );
+        assert_eq!(
+            local_path(&mappings, "/downloads-old/a.iso"),
+            PathBuf::from("/downloads-old/a.iso")
+        );
+        assert_eq!(local_path(&[], "/data/a.iso"), PathBuf::from("/data/a.iso"));
+        assert_eq!(mapped_path(&mappings, "/downloads-old/a.iso"), None);
+    }
+
+    #[test]
+    fn test_label_for() {
+        let rule = |path: &str, label: &str| LabelRule {
+            path: path.to_string(),
+            label: label.to_string(),
+        };
+        let rules = [
+            rule("/downloads/tv/*", "tv"),
+            rule("*/movies/*.mkv", "movies"),
+            rule("/downloads/iso", "iso"),
+        ];
+        assert_eq!(label_for(&rules, "/downloads/tv/Show S01"), Some("tv"));
+        assert_eq!(label_for(&rules, "/data/movies/Film.mkv"), Some("movies"));
+        assert_eq!(label_for(&rules, "/data/movies/Film"), None);
+        assert_eq!(label_for(&rules, "/downloads/iso"), Some("iso"));
+        assert_eq!(label_for(&rules, "/downloads/iso/debian"), None);
+        assert_eq!(label_for(&rules, "/downloads/tv"), None);
+        assert!(matches_pattern("a*a", "aba"));
+        assert!(!matches_pattern("a*a", "a"));
+    }
+
+    #[test]
+    fn test_label_color() {
+        let colors = HashMap::from([
+            ("TV".to_string(), Color::Cyan),
+            ("movies".to_string(), Color::Magenta),
+        ]);
+        let labels = |labels: &[&str]| labels.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            label_color(&colors, &labels(&["other", "tv"])),
+            Some(Color::Cyan)
+        );
+        assert_eq!(
+            label_color(&colors, &labels(&["movies", "tv"])),
+            Some(Color::Magenta)
+        );
+        assert_eq!(label_color(&colors, &labels(&["other"])), None);
+        assert_eq!(label_color(&colors, &[]), None);
+    }
+
+    #[test]
+    fn test_should_pause() {
+        let gb = 1024 * 1024 * 1024;
+        assert!(!FreeSpaceConfig::default().should_pause(0, false));
+        let config = FreeSpaceConfig {
+            pause_below_gb: Some(5.0),
+            ..FreeSpaceConfig::default()
+        };
+        assert!(config.should_pause(4 * gb, false));
+        assert!(!config.should_pause(5 * gb, false));
+        // Paused downloads wait for the margin to be freed as well.
+        assert!(config.should_pause(5 * gb, true));
+        assert!(!config.should_pause(6 * gb, true));
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let retry = RetryConfig::default();
+        let millis = |attempt, jitter| retry.delay(attempt, jitter).as_millis();
+        assert_eq!(millis(1, 0.0), 250);
+        assert_eq!(millis(2, 0.0), 500);
+        assert_eq!(millis(3, 1.0), 500);
+        assert_eq!(millis(10, 0.0), 4000);
+        assert_eq!(millis(u32::MAX, 0.5), 3000);
+    }
 }
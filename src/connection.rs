@@ -0,0 +1,73 @@
+//! Whether the daemon answers, and when to check again once it doesn't.
+
+use std::time::{Duration, Instant};
+
+/// Delay before the first check after losing the connection, doubled after every failure.
+const FIRST_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default)]
+pub struct Connection {
+    /// Failed checks in a row, none while connected.
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Connection {
+    pub fn is_connected(&self) -> bool {
+        self.failures == 0
+    }
+
+    /// Whether to check at `now`, on every tick while connected.
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.retry_at.is_none_or(|retry_at| now >= retry_at)
+    }
+
+    /// Record a failed check at `now`, returning how long until the next one.
+    pub fn failed(&mut self, now: Instant) -> Duration {
+        self.failures += 1;
+        let delay = backoff(self.failures);
+        self.retry_at = Some(now + delay);
+        delay
+    }
+
+    /// Record a successful check, returning whether the connection was lost until now.
+    pub fn succeeded(&mut self) -> bool {
+        let reconnected = !self.is_connected();
+        *self = Self::default();
+        reconnected
+    }
+}
+
+fn backoff(failures: u32) -> Duration {
+    FIRST_RETRY
+        .saturating_mul(2_u32.saturating_pow(failures.saturating_sub(1)))
+        .min(MAX_RETRY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let secs = (1..=8).map(|n| backoff(n).as_secs()).collect::<Vec<_>>();
+        assert_eq!(secs, [1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(backoff(u32::MAX), MAX_RETRY);
+    }
+
+    #[test]
+    fn test_connection() {
+        let now = Instant::now();
+        let mut connection = Connection::default();
+        assert!(connection.is_connected() && connection.is_due(now));
+        assert_eq!(connection.failed(now), Duration::from_secs(1));
+        assert_eq!(connection.failed(now), Duration::from_secs(2));
+        assert!(!connection.is_connected());
+        assert!(!connection.is_due(now + Duration::from_secs(1)));
+        assert!(connection.is_due(now + Duration::from_secs(2)));
+        assert!(connection.succeeded());
+        assert!(!connection.succeeded());
+        assert!(connection.is_due(now));
+    }
+}
@@ -0,0 +1,270 @@
+//! Finding the password of a server without passing it on the command line, where it would end
+//! up in the shell history and in `ps`.
+
+use std::{
+    env, fs,
+    io::{self, IsTerminal, Write},
+};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal,
+};
+use reqwest::Url;
+
+use crate::config::{get_config_dir, ServerProfile, PROJECT_NAME};
+
+/// Fill in the password of `server` if it has none, from the first of: the environment, the
+/// credentials file of the config directory, the OS keyring and, if a username is set, a prompt
+/// on the terminal. The environment and the prompt are only for the server connected to at
+/// `startup`, the variable would give its password to every server switched to.
+pub fn fill(server: &mut ServerProfile, startup: bool) -> Result<(), String> {
+    if server.password.is_some() {
+        return Ok(());
+    }
+    let environment = env::var(format!("{}_PASSWORD", PROJECT_NAME.clone())).ok();
+    let credentials = fs::read_to_string(get_config_dir().join("credentials")).ok();
+    fill_from(server, startup, environment, credentials.as_deref())
+}
+
+/// [`fill`] with the password of the environment and the content of the credentials file
+/// already read.
+fn fill_from(
+    server: &mut ServerProfile,
+    startup: bool,
+    environment: Option<String>,
+    credentials: Option<&str>,
+) -> Result<(), String> {
+    if server.password.is_some() {
+        return Ok(());
+    }
+    if let Some(password) = environment.filter(|_| startup) {
+        server.password = Some(password);
+        return Ok(());
+    }
+    let host = Url::parse(&server.url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+    if let Some(content) = credentials {
+        if let Some((login, password)) = lookup(content, &host, server.username.as_deref()) {
+            server.username = Some(login.to_string());
+            server.password = Some(password.to_string());
+            return Ok(());
+        }
+    }
+    let Some(username) = server.username.clone() else {
+        return Ok(());
+    };
+    #[cfg(feature = "keyring")]
+    if let Ok(password) = keyring::find(&username, &host) {
+        server.password = Some(password);
+        return Ok(());
+    }
+    if startup && io::stdin().is_terminal() {
+        let password = prompt(&format!("Password for {username}@{host}"))?;
+        server.password = Some(password);
+    }
+    Ok(())
+}
+
+/// An entry of a netrc-style file, for the `machine` or for any host if `None`.
+#[derive(Debug, Default)]
+struct Entry<'a> {
+    machine: Option<&'a str>,
+    login: Option<&'a str>,
+    password: Option<&'a str>,
+}
+
+/// The login and password for `host` in `content`, lines of
+/// `machine <host> login <user> password <password>` where `default` stands for any host. An
+/// entry of the host wins over the default one; with a `user`, only entries of that login match.
+fn lookup<'a>(content: &'a str, host: &str, user: Option<&str>) -> Option<(&'a str, &'a str)> {
+    let mut tokens = content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(str::split_whitespace);
+    let mut entries = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" => entries.push(Entry {
+                machine: Some(tokens.next().unwrap_or_default()),
+                ..Entry::default()
+            }),
+            "default" => entries.push(Entry::default()),
+            "login" | "password" => {
+                let (Some(entry), Some(value)) = (entries.last_mut(), tokens.next()) else {
+                    continue;
+                };
+                match token {
+                    "login" => entry.login = Some(value),
+                    _ => entry.password = Some(value),
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+        .into_iter()
+        .filter(|entry| entry.machine.is_none_or(|machine| machine == host))
+        .filter(|entry| user.is_none_or(|user| entry.login == Some(user)))
+        .min_by_key(|entry| entry.machine.is_none())
+        .and_then(|entry| Some((entry.login?, entry.password?)))
+}
+
+/// Ask for a password on the terminal without echoing it.
+fn prompt(label: &str) -> Result<String, String> {
+    eprint!("{label}: ");
+    let _ = io::stderr().flush();
+    terminal::enable_raw_mode().map_err(|err| err.to_string())?;
+    let password = read_hidden();
+    let _ = terminal::disable_raw_mode();
+    eprintln!();
+    match password {
+        Ok(Some(password)) => Ok(password),
+        Ok(None) => Err("No password given".to_string()),
+        Err(err) => Err(format!("Can't read the password: {err}")),
+    }
+}
+
+/// The line typed until `Enter`, `None` if cancelled with `Esc` or `Ctrl-c`.
+fn read_hidden() -> io::Result<Option<String>> {
+    let mut password = String::new();
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => return Ok(Some(password)),
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+            KeyCode::Backspace => {
+                password.pop();
+            }
+            KeyCode::Char(c) => password.push(c),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "keyring")]
+mod keyring {
+    //! Reading passwords from the OS keyring through the tools of the platform, stored under the
+    //! service `sparrow` and the account `<username>@<host>`.
+
+    use std::process::Command;
+
+    #[cfg(target_os = "macos")]
+    fn command(account: &str) -> Command {
+        let mut command = Command::new("security");
+        command.args([
+            "find-generic-password",
+            "-s",
+            "sparrow",
+            "-a",
+            account,
+            "-w",
+        ]);
+        command
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn command(account: &str) -> Command {
+        let mut command = Command::new("secret-tool");
+        command.args(["lookup", "service", "sparrow", "account", account]);
+        command
+    }
+
+    /// The password stored for `username` on `host`.
+    pub fn find(username: &str, host: &str) -> Result<String, String> {
+        let output = command(&format!("{username}@{host}"))
+            .output()
+            .map_err(|err| err.to_string())?;
+        let password = String::from_utf8_lossy(&output.stdout)
+            .trim_end_matches('\n')
+            .to_string();
+        match output.status.success() && !password.is_empty() {
+            true => Ok(password),
+            false => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environment_is_for_the_startup_server() {
+        let environment = || Some("from-env".to_string());
+        let server = || ServerProfile {
+            url: "http://switched-to.invalid:9091/transmission/rpc".to_string(),
+            ..ServerProfile::default()
+        };
+        let mut switched = server();
+        fill_from(&mut switched, false, environment(), None).unwrap();
+        assert_eq!(switched.password, None);
+        let mut initial = server();
+        fill_from(&mut initial, true, environment(), None).unwrap();
+        assert_eq!(initial.password.as_deref(), Some("from-env"));
+    }
+
+    #[test]
+    fn test_credentials_file_after_the_environment() {
+        let credentials = "machine switched-to.invalid login me password from-file";
+        let server = || ServerProfile {
+            url: "http://switched-to.invalid:9091/transmission/rpc".to_string(),
+            ..ServerProfile::default()
+        };
+        let mut switched = server();
+        fill_from(
+            &mut switched,
+            false,
+            Some("from-env".to_string()),
+            Some(credentials),
+        )
+        .unwrap();
+        assert_eq!(switched.username.as_deref(), Some("me"));
+        assert_eq!(switched.password.as_deref(), Some("from-file"));
+        let mut initial = server();
+        fill_from(
+            &mut initial,
+            true,
+            Some("from-env".to_string()),
+            Some(credentials),
+        )
+        .unwrap();
+        assert_eq!(initial.password.as_deref(), Some("from-env"));
+    }
+
+    #[test]
+    fn test_lookup() {
+        let content = "\
+# Seedbox and NAS
+machine seedbox.example login me password secret
+machine nas
+    login admin
+    password hunter2
+machine nas login guest password guest
+default login anyone password fallback
+";
+        assert_eq!(
+            lookup(content, "seedbox.example", None),
+            Some(("me", "secret"))
+        );
+        assert_eq!(lookup(content, "nas", None), Some(("admin", "hunter2")));
+        assert_eq!(
+            lookup(content, "nas", Some("guest")),
+            Some(("guest", "guest"))
+        );
+        assert_eq!(
+            lookup(content, "localhost", None),
+            Some(("anyone", "fallback"))
+        );
+        assert_eq!(lookup(content, "localhost", Some("me")), None);
+        assert_eq!(lookup("machine nas login admin", "nas", None), None);
+    }
+}
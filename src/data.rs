@@ -1,15 +1,31 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    rc::Rc,
+    time::Duration,
+};
 
+use base64::prelude::{Engine, BASE64_STANDARD};
 use chrono::{DateTime, Utc};
 use color_eyre::Result;
 use itertools::Itertools;
-use transmission_rpc::{types::Id, TransClient};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use strum::{Display, EnumIter, FromRepr, IntoEnumIterator};
+use transmission_rpc::types::{
+    self, Id, SessionSetArgs, TorrentAddArgs, TorrentAddedOrDuplicate, TorrentGetField,
+    TorrentSetArgs, TorrentStatus,
+};
 
 use crate::{
     app,
+    config::{label_for, local_path, site_for, LabelRule, PathMapping, SiteConfig},
+    rpc::Client,
     utils::{
-        convert_bytes, convert_eta, convert_percentage, convert_priority, convert_status,
-        handle_ratio,
+        convert_bytes, convert_eta, convert_priority, convert_progress, convert_status,
+        handle_ratio, redact_url, redact_urls, seeding_summary,
     },
 };
 
@@ -17,33 +33,51 @@ use crate::{
 pub struct Torrent {
     pub id: i64,
     pub is_stalled: bool,
-    pub status: String,
+    pub status: TorrentStatus,
     pub name: String,
-    pub formatted_name: String,
-    pub percent_done: String,
-    pub total_size: String,
-    pub size_done: String,
-    pub uploaded: String,
-    pub upload_speed: String,
-    pub downloaded: String,
-    pub download_speed: String,
-    pub ratio: String,
     pub location: String,
     pub hash: String,
     pub added_date: DateTime<Utc>,
     pub done_date: DateTime<Utc>,
-    pub eta: String,
     pub error: String,
 
+    pub percent: f32,
+    pub eta_secs: i64,
+    pub rate_download: i64,
+    pub rate_upload: i64,
+    pub upload_ratio: f32,
+    pub total_bytes: i64,
+    /// Bytes of the wanted files still to be downloaded.
+    pub left_bytes: i64,
+    pub labels: Vec<String>,
+    pub queue_position: i64,
+    pub have_bytes: i64,
+    pub uploaded_bytes: i64,
+    /// Connected peers we download from.
+    pub peers_sending: i64,
+    /// Connected peers we upload to.
+    pub peers_getting: i64,
+    /// Every connected peer, whether or not data flows.
+    pub peers_connected: i64,
+
     pub trackers: Vec<Tracker>,
     pub files: Vec<Files>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Tracker {
+    /// Identifies the tracker within its torrent, to edit or remove it.
+    pub id: i64,
     pub host: String,
+    /// Full announce URL, which may hold a passkey.
+    pub announce: String,
     pub is_backup: bool,
     pub next_announce: DateTime<Utc>,
+    pub next_scrape: DateTime<Utc>,
+    pub last_scrape_result: String,
+    pub seeders: i64,
+    pub leechers: i64,
+    pub downloads: i64,
 }
 
 #[derive(Debug, Clone)]
@@ -53,52 +87,228 @@ pub struct Files {
     pub total_size: String,
     pub priority: String,
     pub wanted: bool,
+    pub bytes_completed: i64,
+    pub length: i64,
 }
 
 impl Torrent {
-    pub const fn ref_array(&self) -> [&String; 6] {
-        [
-            &self.formatted_name,
-            &self.percent_done,
-            &self.eta,
-            &self.download_speed,
-            &self.upload_speed,
-            &self.ratio,
-        ]
+    /// The name, cut to 80 characters, over a line with the status, the progress and the labels.
+    pub fn formatted_name(&self) -> String {
+        let name = match self.name.char_indices().nth(80) {
+            Some((end, _)) => format!("{}...", &self.name[..end]),
+            None => self.name.clone(),
+        };
+        let mut formatted = format!(
+            "{name}\nStatus: {}    Have: {} of {}",
+            convert_status(self.status),
+            self.downloaded(),
+            self.size_done()
+        );
+        if !self.labels.is_empty() {
+            formatted.push_str(&format!("    Labels: {}", self.labels.join(", ")));
+        }
+        formatted
+    }
+
+    pub fn percent_done(&self) -> String {
+        let seeding = matches!(
+            self.status,
+            TorrentStatus::Seeding | TorrentStatus::QueuedToSeed
+        );
+        convert_progress(self.percent, seeding)
+    }
+
+    pub fn eta(&self) -> String {
+        convert_eta(self.eta_secs)
+    }
+
+    pub fn ratio(&self) -> String {
+        handle_ratio(self.upload_ratio)
+    }
+
+    pub fn total_size(&self) -> String {
+        convert_bytes(self.total_bytes)
     }
 
-    pub fn formatted_name(&self) -> &str {
-        &self.formatted_name
+    /// Size of the wanted files.
+    pub fn size_done(&self) -> String {
+        convert_bytes(self.have_bytes + self.left_bytes)
     }
-    pub fn percent_done(&self) -> &str {
-        &self.percent_done
+
+    pub fn downloaded(&self) -> String {
+        convert_bytes(self.have_bytes)
     }
-    pub fn eta(&self) -> &str {
-        &self.eta
+
+    pub fn uploaded(&self) -> String {
+        convert_bytes(self.uploaded_bytes)
     }
-    pub fn upload_speed(&self) -> &str {
-        &self.upload_speed
+
+    pub fn download_speed(&self) -> String {
+        format!("{}/s", convert_bytes(self.rate_download))
     }
-    pub fn download_speed(&self) -> &str {
-        &self.download_speed
+
+    pub fn upload_speed(&self) -> String {
+        format!("{}/s", convert_bytes(self.rate_upload))
     }
-    pub fn ratio(&self) -> &str {
-        &self.ratio
+
+    /// Roughly how many bytes the torrent takes in memory.
+    pub fn heap_bytes(&self) -> usize {
+        let strings = [&self.name, &self.location, &self.hash, &self.error]
+            .into_iter()
+            .chain(&self.labels)
+            .map(String::capacity)
+            .sum::<usize>();
+        let trackers = self
+            .trackers
+            .iter()
+            .map(|t| {
+                size_of::<Tracker>()
+                    + t.host.capacity()
+                    + t.announce.capacity()
+                    + t.last_scrape_result.capacity()
+            })
+            .sum::<usize>();
+        let files = self
+            .files
+            .iter()
+            .map(|f| size_of::<Files>() + f.name.capacity() + f.priority.capacity())
+            .sum::<usize>();
+        size_of::<Self>() + strings + trackers + files
     }
 }
 
+/// Every torrent with its files, or only torrent `id`, see [`map_torrent_list`] for a lighter
+/// call.
 pub async fn map_torrent_data(
-    client: &Rc<RefCell<TransClient>>,
+    client: &Rc<RefCell<Client>>,
     id: Option<i64>,
 ) -> Result<Vec<Torrent>, app::Error> {
-    let res = {
-        let mut client = client.borrow_mut();
-        async move {
-            match id {
-                Some(id) => client.torrent_get(None, Some(vec![Id::Id(id)])).await,
-                None => client.torrent_get(None, None).await,
+    fetch_torrents(client, None, id).await
+}
+
+/// Fields of a torrent besides its files, which can be thousands per torrent.
+const LIST_FIELDS: [TorrentGetField; 23] = [
+    TorrentGetField::Id,
+    TorrentGetField::Name,
+    TorrentGetField::Status,
+    TorrentGetField::IsStalled,
+    TorrentGetField::Eta,
+    TorrentGetField::PercentDone,
+    TorrentGetField::SizeWhenDone,
+    TorrentGetField::LeftUntilDone,
+    TorrentGetField::TotalSize,
+    TorrentGetField::UploadedEver,
+    TorrentGetField::UploadRatio,
+    TorrentGetField::RateDownload,
+    TorrentGetField::RateUpload,
+    TorrentGetField::DownloadDir,
+    TorrentGetField::HashString,
+    TorrentGetField::AddedDate,
+    TorrentGetField::DoneDate,
+    TorrentGetField::ErrorString,
+    TorrentGetField::Labels,
+    TorrentGetField::TrackerStats,
+    TorrentGetField::PeersSendingToUs,
+    TorrentGetField::PeersGettingFromUs,
+    TorrentGetField::PeersConnected,
+];
+
+/// Every torrent, leaving out their files, for views refreshing the whole list every tick.
+pub async fn map_torrent_list(client: &Rc<RefCell<Client>>) -> Result<Vec<Torrent>, app::Error> {
+    fetch_torrents(client, Some(LIST_FIELDS.to_vec()), None).await
+}
+
+/// The torrent list kept between ticks, so that only the torrents active since the previous
+/// tick are fetched again.
+#[derive(Default)]
+pub struct TorrentCache {
+    torrents: HashMap<i64, Torrent>,
+}
+
+#[derive(Deserialize)]
+struct RecentTorrents {
+    torrents: Vec<transmission_rpc::types::Torrent>,
+    /// Ids of the torrents removed since the previous call.
+    #[serde(default)]
+    removed: Vec<i64>,
+}
+
+impl TorrentCache {
+    /// Fetch every torrent, e.g. when the view is opened or asked to refresh.
+    pub async fn load(&mut self, client: &Rc<RefCell<Client>>) -> Result<Vec<Torrent>, app::Error> {
+        let torrents = map_torrent_list(client).await?;
+        self.torrents = torrents.iter().map(|t| (t.id, t.clone())).collect();
+        Ok(torrents)
+    }
+
+    /// Fetch the torrents active since the previous call and forget the removed ones.
+    pub async fn update(
+        &mut self,
+        client: &Rc<RefCell<Client>>,
+    ) -> Result<Vec<Torrent>, app::Error> {
+        let fields = LIST_FIELDS
+            .iter()
+            .map(TorrentGetField::to_str)
+            .collect_vec();
+        let res = {
+            let mut client = client.borrow_mut();
+            async move {
+                client
+                    .call::<RecentTorrents>(
+                        "torrent-get",
+                        json!({ "ids": "recently-active", "fields": fields }),
+                    )
+                    .await
             }
         }
+        .await?;
+        // Moving a torrent in the queue shifts the others without making them active.
+        let queue = fetch_queue_positions(client, None).await?;
+        self.merge(map_torrents(res.torrents, &queue), &res.removed);
+        for torrent in self.torrents.values_mut() {
+            torrent.queue_position = queue.get(&torrent.id).copied().unwrap_or_default();
+        }
+        Ok(self.torrents())
+    }
+
+    /// The number of torrents cached and roughly how many bytes they take.
+    pub fn footprint(&self) -> (usize, usize) {
+        let bytes = self.torrents.values().map(Torrent::heap_bytes).sum();
+        (self.torrents.len(), bytes)
+    }
+
+    /// Add torrents fetched elsewhere, e.g. one just added.
+    pub fn insert(&mut self, torrents: Vec<Torrent>) {
+        self.merge(torrents, &[]);
+    }
+
+    fn merge(&mut self, changed: Vec<Torrent>, removed: &[i64]) {
+        for id in removed {
+            self.torrents.remove(id);
+        }
+        self.torrents
+            .extend(changed.into_iter().map(|torrent| (torrent.id, torrent)));
+    }
+
+    /// The cached torrents, sorted by name like a fresh list.
+    fn torrents(&self) -> Vec<Torrent> {
+        self.torrents
+            .values()
+            .cloned()
+            .sorted_unstable_by(|a, b| a.name.cmp(&b.name))
+            .collect()
+    }
+}
+
+async fn fetch_torrents(
+    client: &Rc<RefCell<Client>>,
+    fields: Option<Vec<TorrentGetField>>,
+    id: Option<i64>,
+) -> Result<Vec<Torrent>, app::Error> {
+    let res = {
+        let mut client = client.borrow_mut();
+        let ids = id.map(|id| vec![Id::Id(id)]);
+        async move { client.torrent_get(fields, ids).await }
     }
     .await;
 
@@ -106,71 +316,1882 @@ pub async fn map_torrent_data(
         Ok(t) => t.arguments.torrents,
         Err(err) => return Err(app::Error::WithMessage(err.to_string())),
     };
+    let queue = fetch_queue_positions(client, id).await?;
+    Ok(map_torrents(torrents, &queue))
+}
+
+/// Map the torrents as received, sorted by name, given their queue positions by id.
+fn map_torrents(
+    torrents: Vec<transmission_rpc::types::Torrent>,
+    queue: &HashMap<i64, i64>,
+) -> Vec<Torrent> {
+    torrents
+        .into_iter()
+        .filter_map(|t| map_torrent(t, queue))
+        .sorted_unstable_by(|a, b| a.name.cmp(&b.name))
+        .collect()
+}
 
-    Ok(torrents
+fn map_torrent(t: transmission_rpc::types::Torrent, queue: &HashMap<i64, i64>) -> Option<Torrent> {
+    let trackers = t
+        .tracker_stats?
+        .into_iter()
+        .map(|tr| Tracker {
+            id: match tr.id {
+                Id::Id(id) => id,
+                Id::Hash(_) => -1,
+            },
+            host: tr.host,
+            announce: tr.announce,
+            is_backup: tr.is_backup,
+            next_announce: tr.next_announce_time,
+            next_scrape: tr.next_scrape_time,
+            last_scrape_result: tr.last_scrape_result,
+            seeders: tr.seeder_count,
+            leechers: tr.leecher_count,
+            downloads: tr.download_count,
+        })
+        .collect_vec();
+    // Lists leave the files out, see `LIST_FIELDS`.
+    let files = match (t.files, t.file_stats) {
+        (Some(files), Some(stats)) => files
+            .into_iter()
+            .zip(stats)
+            .map(|(f, stats)| Files {
+                downloaded: convert_bytes(f.bytes_completed),
+                total_size: convert_bytes(f.length),
+                priority: convert_priority(&stats.priority),
+                wanted: stats.wanted,
+                bytes_completed: f.bytes_completed,
+                length: f.length,
+                name: f.name,
+            })
+            .collect_vec(),
+        _ => Vec::new(),
+    };
+
+    let have_bytes = t.size_when_done? - t.left_until_done?;
+    let id = t.id?;
+
+    Some(Torrent {
+        id,
+        is_stalled: t.is_stalled?,
+        status: t.status?,
+        name: t.name?,
+        location: t.download_dir?,
+        hash: t.hash_string?,
+        added_date: DateTime::from_timestamp(t.added_date?, 0)?,
+        done_date: DateTime::from_timestamp(t.done_date?, 0)?,
+        error: redact_urls(&t.error_string?),
+        percent: t.percent_done?,
+        eta_secs: t.eta?,
+        rate_download: t.rate_download?,
+        rate_upload: t.rate_upload?,
+        upload_ratio: t.upload_ratio?,
+        total_bytes: t.total_size?,
+        left_bytes: t.left_until_done?,
+        labels: t.labels.unwrap_or_default(),
+        queue_position: queue.get(&id).copied().unwrap_or_default(),
+        have_bytes,
+        uploaded_bytes: t.uploaded_ever?,
+        peers_sending: t.peers_sending_to_us?,
+        peers_getting: t.peers_getting_from_us?,
+        peers_connected: t.peers_connected?,
+        trackers,
+        files,
+    })
+}
+
+/// What several torrents add up to, for acting on them at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Aggregate {
+    pub total_bytes: i64,
+    pub left_bytes: i64,
+    pub rate_download: i64,
+    pub rate_upload: i64,
+    /// Tracker hosts every torrent announces to.
+    pub trackers: Vec<String>,
+    /// Labels every torrent has.
+    pub labels: Vec<String>,
+}
+
+impl Aggregate {
+    pub fn new(torrents: &[Torrent]) -> Self {
+        let hosts = torrents
+            .iter()
+            .map(|t| t.trackers.iter().map(|tr| tr.host.clone()).collect_vec())
+            .collect_vec();
+        let labels = torrents.iter().map(|t| t.labels.clone()).collect_vec();
+        Self {
+            total_bytes: torrents.iter().map(|t| t.total_bytes).sum(),
+            left_bytes: torrents.iter().map(|t| t.left_bytes).sum(),
+            rate_download: torrents.iter().map(|t| t.rate_download).sum(),
+            rate_upload: torrents.iter().map(|t| t.rate_upload).sum(),
+            trackers: shared(&hosts),
+            labels: shared(&labels),
+        }
+    }
+}
+
+/// What the torrents announcing to a tracker add up to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Traffic {
+    pub torrents: usize,
+    /// Bytes of the wanted files that are downloaded.
+    pub downloaded: i64,
+    pub uploaded: i64,
+    pub rate_download: i64,
+    pub rate_upload: i64,
+}
+
+impl Traffic {
+    pub fn ratio(&self) -> f64 {
+        match self.downloaded {
+            0 => 0.0,
+            #[allow(clippy::cast_precision_loss)]
+            downloaded => self.uploaded as f64 / downloaded as f64,
+        }
+    }
+
+    fn add(&mut self, other: Self) {
+        self.torrents += other.torrents;
+        self.downloaded += other.downloaded;
+        self.uploaded += other.uploaded;
+        self.rate_download += other.rate_download;
+        self.rate_upload += other.rate_upload;
+    }
+}
+
+impl From<&Torrent> for Traffic {
+    fn from(torrent: &Torrent) -> Self {
+        Self {
+            torrents: 1,
+            downloaded: torrent.have_bytes,
+            uploaded: torrent.uploaded_bytes,
+            rate_download: torrent.rate_download,
+            rate_upload: torrent.rate_upload,
+        }
+    }
+}
+
+/// Host under which torrents without trackers, e.g. found through DHT, are counted.
+pub const NO_TRACKER: &str = "(no tracker)";
+
+/// The traffic of the torrents per tracker host, counting a torrent once under every host it
+/// announces to.
+pub fn traffic_by_tracker(torrents: &[Torrent]) -> Vec<(String, Traffic)> {
+    group_traffic(torrents.iter().map(|t| {
+        let hosts = t.trackers.iter().map(|tr| tr.host.clone()).collect();
+        (hosts, Traffic::from(t))
+    }))
+}
+
+fn group_traffic(
+    entries: impl IntoIterator<Item = (Vec<String>, Traffic)>,
+) -> Vec<(String, Traffic)> {
+    let mut totals = HashMap::<String, Traffic>::new();
+    for (hosts, traffic) in entries {
+        let hosts = match hosts.is_empty() {
+            true => vec![NO_TRACKER.to_string()],
+            false => hosts.into_iter().unique().collect(),
+        };
+        for host in hosts {
+            totals.entry(host).or_default().add(traffic);
+        }
+    }
+    totals.into_iter().sorted_by(|a, b| a.0.cmp(&b.0)).collect()
+}
+
+/// The items found in every list, sorted.
+pub fn shared(lists: &[Vec<String>]) -> Vec<String> {
+    let Some((first, rest)) = lists.split_first() else {
+        return Vec::new();
+    };
+    first
+        .iter()
+        .filter(|item| rest.iter().all(|list| list.contains(item)))
+        .cloned()
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// The labels of each torrent after toggling `label`: removed from every torrent if all of them
+/// have it, added to the ones missing it otherwise.
+pub fn toggle_label(labels: &[Vec<String>], label: &str) -> (bool, Vec<Vec<String>>) {
+    let remove = labels
         .iter()
-        .filter_map(|t| {
-            let t = t.clone();
-            let trackers = t
-                .tracker_stats?
-                .iter()
-                .map(|tr| Tracker {
-                    host: tr.host.to_string(),
-                    is_backup: tr.is_backup,
-                    next_announce: tr.next_announce_time,
-                })
-                .collect_vec();
-            let files = t
-                .files?
-                .iter()
-                .enumerate()
-                .filter_map(|(i, f)| {
-                    let file_stats = t.file_stats.clone()?;
-                    Some(Files {
-                        name: f.name.to_string(),
-                        downloaded: convert_bytes(f.bytes_completed),
-                        total_size: convert_bytes(f.length),
-                        priority: convert_priority(&file_stats.get(i)?.priority),
-                        wanted: file_stats.get(i)?.wanted,
-                    })
-                })
-                .collect_vec();
-
-            let mut raw_name = t.name.clone()?;
-            if raw_name.len() > 80 {
-                raw_name.truncate(80);
-                raw_name.push_str("...");
+        .all(|labels| labels.iter().any(|l| l == label));
+    let toggled = labels
+        .iter()
+        .map(|labels| {
+            let mut labels = labels.clone();
+            match remove {
+                true => labels.retain(|l| l != label),
+                false if !labels.iter().any(|l| l == label) => labels.push(label.to_string()),
+                false => {}
             }
-            let status = convert_status(t.status?);
-            let downloaded = convert_bytes(t.size_when_done? - t.left_until_done?);
-            let size_done = convert_bytes(t.size_when_done?);
-            let formatted_name =
-                format!("{raw_name}\nStatus: {status}    Have: {downloaded} of {size_done}");
-
-            Some(Torrent {
-                id: t.id?,
-                is_stalled: t.is_stalled?,
-                status,
-                name: t.name?,
-                formatted_name,
-                eta: convert_eta(t.eta?),
-                ratio: handle_ratio(t.upload_ratio?),
-                percent_done: convert_percentage(t.percent_done?),
-                total_size: convert_bytes(t.total_size?),
-                size_done,
-                uploaded: convert_bytes(t.uploaded_ever?),
-                upload_speed: format!("{}/s", convert_bytes(t.rate_upload?)),
-                downloaded,
-                download_speed: format!("{}/s", convert_bytes(t.rate_download?)),
-                location: t.download_dir?,
-                hash: t.hash_string?,
-                added_date: DateTime::from_timestamp(t.added_date?, 0)?,
-                done_date: DateTime::from_timestamp(t.done_date?, 0)?,
-                error: t.error_string?,
-                trackers,
-                files,
+            labels
+        })
+        .collect();
+    (!remove, toggled)
+}
+
+/// The labels of each torrent after editing the ones they share into `edited`: the shared labels
+/// missing from `edited` are removed and the new ones added, the others are kept.
+pub fn edit_labels(labels: &[Vec<String>], edited: &[String]) -> Vec<Vec<String>> {
+    let before = shared(labels);
+    labels
+        .iter()
+        .map(|labels| {
+            let mut labels = labels.clone();
+            labels.retain(|l| !before.contains(l) || edited.contains(l));
+            for label in edited {
+                if !labels.contains(label) {
+                    labels.push(label.clone());
+                }
+            }
+            labels
+        })
+        .collect()
+}
+
+/// Torrents that share a name or at least one file on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    /// Indices into the torrent slice the group was computed from.
+    pub members: Vec<usize>,
+    pub same_name: bool,
+    pub overlapping_files: bool,
+}
+
+pub fn find_duplicates(torrents: &[Torrent]) -> Vec<DuplicateGroup> {
+    group_duplicates(
+        &torrents
+            .iter()
+            .map(|t| {
+                let location = t.location.trim_end_matches('/');
+                let paths = t
+                    .files
+                    .iter()
+                    .map(|f| format!("{location}/{}", f.name))
+                    .collect();
+                (t.name.as_str(), paths)
             })
+            .collect_vec(),
+    )
+}
+
+/// An entry of a download directory that no torrent's data is, e.g. left behind by a torrent
+/// removed without its data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orphan {
+    /// Where the daemon sees it.
+    pub path: String,
+    /// Bytes on disk, of everything under it for a directory.
+    pub size: u64,
+}
+
+/// Entries of `directories` and of the torrents' locations that no torrent refers to, read
+/// through `mappings`, largest first, along with the directories that couldn't be read.
+pub fn find_orphans(
+    torrents: &[Torrent],
+    directories: &[String],
+    mappings: &[PathMapping],
+) -> (Vec<Orphan>, Vec<String>) {
+    let referenced = referenced_names(torrents.iter().map(|t| {
+        let names = t
+            .files
+            .iter()
+            .filter_map(|f| f.name.split('/').next())
+            .chain([t.name.as_str()])
+            .collect_vec();
+        (t.location.as_str(), names)
+    }));
+    let directories = directories
+        .iter()
+        .map(String::as_str)
+        .chain(torrents.iter().map(|t| t.location.as_str()))
+        .map(|d| d.trim_end_matches('/'))
+        .sorted()
+        .dedup();
+    let none = HashSet::new();
+    let (mut orphans, mut unreadable) = (Vec::new(), Vec::new());
+    for directory in directories {
+        let Ok(entries) = fs::read_dir(local_path(mappings, directory)) else {
+            unreadable.push(directory.to_string());
+            continue;
+        };
+        let names = referenced.get(directory).unwrap_or(&none);
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !is_referenced(names, &name) {
+                orphans.push(Orphan {
+                    path: data_path(directory, &name),
+                    size: disk_usage(&entry.path()),
+                });
+            }
+        }
+    }
+    orphans.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    (orphans, unreadable)
+}
+
+/// The names in each directory that hold torrent data, from the locations of torrents and the
+/// names at their top level. Directories leading to a location count as well.
+fn referenced_names<'a>(
+    torrents: impl IntoIterator<Item = (&'a str, Vec<&'a str>)>,
+) -> HashMap<String, HashSet<String>> {
+    let mut referenced: HashMap<String, HashSet<String>> = HashMap::new();
+    for (location, names) in torrents {
+        let location = location.trim_end_matches('/');
+        let entry = referenced.entry(location.to_string()).or_default();
+        entry.extend(names.into_iter().map(str::to_string));
+        let mut path = location;
+        while let Some((parent, child)) = path.rsplit_once('/') {
+            if parent.is_empty() {
+                break;
+            }
+            referenced
+                .entry(parent.to_string())
+                .or_default()
+                .insert(child.to_string());
+            path = parent;
+        }
+    }
+    referenced
+}
+
+/// Whether `name` is among `names`, counting the `.part` files of incomplete downloads.
+fn is_referenced(names: &HashSet<String>, name: &str) -> bool {
+    names.contains(name)
+        || name
+            .strip_suffix(".part")
+            .is_some_and(|name| names.contains(name))
+}
+
+/// Bytes taken by the file at `path`, or by everything under the directory, without following
+/// links.
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| disk_usage(&entry.path()))
+        .sum()
+}
+
+fn group_duplicates(entries: &[(&str, Vec<String>)]) -> Vec<DuplicateGroup> {
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut parent = (0..entries.len()).collect_vec();
+    let mut same_name = vec![false; entries.len()];
+    let mut overlapping = vec![false; entries.len()];
+
+    let mut by_name: HashMap<&str, usize> = HashMap::new();
+    let mut by_path: HashMap<&str, usize> = HashMap::new();
+    for (i, (name, paths)) in entries.iter().enumerate() {
+        if let Some(&j) = by_name.get(name) {
+            let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+            parent[a] = b;
+            same_name[i] = true;
+            same_name[j] = true;
+        } else {
+            by_name.insert(name, i);
+        }
+        for path in paths {
+            match by_path.get(path.as_str()) {
+                Some(&j) if j != i => {
+                    let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[a] = b;
+                    overlapping[i] = true;
+                    overlapping[j] = true;
+                }
+                Some(_) => {}
+                None => {
+                    by_path.insert(path, i);
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..entries.len() {
+        groups.entry(root(&mut parent, i)).or_default().push(i);
+    }
+    groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|members| DuplicateGroup {
+            same_name: members.iter().any(|&i| same_name[i]),
+            overlapping_files: members.iter().any(|&i| overlapping[i]),
+            members,
         })
-        .sorted_by(|a, b| a.name.cmp(&b.name))
-        .collect_vec())
+        .sorted_by_key(|group| group.members[0])
+        .collect()
+}
+
+/// Files of `torrent` that would overwrite existing data if moved to `target`, either files
+/// of other torrents in that directory or paths `exists` reports, e.g. for a local daemon.
+pub fn location_collisions(
+    torrent: &Torrent,
+    torrents: &[Torrent],
+    target: &str,
+    exists: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    let target = target.trim_end_matches('/');
+    if target == torrent.location.trim_end_matches('/') {
+        return Vec::new();
+    }
+    let files = torrent.files.iter().map(|f| f.name.clone()).collect_vec();
+    let claimed = torrents
+        .iter()
+        .filter(|t| t.id != torrent.id && t.location.trim_end_matches('/') == target)
+        .flat_map(|t| t.files.iter().map(|f| f.name.clone()))
+        .collect_vec();
+    collisions(&files, &claimed, target, exists)
+}
+
+/// Torrents other than `targets` with files at the same paths as theirs, which deleting the data
+/// of `targets` would break, e.g. the same data cross-seeded under another hash.
+pub fn sharing_data<'a>(targets: &[&Torrent], torrents: &'a [Torrent]) -> Vec<&'a Torrent> {
+    let ids = targets.iter().map(|t| t.id).collect::<HashSet<_>>();
+    let removed = targets
+        .iter()
+        .flat_map(|t| file_paths(t))
+        .collect::<HashSet<_>>();
+    torrents
+        .iter()
+        .filter(|t| !ids.contains(&t.id))
+        .filter(|t| file_paths(t).any(|path| removed.contains(&path)))
+        .collect()
+}
+
+/// Full paths of the files of `torrent` on the daemon's host.
+fn file_paths(torrent: &Torrent) -> impl Iterator<Item = String> + '_ {
+    let location = torrent.location.trim_end_matches('/');
+    torrent
+        .files
+        .iter()
+        .map(move |file| format!("{location}/{}", file.name))
+}
+
+fn collisions(
+    files: &[String],
+    claimed: &[String],
+    target: &str,
+    exists: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    files
+        .iter()
+        .filter(|name| claimed.contains(name) || exists(&format!("{target}/{name}")))
+        .cloned()
+        .collect()
+}
+
+/// The slice of a torrent that is compared between ticks to detect transitions.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub id: i64,
+    pub name: String,
+    /// Transmission's status number, see `is_downloading`.
+    #[serde(default)]
+    pub status: i64,
+    pub percent_done: f32,
+    #[serde(default)]
+    pub is_stalled: bool,
+    pub seed_ratio_mode: i64,
+    pub seed_ratio_limit: f32,
+    pub seed_idle_mode: i64,
+    pub seed_idle_limit: i64,
+    /// Non-zero while the torrent has an error, see `error_string`.
+    pub error: i64,
+    pub error_string: String,
+    pub download_dir: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Whether the speed limits of the session apply to the torrent.
+    #[serde(default)]
+    pub honors_session_limits: bool,
+    #[serde(default)]
+    pub trackers: Vec<SnapshotTracker>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotTracker {
+    pub announce: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SessionSeedLimits {
+    #[serde(rename = "seedRatioLimit")]
+    pub seed_ratio_limit: f32,
+    #[serde(rename = "seedRatioLimited")]
+    pub seed_ratio_limited: bool,
+    pub idle_seeding_limit: i64,
+    pub idle_seeding_limit_enabled: bool,
+}
+
+/// Which limit stops a torrent from seeding, the values of `seedRatioMode` and `seedIdleMode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, FromRepr, Serialize, Deserialize)]
+#[repr(i64)]
+pub enum SeedMode {
+    /// The limit of the session, if it's enabled.
+    #[default]
+    Global = 0,
+    /// The limit of the torrent.
+    Single = 1,
+    /// None, seeding until stopped.
+    Unlimited = 2,
+}
+
+impl SeedMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Global => Self::Single,
+            Self::Single => Self::Unlimited,
+            Self::Unlimited => Self::Global,
+        }
+    }
+}
+
+/// The seeding limits of a torrent, a ratio and minutes without peers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedLimits {
+    pub ratio_mode: SeedMode,
+    /// The ratio times 100, as ratios are shown with two decimals.
+    pub ratio_hundredths: i64,
+    pub idle_mode: SeedMode,
+    pub idle_minutes: i64,
+}
+
+impl Snapshot {
+    /// Downloading, or queued to.
+    pub fn is_downloading(&self) -> bool {
+        matches!(self.status, 3 | 4)
+    }
+
+    /// Hosts of the announce URLs.
+    pub fn tracker_hosts(&self) -> Vec<String> {
+        self.trackers
+            .iter()
+            .filter_map(|tracker| {
+                let url = reqwest::Url::parse(&tracker.announce).ok()?;
+                url.host_str().map(str::to_string)
+            })
+            .collect()
+    }
+
+    /// Describe when the torrent will stop seeding, resolving the session defaults.
+    pub fn seeding_policy(&self, session: &SessionSeedLimits) -> String {
+        let ratio = match SeedMode::from_repr(self.seed_ratio_mode) {
+            Some(SeedMode::Global) if session.seed_ratio_limited => Some(session.seed_ratio_limit),
+            Some(SeedMode::Single) => Some(self.seed_ratio_limit),
+            _ => None,
+        };
+        let idle = match SeedMode::from_repr(self.seed_idle_mode) {
+            Some(SeedMode::Global) if session.idle_seeding_limit_enabled => {
+                Some(session.idle_seeding_limit)
+            }
+            Some(SeedMode::Single) => Some(self.seed_idle_limit),
+            _ => None,
+        };
+        seeding_summary(ratio, idle)
+    }
+}
+
+#[derive(Deserialize)]
+struct Snapshots {
+    torrents: Vec<Snapshot>,
+}
+
+pub async fn fetch_snapshots(client: &Rc<RefCell<Client>>) -> Result<Vec<Snapshot>, app::Error> {
+    let res = {
+        let mut client = client.borrow_mut();
+        async move {
+            client
+                .call::<Snapshots>(
+                    "torrent-get",
+                    json!({
+                        "fields": [
+                            "id",
+                            "name",
+                            "status",
+                            "percentDone",
+                            "isStalled",
+                            "seedRatioMode",
+                            "seedRatioLimit",
+                            "seedIdleMode",
+                            "seedIdleLimit",
+                            "error",
+                            "errorString",
+                            "downloadDir",
+                            "labels",
+                            "honorsSessionLimits",
+                            "trackers",
+                        ]
+                    }),
+                )
+                .await
+        }
+    }
+    .await;
+
+    Ok(res?.torrents)
+}
+
+pub async fn fetch_session_seed_limits(
+    client: &Rc<RefCell<Client>>,
+) -> Result<SessionSeedLimits, app::Error> {
+    let mut client = client.borrow_mut();
+    async move {
+        client
+            .call(
+                "session-get",
+                json!({
+                    "fields": [
+                        "seedRatioLimit",
+                        "seedRatioLimited",
+                        "idle-seeding-limit",
+                        "idle-seeding-limit-enabled",
+                    ]
+                }),
+            )
+            .await
+    }
+    .await
+}
+
+/// A speed limit in KB/s, applied or not.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeedLimit {
+    pub limit: i64,
+    pub enabled: bool,
+}
+
+/// The download and upload limits of the session or of a torrent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeedLimits {
+    pub down: SpeedLimit,
+    pub up: SpeedLimit,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SessionSpeedLimits {
+    speed_limit_down: i64,
+    speed_limit_down_enabled: bool,
+    speed_limit_up: i64,
+    speed_limit_up_enabled: bool,
+}
+
+/// The fields of [`SessionSpeedLimits`] in `session-get`.
+const SPEED_LIMIT_FIELDS: [&str; 4] = [
+    "speed-limit-down",
+    "speed-limit-down-enabled",
+    "speed-limit-up",
+    "speed-limit-up-enabled",
+];
+
+impl From<SessionSpeedLimits> for SpeedLimits {
+    fn from(res: SessionSpeedLimits) -> Self {
+        Self {
+            down: SpeedLimit {
+                limit: res.speed_limit_down,
+                enabled: res.speed_limit_down_enabled,
+            },
+            up: SpeedLimit {
+                limit: res.speed_limit_up,
+                enabled: res.speed_limit_up_enabled,
+            },
+        }
+    }
+}
+
+pub async fn fetch_session_speed_limits(
+    client: &Rc<RefCell<Client>>,
+) -> Result<SpeedLimits, app::Error> {
+    let mut client = client.borrow_mut();
+    let res: SessionSpeedLimits = async move {
+        client
+            .call("session-get", json!({ "fields": SPEED_LIMIT_FIELDS }))
+            .await
+    }
+    .await?;
+    Ok(res.into())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TorrentSpeedLimits {
+    download_limit: i64,
+    download_limited: bool,
+    upload_limit: i64,
+    upload_limited: bool,
+}
+
+#[derive(Deserialize)]
+struct TorrentsSpeedLimits {
+    torrents: Vec<TorrentSpeedLimits>,
+}
+
+pub async fn fetch_torrent_speed_limits(
+    client: &Rc<RefCell<Client>>,
+    id: i64,
+) -> Result<SpeedLimits, app::Error> {
+    let mut client = client.borrow_mut();
+    let res: TorrentsSpeedLimits = async move {
+        client
+            .call(
+                "torrent-get",
+                json!({
+                    "ids": [id],
+                    "fields": ["downloadLimit", "downloadLimited", "uploadLimit", "uploadLimited"]
+                }),
+            )
+            .await
+    }
+    .await?;
+    let torrent = res.torrents.first().ok_or(app::Error::OutOfBound)?;
+    Ok(SpeedLimits {
+        down: SpeedLimit {
+            limit: torrent.download_limit,
+            enabled: torrent.download_limited,
+        },
+        up: SpeedLimit {
+            limit: torrent.upload_limit,
+            enabled: torrent.upload_limited,
+        },
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TorrentSeedLimits {
+    seed_ratio_mode: i64,
+    seed_ratio_limit: f32,
+    seed_idle_mode: i64,
+    seed_idle_limit: i64,
+}
+
+#[derive(Deserialize)]
+struct TorrentsSeedLimits {
+    torrents: Vec<TorrentSeedLimits>,
+}
+
+pub async fn fetch_torrent_seed_limits(
+    client: &Rc<RefCell<Client>>,
+    id: i64,
+) -> Result<SeedLimits, app::Error> {
+    let mut client = client.borrow_mut();
+    let res: TorrentsSeedLimits = async move {
+        client
+            .call(
+                "torrent-get",
+                json!({
+                    "ids": [id],
+                    "fields": ["seedRatioMode", "seedRatioLimit", "seedIdleMode", "seedIdleLimit"]
+                }),
+            )
+            .await
+    }
+    .await?;
+    let torrent = res.torrents.first().ok_or(app::Error::OutOfBound)?;
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(SeedLimits {
+        ratio_mode: SeedMode::from_repr(torrent.seed_ratio_mode).unwrap_or_default(),
+        ratio_hundredths: (f64::from(torrent.seed_ratio_limit) * 100.0).round() as i64,
+        idle_mode: SeedMode::from_repr(torrent.seed_idle_mode).unwrap_or_default(),
+        idle_minutes: torrent.seed_idle_limit,
+    })
+}
+
+/// Apply the seeding `limits` to the torrents `ids`.
+pub async fn set_seed_limits(
+    client: &Rc<RefCell<Client>>,
+    ids: Vec<i64>,
+    limits: SeedLimits,
+) -> Result<(), app::Error> {
+    let idle = i32::try_from(limits.idle_minutes).map_err(|_| {
+        app::Error::WithMessage(format!("{} minutes is too long", limits.idle_minutes))
+    })?;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    let args = TorrentSetArgs {
+        seed_ratio_mode: Some(limits.ratio_mode as i32),
+        seed_ratio_limit: Some(limits.ratio_hundredths as f32 / 100.0),
+        seed_idle_mode: Some(limits.idle_mode as i32),
+        seed_idle_limit: Some(idle),
+        ..TorrentSetArgs::default()
+    };
+    let ids = ids.into_iter().map(Id::Id).collect();
+    let res = {
+        let mut client = client.borrow_mut();
+        async move { client.torrent_set(args, Some(ids)).await }
+    }
+    .await;
+    res.map(|_| ())
+        .map_err(|err| app::Error::WithMessage(err.to_string()))
+}
+
+/// Apply `limits` to the session, or to the torrents `ids` if given.
+pub async fn set_speed_limits(
+    client: &Rc<RefCell<Client>>,
+    ids: Option<Vec<i64>>,
+    limits: SpeedLimits,
+) -> Result<(), app::Error> {
+    let to_i32 = |limit: i64| {
+        i32::try_from(limit).map_err(|_| app::Error::WithMessage(format!("{limit} is too large")))
+    };
+    let (down, up) = (to_i32(limits.down.limit)?, to_i32(limits.up.limit)?);
+    let res = {
+        let mut client = client.borrow_mut();
+        async move {
+            match ids {
+                Some(ids) => {
+                    let args = TorrentSetArgs {
+                        download_limit: Some(down),
+                        download_limited: Some(limits.down.enabled),
+                        upload_limit: Some(up),
+                        upload_limited: Some(limits.up.enabled),
+                        ..TorrentSetArgs::default()
+                    };
+                    let ids = ids.into_iter().map(Id::Id).collect();
+                    client.torrent_set(args, Some(ids)).await.map(|_| ())
+                }
+                None => {
+                    let args = SessionSetArgs {
+                        speed_limit_down: Some(down),
+                        speed_limit_down_enabled: Some(limits.down.enabled),
+                        speed_limit_up: Some(up),
+                        speed_limit_up_enabled: Some(limits.up.enabled),
+                        ..SessionSetArgs::default()
+                    };
+                    client.session_set(args).await.map(|_| ())
+                }
+            }
+        }
+    }
+    .await;
+    res.map_err(|err| app::Error::WithMessage(err.to_string()))
+}
+
+/// Check that the daemon answers, with the smallest call there is.
+pub async fn ping(client: &Rc<RefCell<Client>>) -> Result<(), app::Error> {
+    let mut client = client.borrow_mut();
+    async move {
+        client
+            .call::<serde_json::Value>("session-get", json!({ "fields": ["rpc-version"] }))
+            .await
+    }
+    .await
+    .map(|_| ())
+}
+
+#[derive(Deserialize)]
+struct DownloadDir {
+    #[serde(rename = "download-dir")]
+    download_dir: String,
+}
+
+/// The directory torrents are downloaded to unless told otherwise.
+pub async fn fetch_download_dir(client: &Rc<RefCell<Client>>) -> Result<String, app::Error> {
+    let mut client = client.borrow_mut();
+    let res: DownloadDir = async move {
+        client
+            .call("session-get", json!({ "fields": ["download-dir"] }))
+            .await
+    }
+    .await?;
+    Ok(res.download_dir)
+}
+
+#[derive(Deserialize)]
+struct FreeSpace {
+    #[serde(rename = "size-bytes")]
+    size_bytes: i64,
+}
+
+/// Bytes available in `path` on the daemon's host.
+pub async fn fetch_free_space(client: &Rc<RefCell<Client>>, path: &str) -> Result<i64, app::Error> {
+    let mut client = client.borrow_mut();
+    let res: FreeSpace =
+        async move { client.call("free-space", json!({ "path": path })).await }.await?;
+    Ok(res.size_bytes)
+}
+
+/// The settings of the session shown in the footer, fetched together at every tick.
+pub struct SessionStatus {
+    /// Whether the alternative speed limits, Transmission's turtle mode, are applied.
+    pub alt_speed: bool,
+    pub limits: SpeedLimits,
+    pub download_dir: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SessionStatusFields {
+    alt_speed_enabled: bool,
+    download_dir: String,
+    #[serde(flatten)]
+    limits: SessionSpeedLimits,
+}
+
+pub async fn fetch_session_status(
+    client: &Rc<RefCell<Client>>,
+) -> Result<SessionStatus, app::Error> {
+    let fields = ["alt-speed-enabled", "download-dir"]
+        .into_iter()
+        .chain(SPEED_LIMIT_FIELDS)
+        .collect_vec();
+    let mut client = client.borrow_mut();
+    let res: SessionStatusFields = async move {
+        client
+            .call("session-get", json!({ "fields": fields }))
+            .await
+    }
+    .await?;
+    Ok(SessionStatus {
+        alt_speed: res.alt_speed_enabled,
+        limits: res.limits.into(),
+        download_dir: res.download_dir,
+    })
+}
+
+pub async fn set_alt_speed_enabled(
+    client: &Rc<RefCell<Client>>,
+    enabled: bool,
+) -> Result<(), app::Error> {
+    let res = {
+        let mut client = client.borrow_mut();
+        async move {
+            let args = SessionSetArgs {
+                alt_speed_enabled: Some(enabled),
+                ..SessionSetArgs::default()
+            };
+            client.session_set(args).await
+        }
+    }
+    .await;
+    res.map(|_| ())
+        .map_err(|err| app::Error::WithMessage(err.to_string()))
+}
+
+/// A daemon setting edited in [`Mode::Session`](crate::app::Mode::Session).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum Setting {
+    DownloadDir,
+    IncompleteDirEnabled,
+    IncompleteDir,
+    PeerPort,
+    PeerLimitGlobal,
+    PeerLimitPerTorrent,
+    Encryption,
+    Dht,
+    Pex,
+    Lpd,
+    Utp,
+    SeedRatioLimited,
+    SeedRatioLimit,
+    IdleSeedingLimitEnabled,
+    IdleSeedingLimit,
+}
+
+/// The kind of value of a [`Setting`], deciding how it is edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    Text,
+    /// A whole number from the minimum up to the maximum.
+    Number(i64, i64),
+    Decimal,
+    Toggle,
+    /// One of the given values.
+    Choice(&'static [&'static str]),
+}
+
+impl Setting {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::DownloadDir => "Download directory",
+            Self::IncompleteDirEnabled => "Keep incomplete torrents apart",
+            Self::IncompleteDir => "Incomplete directory",
+            Self::PeerPort => "Peer port",
+            Self::PeerLimitGlobal => "Peer limit",
+            Self::PeerLimitPerTorrent => "Peer limit per torrent",
+            Self::Encryption => "Encryption",
+            Self::Dht => "DHT",
+            Self::Pex => "Peer exchange (PEX)",
+            Self::Lpd => "Local peer discovery (LPD)",
+            Self::Utp => "Micro transport protocol (µTP)",
+            Self::SeedRatioLimited => "Stop seeding at ratio",
+            Self::SeedRatioLimit => "Seed ratio limit",
+            Self::IdleSeedingLimitEnabled => "Stop seeding when idle",
+            Self::IdleSeedingLimit => "Idle seeding limit (minutes)",
+        }
+    }
+
+    /// The name of the setting in `session-get` and `session-set`.
+    fn key(self) -> &'static str {
+        match self {
+            Self::DownloadDir => "download-dir",
+            Self::IncompleteDirEnabled => "incomplete-dir-enabled",
+            Self::IncompleteDir => "incomplete-dir",
+            Self::PeerPort => "peer-port",
+            Self::PeerLimitGlobal => "peer-limit-global",
+            Self::PeerLimitPerTorrent => "peer-limit-per-torrent",
+            Self::Encryption => "encryption",
+            Self::Dht => "dht-enabled",
+            Self::Pex => "pex-enabled",
+            Self::Lpd => "lpd-enabled",
+            Self::Utp => "utp-enabled",
+            Self::SeedRatioLimited => "seedRatioLimited",
+            Self::SeedRatioLimit => "seedRatioLimit",
+            Self::IdleSeedingLimitEnabled => "idle-seeding-limit-enabled",
+            Self::IdleSeedingLimit => "idle-seeding-limit",
+        }
+    }
+
+    pub fn kind(self) -> SettingKind {
+        match self {
+            Self::DownloadDir | Self::IncompleteDir => SettingKind::Text,
+            Self::PeerPort => SettingKind::Number(1, 65535),
+            Self::PeerLimitGlobal | Self::PeerLimitPerTorrent | Self::IdleSeedingLimit => {
+                SettingKind::Number(0, i64::from(u16::MAX))
+            }
+            Self::Encryption => SettingKind::Choice(&["required", "preferred", "tolerated"]),
+            Self::SeedRatioLimit => SettingKind::Decimal,
+            Self::IncompleteDirEnabled
+            | Self::Dht
+            | Self::Pex
+            | Self::Lpd
+            | Self::Utp
+            | Self::SeedRatioLimited
+            | Self::IdleSeedingLimitEnabled => SettingKind::Toggle,
+        }
+    }
+
+    /// The value as shown and edited.
+    pub fn display(self, value: &serde_json::Value) -> String {
+        match (self.kind(), value) {
+            (SettingKind::Toggle, serde_json::Value::Bool(enabled)) => match enabled {
+                true => "on".to_string(),
+                false => "off".to_string(),
+            },
+            (SettingKind::Decimal, value) => format!("{:.2}", value.as_f64().unwrap_or_default()),
+            (_, serde_json::Value::String(value)) => value.clone(),
+            (_, value) => value.to_string(),
+        }
+    }
+
+    /// The value typed for the setting, or why it isn't valid.
+    pub fn parse(self, input: &str) -> Result<serde_json::Value, String> {
+        let input = input.trim();
+        match self.kind() {
+            SettingKind::Text if input.is_empty() => Err("can't be empty".to_string()),
+            SettingKind::Text => Ok(json!(input)),
+            SettingKind::Number(min, max) => match input.parse::<i64>() {
+                Ok(number) if (min..=max).contains(&number) => Ok(json!(number)),
+                _ => Err(format!("must be a whole number from {min} to {max}")),
+            },
+            SettingKind::Decimal => match input.parse::<f64>() {
+                Ok(number) if number >= 0.0 => Ok(json!(number)),
+                _ => Err("must be a positive number".to_string()),
+            },
+            SettingKind::Toggle => match input {
+                "on" | "true" => Ok(json!(true)),
+                "off" | "false" => Ok(json!(false)),
+                _ => Err("must be on or off".to_string()),
+            },
+            SettingKind::Choice(values) => match values.contains(&input) {
+                true => Ok(json!(input)),
+                false => Err(format!("must be one of {}", values.join(", "))),
+            },
+        }
+    }
+
+    /// The value following `value`, for settings toggled or cycled through rather than typed.
+    pub fn next(self, value: &serde_json::Value) -> Option<serde_json::Value> {
+        match self.kind() {
+            SettingKind::Toggle => Some(json!(!value.as_bool()?)),
+            SettingKind::Choice(values) => {
+                let i = values.iter().position(|v| Some(*v) == value.as_str());
+                Some(json!(values[i.map_or(0, |i| (i + 1) % values.len())]))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The settings of the daemon's session, in the order of [`Setting`].
+pub async fn fetch_session_settings(
+    client: &Rc<RefCell<Client>>,
+) -> Result<Vec<(Setting, serde_json::Value)>, app::Error> {
+    let keys = Setting::iter().map(Setting::key).collect_vec();
+    let mut res: serde_json::Map<String, serde_json::Value> = {
+        let mut client = client.borrow_mut();
+        async move { client.call("session-get", json!({ "fields": keys })).await }
+    }
+    .await?;
+    // Settings missing from older daemons are left out.
+    Ok(Setting::iter()
+        .filter_map(|setting| Some((setting, res.remove(setting.key())?)))
+        .collect())
+}
+
+pub async fn set_session_setting(
+    client: &Rc<RefCell<Client>>,
+    setting: Setting,
+    value: serde_json::Value,
+) -> Result<(), app::Error> {
+    let mut arguments = serde_json::Map::new();
+    arguments.insert(setting.key().to_string(), value);
+    let mut client = client.borrow_mut();
+    async move {
+        client
+            .call::<serde_json::Value>("session-set", arguments.into())
+            .await
+    }
+    .await
+    .map(|_| ())
+}
+
+#[derive(Deserialize)]
+struct QueueTorrents {
+    torrents: Vec<QueueEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueueEntry {
+    id: i64,
+    queue_position: i64,
+}
+
+/// The queue position of each torrent by id, which transmission-rpc doesn't fetch.
+async fn fetch_queue_positions(
+    client: &Rc<RefCell<Client>>,
+    id: Option<i64>,
+) -> Result<HashMap<i64, i64>, app::Error> {
+    let mut arguments = json!({ "fields": ["id", "queuePosition"] });
+    if let Some(id) = id {
+        arguments["ids"] = json!([id]);
+    }
+    let mut client = client.borrow_mut();
+    let res = async move { client.call::<QueueTorrents>("torrent-get", arguments).await }.await?;
+    Ok(res
+        .torrents
+        .into_iter()
+        .map(|t| (t.id, t.queue_position))
+        .collect())
+}
+
+/// Make the speed limits of the session apply to the torrents with the given ids, or not.
+pub async fn set_honors_session_limits(
+    client: &Rc<RefCell<Client>>,
+    ids: Vec<i64>,
+    honors: bool,
+) -> Result<(), app::Error> {
+    let res = {
+        let mut client = client.borrow_mut();
+        async move {
+            let args = TorrentSetArgs {
+                honors_session_limits: Some(honors),
+                ..TorrentSetArgs::default()
+            };
+            client
+                .torrent_set(args, Some(ids.into_iter().map(Id::Id).collect()))
+                .await
+        }
+    }
+    .await;
+    res.map(|_| ())
+        .map_err(|err| app::Error::WithMessage(err.to_string()))
+}
+
+pub async fn set_labels(
+    client: &Rc<RefCell<Client>>,
+    ids: Vec<i64>,
+    labels: Vec<String>,
+) -> Result<(), app::Error> {
+    let res = {
+        let mut client = client.borrow_mut();
+        async move {
+            let args = TorrentSetArgs {
+                labels: Some(labels),
+                ..TorrentSetArgs::default()
+            };
+            client
+                .torrent_set(args, Some(ids.into_iter().map(Id::Id).collect()))
+                .await
+        }
+    }
+    .await;
+    res.map(|_| ())
+        .map_err(|err| app::Error::WithMessage(err.to_string()))
+}
+
+/// Add `source`, a local `.torrent` file, a URL or a magnet link, returning its id and
+/// whether it was already added.
+pub async fn add_torrent(
+    client: &Rc<RefCell<Client>>,
+    sites: &HashMap<String, SiteConfig>,
+    source: String,
+    location: String,
+    paused: bool,
+) -> types::Result<(i64, bool)> {
+    // Local files are sent along since the daemon may run on another host, and links are
+    // downloaded here since they may need the cookies of a private tracker.
+    let content = match std::fs::read(&source) {
+        Ok(content) => Some(content),
+        Err(_) if source.starts_with("http://") || source.starts_with("https://") => {
+            Some(download_torrent(&source, sites).await?)
+        }
+        Err(_) => None,
+    };
+    let (filename, metainfo) = match content {
+        Some(content) => (None, Some(BASE64_STANDARD.encode(content))),
+        None => (Some(source), None),
+    };
+    let args = TorrentAddArgs {
+        filename,
+        metainfo,
+        download_dir: (!location.is_empty()).then_some(location),
+        paused: Some(paused),
+        ..TorrentAddArgs::default()
+    };
+    let mut client = client.borrow_mut();
+    let res = async move { client.torrent_add(args).await }.await?;
+    let (torrent, duplicate) = match res.arguments {
+        TorrentAddedOrDuplicate::TorrentAdded(torrent) => (torrent, false),
+        TorrentAddedOrDuplicate::TorrentDuplicate(torrent) => (torrent, true),
+        TorrentAddedOrDuplicate::Error => {
+            return Err(app::Error::WithMessage(res.result).into());
+        }
+    };
+    let id = torrent
+        .id
+        .ok_or(app::Error::WithMessage("Missing torrent id".to_string()))?;
+    Ok((id, duplicate))
+}
+
+/// Longest a `.torrent` file may take to download.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+/// Redirects followed when downloading a `.torrent` file.
+const MAX_REDIRECTS: usize = 10;
+/// Largest `.torrent` file downloaded, well above those of torrents of thousands of files.
+const MAX_TORRENT_SIZE: usize = 16 * 1024 * 1024;
+
+/// Download the `.torrent` file at `url` with the cookies and headers configured for its site.
+///
+/// Redirects are followed here rather than by reqwest, which would send the headers of the site
+/// on to whichever host it redirects to: each request gets those of its own host.
+async fn download_torrent(
+    url: &str,
+    sites: &HashMap<String, SiteConfig>,
+) -> Result<Vec<u8>, app::Error> {
+    let message = |err: &dyn std::fmt::Display| {
+        app::Error::WithMessage(format!("Failed to download {}: {err}", redact_url(url)))
+    };
+    let fail = |err: reqwest::Error| message(&err.without_url());
+    let http = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(fail)?;
+    let mut target = reqwest::Url::parse(url).map_err(|err| message(&err))?;
+    let mut redirects = 0;
+    let mut response = loop {
+        let mut request = http.get(target.clone());
+        if let Some(site) = target.host_str().and_then(|host| site_for(sites, host)) {
+            if let Some(cookies) = &site.cookies {
+                request = request.header(reqwest::header::COOKIE, cookies);
+            }
+            for (key, value) in &site.headers {
+                request = request.header(key, value);
+            }
+        }
+        let response = request.send().await.map_err(fail)?;
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .filter(|_| response.status().is_redirection())
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| target.join(location).ok());
+        match location {
+            Some(_) if redirects == MAX_REDIRECTS => return Err(message(&"too many redirects")),
+            Some(location) => {
+                target = location;
+                redirects += 1;
+            }
+            None => break response.error_for_status().map_err(fail)?,
+        }
+    };
+    let too_large = || {
+        app::Error::WithMessage(format!(
+            "{} is over {}, not a .torrent file",
+            redact_url(url),
+            convert_bytes(MAX_TORRENT_SIZE as i64)
+        ))
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_TORRENT_SIZE as u64)
+    {
+        return Err(too_large());
+    }
+    let mut content = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(fail)? {
+        if content.len() + chunk.len() > MAX_TORRENT_SIZE {
+            return Err(too_large());
+        }
+        content.extend_from_slice(&chunk);
+    }
+    // A bencoded dictionary, rather than e.g. the login page of a tracker.
+    if !content.starts_with(b"d") {
+        return Err(app::Error::WithMessage(format!(
+            "{} is not a .torrent file",
+            redact_url(url)
+        )));
+    }
+    Ok(content)
+}
+
+/// Rename `path` of torrent `id`, its name or a file or directory in it, to `name`. The data is
+/// renamed on disk by the daemon.
+pub async fn rename_path(
+    client: &Rc<RefCell<Client>>,
+    id: i64,
+    path: String,
+    name: String,
+) -> Result<(), app::Error> {
+    check_name(&path, &name).map_err(app::Error::WithMessage)?;
+    let mut client = client.borrow_mut();
+    let res = async move {
+        client
+            .torrent_rename_path(vec![Id::Id(id)], path, name)
+            .await
+    }
+    .await
+    .map_err(|err| app::Error::WithMessage(err.to_string()))?;
+    match res.is_ok() {
+        true => Ok(()),
+        false => Err(app::Error::WithMessage(res.result)),
+    }
+}
+
+/// Whether `name` can replace the last component of `path`, staying in the same directory.
+fn check_name(path: &str, name: &str) -> Result<(), String> {
+    let current = path.rsplit('/').next().unwrap_or(path);
+    match name {
+        "" | "." | ".." => Err(format!("Invalid name \"{name}\"")),
+        _ if name.contains('/') => Err("Names can't contain \"/\"".to_string()),
+        _ if name == current => Err(format!("{current} already has this name")),
+        _ => Ok(()),
+    }
+}
+
+/// Where the data of a torrent is, as matched by [`crate::config::LabelRule`].
+pub fn data_path(download_dir: &str, name: &str) -> String {
+    format!("{}/{name}", download_dir.trim_end_matches('/'))
+}
+
+/// The label rules matched by `torrents`, ids and data paths of unlabeled torrents, with the
+/// ids matching each label.
+pub fn rule_labels(
+    rules: &[LabelRule],
+    torrents: impl IntoIterator<Item = (i64, String)>,
+) -> Vec<(String, Vec<i64>)> {
+    torrents
+        .into_iter()
+        .filter_map(|(id, path)| Some((label_for(rules, &path)?.to_string(), id)))
+        .into_group_map()
+        .into_iter()
+        .sorted()
+        .collect()
+}
+
+/// Where to move torrents in the download queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum QueueMove {
+    Up,
+    Down,
+    Top,
+    Bottom,
+}
+
+pub async fn move_in_queue(
+    client: &Rc<RefCell<Client>>,
+    ids: Vec<i64>,
+    to: QueueMove,
+) -> Result<(), app::Error> {
+    // The queue methods aren't wrapped by transmission-rpc.
+    let method = format!("queue-move-{to}");
+    let mut client = client.borrow_mut();
+    async move {
+        client
+            .call::<serde_json::Value>(&method, json!({ "ids": ids }))
+            .await
+    }
+    .await
+    .map(|_| ())
+}
+
+/// A change to the trackers of a torrent, whose existing trackers are named by id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackerChange {
+    Add(String),
+    Replace(i64, String),
+    Remove(i64),
+}
+
+impl TrackerChange {
+    /// The `torrent-set` arguments making the change to torrent `id`.
+    fn arguments(&self, id: i64) -> serde_json::Value {
+        match self {
+            Self::Add(url) => json!({ "ids": [id], "trackerAdd": [url] }),
+            Self::Replace(tracker, url) => json!({ "ids": [id], "trackerReplace": [tracker, url] }),
+            Self::Remove(tracker) => json!({ "ids": [id], "trackerRemove": [tracker] }),
+        }
+    }
+}
+
+pub async fn change_tracker(
+    client: &Rc<RefCell<Client>>,
+    id: i64,
+    change: &TrackerChange,
+) -> Result<(), app::Error> {
+    // transmission-rpc sends tracker ids as strings, which the daemon rejects.
+    let arguments = change.arguments(id);
+    let mut client = client.borrow_mut();
+    async move {
+        client
+            .call::<serde_json::Value>("torrent-set", arguments)
+            .await
+    }
+    .await
+    .map(|_| ())
+}
+
+/// A peer connected for a torrent, from the raw `peers` field.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Peer {
+    pub address: String,
+    pub port: i64,
+    pub client_name: String,
+    pub flag_str: String,
+    pub progress: f32,
+    /// Bytes per second downloaded from the peer.
+    pub rate_to_client: i64,
+    /// Bytes per second uploaded to the peer.
+    pub rate_to_peer: i64,
+}
+
+impl Peer {
+    /// Identifies the peer between refreshes.
+    pub fn endpoint(&self) -> String {
+        format!("{}:{}", self.address, self.port)
+    }
+}
+
+#[derive(Deserialize)]
+struct PeerTorrents {
+    torrents: Vec<PeerList>,
+}
+
+#[derive(Deserialize)]
+struct PeerList {
+    peers: Vec<Peer>,
+}
+
+pub async fn fetch_peers(client: &Rc<RefCell<Client>>, id: i64) -> Result<Vec<Peer>, app::Error> {
+    let mut client = client.borrow_mut();
+    let res = async move {
+        client
+            .call::<PeerTorrents>("torrent-get", json!({ "ids": [id], "fields": ["peers"] }))
+            .await
+    }
+    .await?;
+
+    Ok(res
+        .torrents
+        .into_iter()
+        .next()
+        .map(|t| t.peers)
+        .unwrap_or_default())
+}
+
+/// Torrents for the tests of the data and of the views drawing it.
+#[cfg(test)]
+pub mod fixtures {
+    use super::*;
+
+    /// A torrent as listed with `LIST_FIELDS`, and its files if `files`.
+    pub fn rpc_torrent(id: i64, files: bool) -> transmission_rpc::types::Torrent {
+        serde_json::from_value(torrent_json(id, files)).unwrap()
+    }
+
+    /// [`rpc_torrent`] as the daemon sends it.
+    pub fn torrent_json(id: i64, files: bool) -> serde_json::Value {
+        let mut torrent = json!({
+            "id": id, "name": format!("torrent {id}"), "status": 4, "isStalled": false,
+            "eta": 120, "percentDone": 0.5, "sizeWhenDone": 2048, "leftUntilDone": 1024,
+            "totalSize": 2048, "uploadedEver": 512, "uploadRatio": 0.5, "rateDownload": 100,
+            "rateUpload": 10, "downloadDir": "/downloads", "hashString": "abc",
+            "addedDate": 1_700_000_000, "doneDate": 0, "errorString": "", "labels": ["tv"],
+            "trackerStats": [], "peersSendingToUs": 2, "peersGettingFromUs": 1,
+            "peersConnected": 5,
+        });
+        if files {
+            torrent["files"] = json!([{ "name": "a.mkv", "length": 2048, "bytesCompleted": 1024 }]);
+            torrent["fileStats"] =
+                json!([{ "bytesCompleted": 1024, "wanted": true, "priority": 0 }]);
+        }
+        torrent
+    }
+
+    /// [`rpc_torrent`] as the views get it.
+    pub fn torrent(id: i64, files: bool) -> Torrent {
+        map_torrent(rpc_torrent(id, files), &HashMap::new()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::{fixtures::rpc_torrent, *};
+    use crate::rpc::fixtures::serve_raw;
+
+    fn entry(name: &'static str, paths: &[&str]) -> (&'static str, Vec<String>) {
+        (name, paths.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn test_group_duplicates_by_name() {
+        let groups = group_duplicates(&[
+            entry("a", &["/d/a"]),
+            entry("b", &["/d/b"]),
+            entry("a", &["/e/a"]),
+        ]);
+        assert_eq!(
+            groups,
+            vec![DuplicateGroup {
+                members: vec![0, 2],
+                same_name: true,
+                overlapping_files: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_group_duplicates_by_files() {
+        let groups = group_duplicates(&[
+            entry("a", &["/d/x/1", "/d/x/2"]),
+            entry("b", &["/d/y"]),
+            entry("c", &["/d/x/2"]),
+            entry("d", &["/d/y/1"]),
+        ]);
+        assert_eq!(
+            groups,
+            vec![DuplicateGroup {
+                members: vec![0, 2],
+                same_name: false,
+                overlapping_files: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_group_duplicates_transitive() {
+        let groups = group_duplicates(&[
+            entry("a", &["/d/1"]),
+            entry("b", &["/d/1"]),
+            entry("b", &["/e/2"]),
+        ]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].members, vec![0, 1, 2]);
+        assert!(groups[0].same_name && groups[0].overlapping_files);
+    }
+
+    #[test]
+    fn test_collisions() {
+        let files = ["a/1".to_string(), "a/2".to_string(), "a/3".to_string()];
+        let claimed = ["a/1".to_string()];
+        let exists = |path: &str| path == "/dl/a/3";
+        assert_eq!(collisions(&files, &claimed, "/dl", exists), ["a/1", "a/3"]);
+        assert!(collisions(&files, &[], "/other", exists).is_empty());
+    }
+
+    #[test]
+    fn test_shared() {
+        let list = |items: &[&str]| items.iter().map(ToString::to_string).collect_vec();
+        assert_eq!(
+            shared(&[
+                list(&["b.org", "a.org", "a.org"]),
+                list(&["a.org", "b.org"]),
+                list(&["c.org", "b.org", "a.org"]),
+            ]),
+            list(&["a.org", "b.org"])
+        );
+        assert!(shared(&[list(&["a.org"]), list(&[])]).is_empty());
+        assert!(shared(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_toggle_label() {
+        let list = |items: &[&str]| items.iter().map(ToString::to_string).collect_vec();
+        let (added, labels) = toggle_label(&[list(&["tv", "keep"]), list(&["tv"])], "keep");
+        assert!(added);
+        assert_eq!(labels, [list(&["tv", "keep"]), list(&["tv", "keep"])]);
+        let (added, labels) = toggle_label(&labels, "keep");
+        assert!(!added);
+        assert_eq!(labels, [list(&["tv"]), list(&["tv"])]);
+    }
+
+    #[test]
+    fn test_edit_labels() {
+        let list = |items: &[&str]| items.iter().map(ToString::to_string).collect_vec();
+        let labels = [list(&["tv", "keep"]), list(&["tv", "hd"])];
+        // Saved as shown, the labels only some torrents have stay.
+        assert_eq!(edit_labels(&labels, &list(&["tv"])), labels);
+        assert_eq!(
+            edit_labels(&labels, &list(&["shows"])),
+            [list(&["keep", "shows"]), list(&["hd", "shows"])]
+        );
+        assert_eq!(
+            edit_labels(&[list(&["a", "b"])], &list(&["b", "c"])),
+            [list(&["b", "c"])]
+        );
+    }
+
+    #[test]
+    fn test_sharing_data() {
+        let rpc = (1..=4).map(|id| rpc_torrent(id, id != 4)).collect();
+        let mut torrents = map_torrents(rpc, &HashMap::new());
+        torrents[2].location = "/elsewhere/".to_string();
+        let ids = |torrents: Vec<&Torrent>| torrents.iter().map(|t| t.id).collect_vec();
+        assert_eq!(ids(sharing_data(&[&torrents[0]], &torrents)), [2]);
+        assert!(sharing_data(&[&torrents[0], &torrents[1]], &torrents).is_empty());
+        assert!(sharing_data(&[&torrents[2]], &torrents).is_empty());
+    }
+
+    #[test]
+    fn test_cache_merge() {
+        let queue = HashMap::new();
+        let mut cache = TorrentCache::default();
+        cache.insert(map_torrents(
+            (1..=3).map(|id| rpc_torrent(id, false)).collect(),
+            &queue,
+        ));
+        let mut changed = map_torrents(vec![rpc_torrent(2, false)], &queue);
+        changed[0].rate_download = 500;
+        cache.merge(changed, &[3]);
+        let torrents = cache.torrents();
+        assert_eq!(torrents.iter().map(|t| t.id).collect_vec(), [1, 2]);
+        assert_eq!(torrents[1].rate_download, 500);
+    }
+
+    #[test]
+    fn test_setting_parse() {
+        assert_eq!(Setting::PeerPort.parse(" 51413 "), Ok(json!(51413)));
+        assert!(Setting::PeerPort.parse("0").is_err());
+        assert!(Setting::PeerLimitGlobal.parse("many").is_err());
+        assert_eq!(Setting::SeedRatioLimit.parse("1.5"), Ok(json!(1.5)));
+        assert!(Setting::SeedRatioLimit.parse("-1").is_err());
+        assert_eq!(Setting::DownloadDir.parse("/data"), Ok(json!("/data")));
+        assert!(Setting::DownloadDir.parse(" ").is_err());
+        assert_eq!(Setting::Dht.parse("off"), Ok(json!(false)));
+        assert!(Setting::Encryption.parse("always").is_err());
+        assert_eq!(Setting::Utp.parse("on"), Ok(json!(true)));
+    }
+
+    #[test]
+    fn test_setting_next() {
+        assert_eq!(Setting::Pex.next(&json!(true)), Some(json!(false)));
+        assert_eq!(
+            Setting::Encryption.next(&json!("preferred")),
+            Some(json!("tolerated"))
+        );
+        assert_eq!(
+            Setting::Encryption.next(&json!("tolerated")),
+            Some(json!("required"))
+        );
+        assert_eq!(Setting::PeerPort.next(&json!(51413)), None);
+        assert_eq!(Setting::Dht.display(&json!(true)), "on");
+        assert_eq!(Setting::SeedRatioLimit.display(&json!(2)), "2.00");
+    }
+
+    #[test]
+    fn test_map_torrents() {
+        let queue = HashMap::from([(2, 7)]);
+        let torrents = map_torrents(vec![rpc_torrent(2, true), rpc_torrent(1, false)], &queue);
+        assert_eq!(torrents.iter().map(|t| t.id).collect_vec(), [1, 2]);
+        assert!(torrents[0].files.is_empty());
+        assert_eq!(torrents[1].files[0].name, "a.mkv");
+        assert_eq!(torrents[1].queue_position, 7);
+        assert_eq!(torrents[1].have_bytes, 1024);
+        assert!(torrents[1].formatted_name().ends_with("Labels: tv"));
+    }
+
+    /// Time mapping a large list, with `cargo test --release bench_map_torrents -- --ignored
+    /// --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_map_torrents() {
+        let queue = HashMap::new();
+        for files in [false, true] {
+            let torrents = (0..2000).map(|id| rpc_torrent(id, files)).collect_vec();
+            let start = Instant::now();
+            for _ in 0..100 {
+                map_torrents(torrents.clone(), &queue);
+            }
+            println!(
+                "2000 torrents, files: {files}: {:?} per tick",
+                start.elapsed() / 100
+            );
+        }
+    }
+
+    #[test]
+    fn test_group_traffic() {
+        let traffic = |downloaded, uploaded| Traffic {
+            torrents: 1,
+            downloaded,
+            uploaded,
+            ..Traffic::default()
+        };
+        let hosts = |hosts: &[&str]| hosts.iter().map(ToString::to_string).collect_vec();
+        let totals = group_traffic([
+            (hosts(&["a.org", "b.org", "a.org"]), traffic(100, 50)),
+            (hosts(&["a.org"]), traffic(100, 350)),
+            (hosts(&[]), traffic(10, 0)),
+        ]);
+        assert_eq!(
+            totals,
+            [
+                (NO_TRACKER.to_string(), traffic(10, 0)),
+                (
+                    "a.org".to_string(),
+                    Traffic {
+                        torrents: 2,
+                        ..traffic(200, 400)
+                    }
+                ),
+                ("b.org".to_string(), traffic(100, 50)),
+            ]
+        );
+        assert!((totals[1].1.ratio() - 2.0).abs() < f64::EPSILON);
+        assert!(traffic(0, 10).ratio().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_check_name() {
+        assert_eq!(check_name("Show/s01/e01.mkv", "E01.mkv"), Ok(()));
+        assert_eq!(check_name("Show", "Show (2024)"), Ok(()));
+        assert!(check_name("Show", "Show").is_err());
+        assert!(check_name("Show/e01.mkv", "s01/e01.mkv").is_err());
+        assert!(check_name("Show", "").is_err());
+        assert!(check_name("Show", "..").is_err());
+    }
+
+    #[test]
+    fn test_tracker_change_arguments() {
+        assert_eq!(
+            TrackerChange::Add("https://t.example/announce".to_string()).arguments(3),
+            json!({ "ids": [3], "trackerAdd": ["https://t.example/announce"] })
+        );
+        assert_eq!(
+            TrackerChange::Replace(1, "udp://t.example:80".to_string()).arguments(3),
+            json!({ "ids": [3], "trackerReplace": [1, "udp://t.example:80"] })
+        );
+        assert_eq!(
+            TrackerChange::Remove(0).arguments(3),
+            json!({ "ids": [3], "trackerRemove": [0] })
+        );
+    }
+
+    #[test]
+    fn test_referenced_names() {
+        let referenced = referenced_names([
+            ("/data/", vec!["Show", "Show"]),
+            ("/data/movies/hd", vec!["Film.mkv"]),
+        ]);
+        let data = &referenced["/data"];
+        assert!(is_referenced(data, "Show"));
+        // Directories leading to a location aren't orphans.
+        assert!(is_referenced(data, "movies"));
+        assert!(!is_referenced(data, "Old"));
+        assert!(is_referenced(&referenced["/data/movies"], "hd"));
+        let hd = &referenced["/data/movies/hd"];
+        assert!(is_referenced(hd, "Film.mkv.part"));
+        assert!(!is_referenced(hd, "Film.part"));
+        assert!(!referenced.contains_key(""));
+    }
+
+    fn http_response(head: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {head}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[tokio::test]
+    async fn test_download_torrent_keeps_the_site_headers_to_its_host() {
+        let torrent = "d4:infod4:name1:xee";
+        let (elsewhere, elsewhere_requests) = serve_raw(vec![http_response("200 OK", torrent)]);
+        let location = format!(
+            "302 Found\r\nLocation: http://localhost:{}/a.torrent",
+            elsewhere.port()
+        );
+        let (site, site_requests) = serve_raw(vec![http_response(&location, "")]);
+        let config = SiteConfig {
+            cookies: Some("uid=1".to_string()),
+            headers: HashMap::from([("X-Api-Key".to_string(), "secret".to_string())]),
+        };
+        let sites = HashMap::from([("127.0.0.1".to_string(), config)]);
+        let content = download_torrent(&format!("http://{site}/download/1"), &sites)
+            .await
+            .unwrap();
+        assert_eq!(content, torrent.as_bytes());
+        let first = site_requests.recv().unwrap().to_lowercase();
+        assert!(first.contains("x-api-key: secret") && first.contains("cookie: uid=1"));
+        let second = elsewhere_requests.recv().unwrap().to_lowercase();
+        assert!(!second.contains("secret") && !second.contains("uid=1"));
+    }
+
+    #[tokio::test]
+    async fn test_download_torrent_size_limit() {
+        let declared = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\nd",
+            MAX_TORRENT_SIZE + 1
+        );
+        // Without a length, the body is read until it goes over.
+        let streamed = format!(
+            "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\nd{}",
+            "0".repeat(MAX_TORRENT_SIZE)
+        );
+        for response in [declared, streamed] {
+            let (address, _) = serve_raw(vec![response]);
+            let err = download_torrent(&format!("http://{address}/a.torrent"), &HashMap::new())
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("is over 16"), "{err}");
+        }
+    }
 }
@@ -0,0 +1,38 @@
+//! Showing desktop notifications through the tools of the platform.
+//!
+//! They're sent with `notify-send` and `osascript` rather than `notify-rust`, which would bring
+//! a D-Bus client and its async runtime into the build for a notification now and then. The
+//! commands also fail with a message saying what's missing, shown like other failures.
+
+use std::process::Command;
+
+/// The command showing a notification with `title` and `body`.
+#[cfg(target_os = "macos")]
+fn command(title: &str, body: &str) -> Command {
+    // AppleScript strings escape quotes and backslashes like Rust's.
+    let script = format!("display notification {body:?} with title {title:?}");
+    let mut command = Command::new("osascript");
+    command.args(["-e", &script]);
+    command
+}
+
+#[cfg(not(target_os = "macos"))]
+fn command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=sparrow", title, body]);
+    command
+}
+
+/// Show a notification, waiting for the command to hand it over.
+pub fn show(title: &str, body: &str) -> Result<(), String> {
+    let mut command = command(title, body);
+    let program = command.get_program().to_string_lossy().into_owned();
+    match command.output() {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(err) => Err(format!("Can't run {program}: {err}")),
+    }
+}
@@ -0,0 +1,324 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::RwLock,
+};
+
+use chrono::Utc;
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::{data::Torrent, store::Store};
+
+/// Minimum number of seconds between two stored samples.
+const SAMPLE_INTERVAL: i64 = 5 * 60;
+/// Samples older than this many seconds are dropped.
+const RETENTION: i64 = 31 * 24 * 60 * 60;
+
+/// Cumulative session totals at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sample {
+    /// Unix timestamp in seconds.
+    pub time: i64,
+    pub downloaded: i64,
+    pub uploaded: i64,
+}
+
+/// Session stat samples kept in the local store, since the daemon only knows the totals.
+pub struct History {
+    store: Store,
+    samples: Vec<Sample>,
+}
+
+impl History {
+    pub fn load() -> Result<Self> {
+        let mut history = Self {
+            store: Store::open()?,
+            samples: Vec::new(),
+        };
+        history.reload()?;
+        Ok(history)
+    }
+
+    /// No samples, in a store that isn't kept, for tests.
+    #[cfg(test)]
+    pub fn empty() -> Result<Self> {
+        Ok(Self {
+            store: Store::in_memory()?,
+            samples: Vec::new(),
+        })
+    }
+
+    /// Read the samples again, e.g. after another instance recorded some.
+    pub fn reload(&mut self) -> Result<()> {
+        self.samples = self.store.samples(Utc::now().timestamp() - RETENTION)?;
+        Ok(())
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    /// Store `sample` if enough time passed since the last one.
+    pub fn record(&mut self, sample: Sample) -> Result<()> {
+        if push(&mut self.samples, sample) {
+            self.store.insert_sample(sample, sample.time - RETENTION)?;
+        }
+        Ok(())
+    }
+}
+
+/// The first and the latest samples of this run of sparrow.
+static RUN: RwLock<Option<(Sample, Sample)>> = RwLock::new(None);
+
+/// Note `sample` as the latest totals of this run, the first one being where it started.
+pub fn record_run(sample: Sample) {
+    let mut run = RUN.write().unwrap_or_else(|err| err.into_inner());
+    let first = run.map_or(sample, |(first, _)| first);
+    *run = Some((first, sample));
+}
+
+/// Forget the totals of this run, e.g. after switching to another daemon.
+pub fn reset_run() {
+    *RUN.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// The traffic since sparrow started, with the seconds elapsed as `time`.
+pub fn run_traffic() -> Option<Sample> {
+    let run = *RUN.read().unwrap_or_else(|err| err.into_inner());
+    run.map(|(first, latest)| difference(first, latest))
+}
+
+/// What happened between `first` and `latest`, nothing if the daemon's totals were reset.
+fn difference(first: Sample, latest: Sample) -> Sample {
+    Sample {
+        time: latest.time - first.time,
+        downloaded: (latest.downloaded - first.downloaded).max(0),
+        uploaded: (latest.uploaded - first.uploaded).max(0),
+    }
+}
+
+/// Add `sample` and drop expired ones, returning whether anything changed.
+fn push(samples: &mut Vec<Sample>, sample: Sample) -> bool {
+    if samples
+        .last()
+        .is_some_and(|last| sample.time - last.time < SAMPLE_INTERVAL)
+    {
+        return false;
+    }
+    samples.push(sample);
+    samples.retain(|s| sample.time - s.time <= RETENTION);
+    true
+}
+
+/// Rates kept per torrent and for the session, one per tick.
+pub const RATE_SAMPLES: usize = 120;
+
+/// The latest download and upload rates, oldest first.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Rates {
+    down: VecDeque<i64>,
+    up: VecDeque<i64>,
+}
+
+impl Rates {
+    pub fn push(&mut self, down: i64, up: i64) {
+        if self.down.len() == RATE_SAMPLES {
+            self.down.pop_front();
+            self.up.pop_front();
+        }
+        self.down.push_back(down);
+        self.up.push_back(up);
+    }
+
+    /// The last `count` download rates.
+    pub fn down(&self, count: usize) -> Vec<i64> {
+        last(&self.down, count)
+    }
+
+    /// The last `count` upload rates.
+    pub fn up(&self, count: usize) -> Vec<i64> {
+        last(&self.up, count)
+    }
+}
+
+fn last(samples: &VecDeque<i64>, count: usize) -> Vec<i64> {
+    let skip = samples.len().saturating_sub(count);
+    samples.iter().skip(skip).copied().collect()
+}
+
+/// Rates of the torrents by id, sampled by whichever view shows them.
+static TORRENT_RATES: RwLock<BTreeMap<i64, Rates>> = RwLock::new(BTreeMap::new());
+
+/// Sample the rates of `torrents`, forgetting the others when the list holds every torrent.
+pub fn record_torrent_rates(torrents: &[Torrent], every_torrent: bool) {
+    let mut rates = TORRENT_RATES.write().unwrap_or_else(|err| err.into_inner());
+    if every_torrent {
+        rates.retain(|id, _| torrents.iter().any(|t| t.id == *id));
+    }
+    for torrent in torrents {
+        let samples = rates.entry(torrent.id).or_default();
+        samples.push(torrent.rate_download, torrent.rate_upload);
+    }
+}
+
+/// The rates sampled for torrent `id`.
+pub fn torrent_rates(id: i64) -> Rates {
+    let rates = TORRENT_RATES.read().unwrap_or_else(|err| err.into_inner());
+    rates.get(&id).cloned().unwrap_or_default()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display)]
+pub enum Period {
+    #[default]
+    Hourly,
+    Daily,
+}
+
+impl Period {
+    pub const fn seconds(self) -> i64 {
+        match self {
+            Period::Hourly => 60 * 60,
+            Period::Daily => 24 * 60 * 60,
+        }
+    }
+
+    /// Number of buckets shown for the period.
+    pub const fn len(self) -> usize {
+        match self {
+            Period::Hourly => 24,
+            Period::Daily => 30,
+        }
+    }
+
+    pub const fn toggle(self) -> Self {
+        match self {
+            Period::Hourly => Period::Daily,
+            Period::Daily => Period::Hourly,
+        }
+    }
+}
+
+/// Bytes transferred during one period.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    /// Unix timestamp of the start of the period.
+    pub start: i64,
+    pub downloaded: u64,
+    pub uploaded: u64,
+}
+
+/// Sum the traffic between consecutive samples into the last `period.len()` periods up to `now`.
+///
+/// `offset` is the local UTC offset in seconds so that days start at local midnight.
+/// When the totals go down, e.g. after the daemon's stats were reset, the interval is skipped.
+pub fn aggregate(samples: &[Sample], period: Period, now: i64, offset: i64) -> Vec<Bucket> {
+    let size = period.seconds();
+    let index = |time: i64| (time + offset).div_euclid(size);
+    let last = index(now);
+    let first = last - period.len() as i64 + 1;
+    let mut buckets = (first..=last)
+        .map(|i| Bucket {
+            start: i * size - offset,
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+    for pair in samples.windows(2) {
+        let (previous, current) = (pair[0], pair[1]);
+        let i = index(current.time);
+        if i < first || i > last {
+            continue;
+        }
+        let bucket = &mut buckets[(i - first) as usize];
+        let downloaded = current.downloaded - previous.downloaded;
+        let uploaded = current.uploaded - previous.uploaded;
+        if downloaded >= 0 && uploaded >= 0 {
+            bucket.downloaded += downloaded as u64;
+            bucket.uploaded += uploaded as u64;
+        }
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(time: i64, downloaded: i64, uploaded: i64) -> Sample {
+        Sample {
+            time,
+            downloaded,
+            uploaded,
+        }
+    }
+
+    #[test]
+    fn test_push() {
+        let mut samples = Vec::new();
+        assert!(push(&mut samples, sample(0, 0, 0)));
+        assert!(!push(&mut samples, sample(SAMPLE_INTERVAL - 1, 0, 0)));
+        assert!(push(&mut samples, sample(SAMPLE_INTERVAL, 0, 0)));
+        assert!(push(
+            &mut samples,
+            sample(RETENTION + SAMPLE_INTERVAL + 1, 0, 0)
+        ));
+        assert_eq!(samples.len(), 1);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let hour = Period::Hourly.seconds();
+        let now = 100 * hour + 10;
+        let samples = [
+            sample(97 * hour, 0, 0),
+            sample(98 * hour + 5, 100, 10),
+            sample(98 * hour + 50, 150, 20),
+            // Stats were reset.
+            sample(99 * hour, 20, 0),
+            sample(100 * hour, 30, 5),
+        ];
+        let buckets = aggregate(&samples, Period::Hourly, now, 0);
+        assert_eq!(buckets.len(), 24);
+        assert_eq!(buckets[23].start, 100 * hour);
+        let tail = buckets[20..].iter().map(|b| (b.downloaded, b.uploaded));
+        assert_eq!(
+            tail.collect::<Vec<_>>(),
+            [(0, 0), (150, 20), (0, 0), (10, 5)]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_offset() {
+        let day = Period::Daily.seconds();
+        let offset = 2 * 60 * 60;
+        let buckets = aggregate(&[], Period::Daily, 10 * day, offset);
+        assert_eq!(buckets[29].start, 10 * day - offset);
+    }
+
+    #[test]
+    fn test_difference() {
+        assert_eq!(
+            difference(sample(100, 1000, 500), sample(160, 1800, 900)),
+            sample(60, 800, 400)
+        );
+        assert_eq!(
+            difference(sample(100, 1000, 500), sample(160, 10, 5)),
+            sample(60, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_rates() {
+        let mut rates = Rates::default();
+        for rate in 0..RATE_SAMPLES as i64 + 5 {
+            rates.push(rate, rate * 2);
+        }
+        assert_eq!(rates.down(usize::MAX).len(), RATE_SAMPLES);
+        assert_eq!(rates.down(usize::MAX)[0], 5);
+        let last = RATE_SAMPLES as i64 + 4;
+        assert_eq!(rates.down(2), [last - 1, last]);
+        assert_eq!(rates.up(1), [last * 2]);
+        assert!(Rates::default().down(3).is_empty());
+    }
+}
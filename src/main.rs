@@ -1,23 +1,43 @@
 use std::{cell::RefCell, rc::Rc};
 
 use clap::Parser;
-use cli::Cli;
-use color_eyre::Result;
-use transmission_rpc::{types::BasicAuth, TransClient};
+use cli::{Cli, Command};
+use color_eyre::{eyre::eyre, Result};
 
-use crate::app::App;
+use crate::{
+    app::App,
+    config::{Config, DEFAULT_FRAME_RATE, DEFAULT_TICK_RATE},
+    rpc::Client,
+};
 
 mod action;
+mod animation;
 mod app;
+mod batch;
 mod cli;
+#[cfg(feature = "clipboard")]
+mod clipboard;
 mod colors;
 mod components;
 mod config;
+mod connection;
+mod credentials;
 mod data;
+#[cfg(feature = "desktop")]
+mod desktop;
 mod errors;
+mod history;
 mod logging;
+mod media;
+mod metrics;
+mod notify;
+mod opener;
+mod proxy;
+mod rpc;
+mod store;
 mod tui;
 mod utils;
+mod view;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -25,17 +45,45 @@ async fn main() -> Result<()> {
     crate::logging::init()?;
 
     let args = Cli::parse();
-    let url = args.url;
-    let client;
-    if let (Some(user), Some(password)) = (args.username, args.password) {
-        client = Rc::new(RefCell::new(TransClient::with_auth(
-            url.parse()?,
-            BasicAuth { user, password },
-        )));
-    } else {
-        client = Rc::new(RefCell::new(TransClient::new(url.parse()?)));
+    let mut config = Config::new()?;
+    args.apply(&mut config);
+    utils::set_eta_format(config.eta);
+    utils::set_percent_format(config.percent.clone());
+    utils::set_locale_format(config.locale.clone());
+    colors::set_theme(
+        config
+            .theme_colors(config.theme_name())
+            .map_err(|err| eyre!(err))?,
+    );
+    let mut server = args.server(&config).map_err(|err| eyre!(err))?;
+    credentials::fill(&mut server, true).map_err(|err| eyre!(err))?;
+    let client = Rc::new(RefCell::new(
+        Client::connect(&server)?
+            .trace(args.debug_rpc)
+            .retry(config.retry),
+    ));
+    // Fail now with what's wrong rather than showing an empty list.
+    if let Err(err) = data::ping(&client).await {
+        return Err(eyre!(
+            "Can't talk to the daemon at {}\n{err}",
+            utils::redact_url(&server.url)
+        ));
     }
-    let mut app = App::new(args.tick_rate, args.frame_rate, &client)?;
+    if let Some(Command::Proxy { listen }) = args.command {
+        return proxy::serve(&client, &config, listen).await;
+    }
+    let mut app = App::new(
+        args.tick_rate
+            .or(config.tick_rate)
+            .unwrap_or(DEFAULT_TICK_RATE),
+        args.frame_rate
+            .or(config.frame_rate)
+            .unwrap_or(DEFAULT_FRAME_RATE),
+        &client,
+        config,
+        args.mini,
+        args.no_alt_screen,
+    )?;
     app.run().await?;
     Ok(())
 }
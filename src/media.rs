@@ -0,0 +1,128 @@
+//! Basic media inspection of local files through `ffprobe`, from FFmpeg.
+
+use std::{path::Path, process::Command};
+
+use serde::Deserialize;
+
+use crate::{app, utils::convert_eta};
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct MediaInfo {
+    #[serde(default)]
+    format: Format,
+    #[serde(default)]
+    streams: Vec<Stream>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct Format {
+    format_long_name: Option<String>,
+    /// Seconds, as a decimal string.
+    duration: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct Stream {
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    channels: Option<u32>,
+    #[serde(default)]
+    tags: Tags,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct Tags {
+    language: Option<String>,
+}
+
+impl MediaInfo {
+    fn parse(json: &[u8]) -> Result<Self, app::Error> {
+        serde_json::from_slice(json).map_err(|err| app::Error::WithMessage(err.to_string()))
+    }
+
+    /// The format, duration and one line per stream.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(name) = &self.format.format_long_name {
+            lines.push(format!("Format: {name}"));
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        if let Some(seconds) = self
+            .format
+            .duration
+            .as_ref()
+            .and_then(|d| d.parse::<f64>().ok())
+        {
+            lines.push(format!("Duration: {}", convert_eta(seconds.round() as i64)));
+        }
+        for stream in &self.streams {
+            let kind = stream.codec_type.as_deref().unwrap_or("unknown");
+            let mut line = format!("{kind}: {}", stream.codec_name.as_deref().unwrap_or("?"));
+            if let (Some(width), Some(height)) = (stream.width, stream.height) {
+                line.push_str(&format!(", {width}x{height}"));
+            }
+            if let Some(channels) = stream.channels {
+                line.push_str(&format!(", {channels} channels"));
+            }
+            if let Some(language) = &stream.tags.language {
+                line.push_str(&format!(", {language}"));
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// Inspect the media file at `path`.
+pub fn probe(path: &Path) -> Result<MediaInfo, app::Error> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|err| app::Error::WithMessage(format!("Failed to run ffprobe: {err}")))?;
+    if !output.status.success() {
+        return Err(app::Error::WithMessage(format!(
+            "ffprobe can't read {}",
+            path.display()
+        )));
+    }
+    MediaInfo::parse(&output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_info_lines() {
+        let json = br#"{
+            "streams": [
+                { "codec_type": "video", "codec_name": "h264", "width": 1920, "height": 1080 },
+                { "codec_type": "audio", "codec_name": "aac", "channels": 2,
+                  "tags": { "language": "eng" } },
+                { "codec_type": "subtitle" }
+            ],
+            "format": { "format_long_name": "Matroska / WebM", "duration": "3725.4" }
+        }"#;
+        assert_eq!(
+            MediaInfo::parse(json).unwrap().lines(),
+            [
+                "Format: Matroska / WebM",
+                "Duration: 1h2m5s",
+                "video: h264, 1920x1080",
+                "audio: aac, 2 channels, eng",
+                "subtitle: ?",
+            ]
+        );
+        assert!(MediaInfo::parse(b"{}").unwrap().lines().is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+//! Measurements of sparrow itself, shown by the stats overlay to tune the tick and frame rates.
+
+use std::{
+    collections::VecDeque,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+/// RPC calls remembered, and intervals averaged over.
+const SAMPLES: usize = 60;
+
+/// A call to the daemon and how it went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcCall {
+    pub method: String,
+    pub elapsed: Duration,
+    pub ok: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Metrics {
+    /// The latest calls, oldest first.
+    pub calls: VecDeque<RpcCall>,
+    /// Torrents kept between ticks by the torrent list.
+    pub cached_torrents: usize,
+    /// Rough heap size of those torrents, in bytes.
+    pub cached_bytes: usize,
+}
+
+static METRICS: RwLock<Metrics> = RwLock::new(Metrics {
+    calls: VecDeque::new(),
+    cached_torrents: 0,
+    cached_bytes: 0,
+});
+
+pub fn record_call(method: &str, elapsed: Duration, ok: bool) {
+    let mut metrics = METRICS.write().unwrap_or_else(|err| err.into_inner());
+    if metrics.calls.len() == SAMPLES {
+        metrics.calls.pop_front();
+    }
+    metrics.calls.push_back(RpcCall {
+        method: method.to_string(),
+        elapsed,
+        ok,
+    });
+}
+
+pub fn record_cache(torrents: usize, bytes: usize) {
+    let mut metrics = METRICS.write().unwrap_or_else(|err| err.into_inner());
+    metrics.cached_torrents = torrents;
+    metrics.cached_bytes = bytes;
+}
+
+pub fn metrics() -> Metrics {
+    METRICS
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+}
+
+/// The times between the latest occurrences of an event, like frames or ticks.
+#[derive(Debug, Default)]
+pub struct Intervals {
+    last: Option<Instant>,
+    gaps: VecDeque<Duration>,
+}
+
+impl Intervals {
+    pub fn record(&mut self, now: Instant) {
+        if let Some(last) = self.last {
+            if self.gaps.len() == SAMPLES {
+                self.gaps.pop_front();
+            }
+            self.gaps.push_back(now.saturating_duration_since(last));
+        }
+        self.last = Some(now);
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        let count = u32::try_from(self.gaps.len()).ok().filter(|&n| n > 0)?;
+        Some(self.gaps.iter().sum::<Duration>() / count)
+    }
+
+    /// Occurrences per second, from the mean interval.
+    pub fn rate(&self) -> Option<f64> {
+        self.mean()
+            .filter(|mean| !mean.is_zero())
+            .map(|mean| 1.0 / mean.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intervals() {
+        let start = Instant::now();
+        let mut intervals = Intervals::default();
+        assert_eq!(intervals.mean(), None);
+        intervals.record(start);
+        assert_eq!(intervals.rate(), None);
+        intervals.record(start + Duration::from_millis(200));
+        intervals.record(start + Duration::from_millis(300));
+        assert_eq!(intervals.mean(), Some(Duration::from_millis(150)));
+        let rate = intervals.rate().unwrap();
+        assert!((rate - 1.0 / 0.15).abs() < 1e-9);
+
+        for i in 0..SAMPLES as u64 * 2 {
+            intervals.record(start + Duration::from_secs(1 + i));
+        }
+        assert_eq!(intervals.gaps.len(), SAMPLES);
+        assert_eq!(intervals.mean(), Some(Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,249 @@
+//! Sends notable events to services outside the terminal.
+
+use std::collections::HashMap;
+
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use strum::Display;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::info;
+
+use crate::{
+    action::{Action, Failure, Level},
+    utils::redact_urls,
+};
+
+/// Something worth telling the user about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    /// A torrent finished downloading.
+    Completed,
+    /// A torrent ran into an error.
+    Error,
+}
+
+/// Where notifications are sent to.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Backend {
+    /// POST the event as JSON to `url`.
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// Message a chat through a Telegram bot.
+    Telegram { token: String, chat_id: String },
+    /// Publish to an ntfy topic.
+    Ntfy {
+        #[serde(default = "default_ntfy_server")]
+        server: String,
+        topic: String,
+        /// Access token for protected topics.
+        token: Option<String>,
+    },
+}
+
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+/// An HTTP request delivering a notification.
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+    url: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Backend {
+    fn request(&self, event: Event, title: &str, message: &str) -> Request {
+        match self {
+            Backend::Webhook { url, headers } => Request {
+                url: url.clone(),
+                headers: headers
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .chain([(CONTENT_TYPE.to_string(), "application/json".to_string())])
+                    .collect(),
+                body: json!({ "event": event, "title": title, "message": message }).to_string(),
+            },
+            Backend::Telegram { token, chat_id } => Request {
+                url: format!("https://api.telegram.org/bot{token}/sendMessage"),
+                headers: vec![(CONTENT_TYPE.to_string(), "application/json".to_string())],
+                body: json!({ "chat_id": chat_id, "text": format!("{title}\n{message}") })
+                    .to_string(),
+            },
+            Backend::Ntfy {
+                server,
+                topic,
+                token,
+            } => Request {
+                url: format!("{}/{topic}", server.trim_end_matches('/')),
+                headers: [("Title".to_string(), title.to_string())]
+                    .into_iter()
+                    .chain(
+                        token
+                            .iter()
+                            .map(|t| ("Authorization".to_string(), format!("Bearer {t}"))),
+                    )
+                    .collect(),
+                body: message.to_string(),
+            },
+        }
+    }
+}
+
+/// The `notifications` section of the config.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Show every event as a desktop notification too, when built with the `desktop` feature.
+    pub desktop: bool,
+    /// Backends by name.
+    pub backends: HashMap<String, Backend>,
+    /// Names of the backends each event is sent to.
+    pub events: HashMap<Event, Vec<String>>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            desktop: true,
+            backends: HashMap::new(),
+            events: HashMap::new(),
+        }
+    }
+}
+
+impl NotificationsConfig {
+    /// Backend names that aren't defined, which would silently drop notifications.
+    pub fn unknown_backends(&self) -> Vec<&str> {
+        let mut unknown = self
+            .events
+            .values()
+            .flatten()
+            .filter(|name| !self.backends.contains_key(*name))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        unknown.sort_unstable();
+        unknown.dedup();
+        unknown
+    }
+}
+
+/// Delivers events to the backends configured for them, in the background.
+#[derive(Default)]
+pub struct Notifier {
+    config: NotificationsConfig,
+    http: reqwest::Client,
+}
+
+impl Notifier {
+    pub fn new(config: NotificationsConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Send `event` to its backends, reporting failures as error actions on `tx`.
+    pub fn notify(&self, event: Event, title: &str, message: &str, tx: &UnboundedSender<Action>) {
+        #[cfg(feature = "desktop")]
+        if self.config.desktop {
+            let (title, message, tx) = (title.to_string(), message.to_string(), tx.clone());
+            tokio::task::spawn_blocking(move || {
+                if let Err(err) = crate::desktop::show(&title, &message) {
+                    let message = format!("Failed to notify the desktop: {err}");
+                    let _ = tx.send(Action::Error(
+                        Failure::new("Notifier", message).level(Level::Warning),
+                    ));
+                }
+            });
+        }
+        let names = self.config.events.get(&event).into_iter().flatten();
+        for (name, backend) in names.filter_map(|n| self.config.backends.get_key_value(n)) {
+            let request = backend.request(event, title, message);
+            let mut builder = self.http.post(&request.url).body(request.body);
+            for (key, value) in &request.headers {
+                builder = builder.header(key, value);
+            }
+            let (name, tx) = (name.clone(), tx.clone());
+            tokio::spawn(async move {
+                let result = builder
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status);
+                match result {
+                    Ok(_) => info!("Sent {event} notification to {name}"),
+                    Err(err) => {
+                        // Errors may hold the URL, and with it a bot token.
+                        let err = redact_urls(&err.without_url().to_string());
+                        let message = format!("Failed to notify {name}: {err}");
+                        let _ = tx.send(Action::Error(
+                            Failure::new("Notifier", message).level(Level::Warning),
+                        ));
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_requests() {
+        let webhook = Backend::Webhook {
+            url: "https://example.com/hook".to_string(),
+            headers: HashMap::new(),
+        };
+        let request = webhook.request(Event::Completed, "Completed", "debian.iso");
+        assert_eq!(request.url, "https://example.com/hook");
+        assert_eq!(
+            request.body,
+            r#"{"event":"completed","message":"debian.iso","title":"Completed"}"#
+        );
+
+        let telegram = Backend::Telegram {
+            token: "123:abc".to_string(),
+            chat_id: "42".to_string(),
+        };
+        let request = telegram.request(Event::Error, "Error", "debian.iso");
+        assert_eq!(
+            request.url,
+            "https://api.telegram.org/bot123:abc/sendMessage"
+        );
+        assert_eq!(
+            request.body,
+            r#"{"chat_id":"42","text":"Error\ndebian.iso"}"#
+        );
+
+        let ntfy: Backend =
+            serde_json::from_str(r#"{ "type": "ntfy", "topic": "torrents", "token": "tk" }"#)
+                .unwrap();
+        let request = ntfy.request(Event::Completed, "Completed", "debian.iso");
+        assert_eq!(request.url, "https://ntfy.sh/torrents");
+        assert_eq!(request.body, "debian.iso");
+        assert!(request
+            .headers
+            .contains(&("Authorization".to_string(), "Bearer tk".to_string())));
+    }
+
+    #[test]
+    fn test_unknown_backends() {
+        let config: NotificationsConfig = serde_json::from_str(
+            r#"{
+                "backends": { "phone": { "type": "telegram", "token": "t", "chat_id": "1" } },
+                "events": { "completed": ["phone", "desk"], "error": ["desk"] }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.unknown_backends(), ["desk"]);
+    }
+}
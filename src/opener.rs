@@ -0,0 +1,103 @@
+//! Opening the data of finished torrents with the configured command or the platform's.
+
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use serde::Deserialize;
+
+use crate::data::Files;
+
+/// What `Enter` does on a finished torrent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnEnter {
+    /// Show its properties, as for unfinished torrents.
+    #[default]
+    Properties,
+    /// Open the folder holding its data.
+    Folder,
+    /// Open its primary media file, see [`primary_file`].
+    File,
+}
+
+/// The `open` section of the config.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct OpenConfig {
+    pub on_enter: OnEnter,
+    /// Command opening folders and files, given the path as its last argument; `xdg-open`, or
+    /// `open` on macOS, if not set.
+    pub command: Option<String>,
+}
+
+/// Extensions of the files worth opening first, lowercase.
+const MEDIA_EXTENSIONS: [&str; 16] = [
+    "mkv", "mp4", "avi", "mov", "webm", "m4v", "wmv", "ts", "flac", "mp3", "m4a", "ogg", "opus",
+    "wav", "pdf", "epub",
+];
+
+/// The largest wanted media file, or the largest wanted file if none is media.
+pub fn primary_file(files: &[Files]) -> Option<&Files> {
+    let is_media = |file: &Files| {
+        Path::new(&file.name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| MEDIA_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+    };
+    files
+        .iter()
+        .filter(|file| file.wanted)
+        .max_by_key(|file| (is_media(file), file.length))
+}
+
+#[cfg(target_os = "macos")]
+const DEFAULT_COMMAND: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_COMMAND: &str = "xdg-open";
+
+/// Open `path` with `command`, without waiting for it to exit.
+pub fn open(path: &Path, command: Option<&str>) -> Result<(), String> {
+    let mut words = command.unwrap_or(DEFAULT_COMMAND).split_whitespace();
+    let program = words.next().ok_or("The open command is empty")?;
+    Command::new(program)
+        .args(words)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+        .map_err(|err| format!("Can't run {program}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, length: i64, wanted: bool) -> Files {
+        Files {
+            name: name.to_string(),
+            downloaded: String::new(),
+            total_size: String::new(),
+            priority: String::new(),
+            wanted,
+            bytes_completed: length,
+            length,
+        }
+    }
+
+    #[test]
+    fn test_primary_file() {
+        let files = [
+            file("show/sample.txt", 9000, true),
+            file("show/e01.MKV", 700, true),
+            file("show/e02.mkv", 800, false),
+            file("show/e03.mp4", 600, true),
+        ];
+        assert_eq!(primary_file(&files).unwrap().name, "show/e01.MKV");
+        assert_eq!(primary_file(&files[..1]).unwrap().name, "show/sample.txt");
+        assert!(primary_file(&files[2..3]).is_none());
+    }
+}
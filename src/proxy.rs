@@ -0,0 +1,454 @@
+//! `sparrow proxy`: a small local HTTP API forwarding to the daemon, so that other tools can
+//! reuse the server, credentials and path mappings of sparrow's config.
+//!
+//! - `GET /torrents` lists the torrents.
+//! - `POST /torrents` adds `{"source", "download_dir", "paused"}`, a file, link or magnet.
+//! - `POST /torrents/remove` removes `{"ids", "delete_data"}`.
+//! - `POST /torrents/action` applies `{"ids", "action"}`: start, stop, verify or reannounce.
+//!
+//! Browsers let any page send requests to local addresses, so requests from a page (with an
+//! `Origin`), to another host name (DNS rebinding) or with a body that isn't JSON (forms) are
+//! refused, and `proxy_token` can be required on top.
+
+use std::{cell::RefCell, net::SocketAddr, rc::Rc, time::Duration};
+
+use color_eyre::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+use tracing::{info, warn};
+use transmission_rpc::types::{Id, TorrentAction};
+
+use crate::{
+    config::{local_path, Config},
+    data::{add_torrent, data_path, map_torrent_list, Torrent},
+    rpc::Client,
+    utils::convert_status,
+};
+
+/// Largest request body accepted, plenty for a magnet link or a path.
+const MAX_BODY: usize = 64 * 1024;
+/// Time a client gets to send its request, since requests are served one at a time.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct AddRequest {
+    source: String,
+    #[serde(default)]
+    download_dir: Option<String>,
+    #[serde(default)]
+    paused: bool,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct RemoveRequest {
+    ids: Vec<i64>,
+    #[serde(default)]
+    delete_data: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Command {
+    Start,
+    Stop,
+    Verify,
+    Reannounce,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct ActionRequest {
+    ids: Vec<i64>,
+    action: Command,
+}
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    List,
+    Add(AddRequest),
+    Remove(RemoveRequest),
+    Action(ActionRequest),
+}
+
+/// What the API needs from a request's head.
+#[derive(Debug, Default, PartialEq)]
+struct Head {
+    method: String,
+    /// Without its query.
+    path: String,
+    length: usize,
+    host: Option<String>,
+    origin: Option<String>,
+    content_type: Option<String>,
+    authorization: Option<String>,
+}
+
+/// A status code and a JSON body.
+#[derive(Debug, PartialEq)]
+struct Reply {
+    status: u16,
+    body: Value,
+}
+
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            _ => "Bad Gateway",
+        };
+        let body = self.body.to_string();
+        format!(
+            "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            self.status,
+            body.len()
+        )
+        .into_bytes()
+    }
+}
+
+/// Serve the API on `address` until interrupted, forwarding to `client`.
+pub async fn serve(
+    client: &Rc<RefCell<Client>>,
+    config: &Config,
+    address: SocketAddr,
+) -> Result<()> {
+    let listener = TcpListener::bind(address).await?;
+    info!("Proxy listening on {address}");
+    println!("Listening on http://{address}, press Ctrl-C to stop");
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        if let Err(err) = handle(client, config, stream, address).await {
+            warn!("Proxy request from {peer} failed: {err}");
+        }
+    }
+}
+
+async fn handle(
+    client: &Rc<RefCell<Client>>,
+    config: &Config,
+    stream: TcpStream,
+    address: SocketAddr,
+) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let request = match timeout(READ_TIMEOUT, read_request(&mut reader)).await {
+        Ok(request) => request,
+        Err(_) => Err(Reply::error(400, "Timed out reading the request")),
+    };
+    let token = config.proxy_token.as_deref();
+    let reply = match request.and_then(|(head, body)| {
+        authorize(&head, address, token)?;
+        route(&head.method, &head.path, &body)
+    }) {
+        Ok(route) => forward(client, config, route).await,
+        Err(reply) => reply,
+    };
+    info!("Proxy replied {}", reply.status);
+    reader.get_mut().write_all(&reply.to_bytes()).await?;
+    Ok(())
+}
+
+/// The head and body of the request read from `reader`.
+async fn read_request(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<(Head, Vec<u8>), Reply> {
+    let bad_request = |err: std::io::Error| Reply::error(400, err.to_string());
+    let mut head = String::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.map_err(bad_request)? == 0 || line == "\r\n" {
+            break;
+        }
+        head.push_str(&line);
+    }
+    let head = parse_head(&head)?;
+    let mut body = vec![0; head.length];
+    reader.read_exact(&mut body).await.map_err(bad_request)?;
+    Ok((head, body))
+}
+
+/// The request line and the headers the API looks at.
+fn parse_head(head: &str) -> Result<Head, Reply> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Err(Reply::error(400, "Malformed request line"));
+    };
+    let mut parsed = Head {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        ..Head::default()
+    };
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => {
+                parsed.length = value
+                    .parse()
+                    .map_err(|_| Reply::error(400, "Invalid Content-Length"))?;
+            }
+            "host" => parsed.host = Some(value.to_string()),
+            "origin" => parsed.origin = Some(value.to_string()),
+            "content-type" => parsed.content_type = Some(value.to_string()),
+            "authorization" => parsed.authorization = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    if parsed.length > MAX_BODY {
+        return Err(Reply::error(
+            413,
+            format!("Bodies are limited to {MAX_BODY} bytes"),
+        ));
+    }
+    Ok(parsed)
+}
+
+/// Refuse the requests a web page could have sent, and the ones without `token` if set.
+fn authorize(head: &Head, address: SocketAddr, token: Option<&str>) -> Result<(), Reply> {
+    let forbidden = |message: &str| Err(Reply::error(403, message));
+    if head.origin.is_some() {
+        return forbidden("Requests from web pages are refused");
+    }
+    let port = address.port();
+    let host_allowed = head.host.as_deref().is_some_and(|host| {
+        let ip = address.ip();
+        host == address.to_string()
+            || (ip.is_loopback() && host == format!("localhost:{port}"))
+            // Listening everywhere, any name of this machine may be used.
+            || (ip.is_unspecified() && host.ends_with(&format!(":{port}")))
+    });
+    if !host_allowed {
+        return forbidden(&format!("The Host header must be {address}"));
+    }
+    if let Some(token) = token {
+        if head.authorization.as_deref() != Some(&format!("Bearer {token}")) {
+            return forbidden("Missing or wrong token, see proxy_token");
+        }
+    }
+    let json = head.content_type.as_deref().is_some_and(|content_type| {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        essence.eq_ignore_ascii_case("application/json")
+    });
+    if head.method != "GET" && !json {
+        return forbidden("Bodies must be sent as application/json");
+    }
+    Ok(())
+}
+
+fn route(method: &str, path: &str, body: &[u8]) -> Result<Route, Reply> {
+    fn parse<'a, T: Deserialize<'a>>(body: &'a [u8]) -> Result<T, Reply> {
+        serde_json::from_slice(body).map_err(|err| Reply::error(400, err.to_string()))
+    }
+    match (method, path.trim_end_matches('/')) {
+        ("GET", "/torrents") => Ok(Route::List),
+        ("POST", "/torrents") => parse(body).map(Route::Add),
+        ("POST", "/torrents/remove") => parse(body).map(Route::Remove),
+        ("POST", "/torrents/action") => parse(body).map(Route::Action),
+        (_, "/torrents" | "/torrents/remove" | "/torrents/action") => Err(Reply::error(
+            405,
+            format!("{method} is not allowed on {path}"),
+        )),
+        _ => Err(Reply::error(404, format!("No such endpoint: {path}"))),
+    }
+}
+
+/// Make the calls `route` stands for, replying with what the daemon answered.
+async fn forward(client: &Rc<RefCell<Client>>, config: &Config, route: Route) -> Reply {
+    let result = match route {
+        Route::List => map_torrent_list(client)
+            .await
+            .map(|torrents| {
+                let torrents = torrents
+                    .iter()
+                    .map(|t| to_json(t, config))
+                    .collect::<Vec<_>>();
+                json!(torrents)
+            })
+            .map_err(|err| err.to_string()),
+        Route::Add(add) => {
+            let location = add
+                .download_dir
+                .or_else(|| config.server().and_then(|s| s.download_dir.clone()))
+                .unwrap_or_default();
+            add_torrent(client, &config.sites, add.source, location, add.paused)
+                .await
+                .map(|(id, duplicate)| json!({ "id": id, "duplicate": duplicate }))
+                .map_err(|err| err.to_string())
+        }
+        Route::Remove(remove) => {
+            let ids = remove.ids.into_iter().map(Id::Id).collect();
+            let mut client = client.borrow_mut();
+            async move { client.torrent_remove(ids, remove.delete_data).await }
+                .await
+                .map(|_| json!({}))
+                .map_err(|err| err.to_string())
+        }
+        Route::Action(action) => {
+            let ids = action.ids.into_iter().map(Id::Id).collect();
+            let command = match action.action {
+                Command::Start => TorrentAction::Start,
+                Command::Stop => TorrentAction::Stop,
+                Command::Verify => TorrentAction::Verify,
+                Command::Reannounce => TorrentAction::Reannounce,
+            };
+            let mut client = client.borrow_mut();
+            async move { client.torrent_action(command, ids).await }
+                .await
+                .map(|_| json!({}))
+                .map_err(|err| err.to_string())
+        }
+    };
+    match result {
+        Ok(body) => Reply::ok(body),
+        Err(err) => Reply::error(502, err),
+    }
+}
+
+/// A torrent as listed by the API, with where its data is reachable here.
+fn to_json(torrent: &Torrent, config: &Config) -> Value {
+    let path = data_path(&torrent.location, &torrent.name);
+    json!({
+        "id": torrent.id,
+        "hash": torrent.hash,
+        "name": torrent.name,
+        "status": convert_status(torrent.status),
+        "percent_done": torrent.percent,
+        "eta": torrent.eta_secs,
+        "rate_download": torrent.rate_download,
+        "rate_upload": torrent.rate_upload,
+        "ratio": torrent.upload_ratio,
+        "total_bytes": torrent.total_bytes,
+        "left_bytes": torrent.left_bytes,
+        "labels": torrent.labels,
+        "download_dir": torrent.location,
+        "local_path": local_path(&config.path_mappings, &path),
+        "error": torrent.error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let head = "POST /torrents/action?x=1 HTTP/1.1\r\nHost: localhost:9092\r\n\
+                    content-length: 12\r\nContent-Type: application/json\r\n";
+        assert_eq!(
+            parse_head(head),
+            Ok(Head {
+                method: "POST".to_string(),
+                path: "/torrents/action".to_string(),
+                length: 12,
+                host: Some("localhost:9092".to_string()),
+                content_type: Some("application/json".to_string()),
+                ..Head::default()
+            })
+        );
+        assert_eq!(parse_head("GET\r\n").unwrap_err().status, 400);
+        let huge = format!(
+            "POST /torrents HTTP/1.1\r\nContent-Length: {}\r\n",
+            MAX_BODY + 1
+        );
+        assert_eq!(parse_head(&huge).unwrap_err().status, 413);
+    }
+
+    #[test]
+    fn test_authorize() {
+        let address = "127.0.0.1:9092".parse().unwrap();
+        let status = |head: &str, token| {
+            let head = parse_head(head).unwrap();
+            authorize(&head, address, token).map_err(|reply| reply.status)
+        };
+        let post = "POST /torrents/remove HTTP/1.1\r\nHost: localhost:9092\r\n";
+        let json = format!("{post}Content-Type: application/json; charset=utf-8\r\n");
+        assert_eq!(status(&json, None), Ok(()));
+        assert_eq!(
+            status("GET /torrents HTTP/1.1\r\nHost: 127.0.0.1:9092\r\n", None),
+            Ok(())
+        );
+        // A page posting a form or plain text, or any request of a page.
+        let form = format!("{post}Content-Type: application/x-www-form-urlencoded\r\n");
+        assert_eq!(status(&form, None), Err(403));
+        assert_eq!(
+            status(&format!("{post}Content-Type: text/plain\r\n"), None),
+            Err(403)
+        );
+        let origin = format!("{json}Origin: https://example.com\r\n");
+        assert_eq!(status(&origin, None), Err(403));
+        // DNS rebinding, or no host at all.
+        let rebound = "GET /torrents HTTP/1.1\r\nHost: evil.example.com:9092\r\n";
+        assert_eq!(status(rebound, None), Err(403));
+        assert_eq!(status("GET /torrents HTTP/1.1\r\n", None), Err(403));
+        assert_eq!(status(&json, Some("secret")), Err(403));
+        let authorized = format!("{json}Authorization: Bearer secret\r\n");
+        assert_eq!(status(&authorized, Some("secret")), Ok(()));
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route("GET", "/torrents/", b""), Ok(Route::List));
+        assert_eq!(
+            route("POST", "/torrents", br#"{"source": "magnet:?xt=1"}"#),
+            Ok(Route::Add(AddRequest {
+                source: "magnet:?xt=1".to_string(),
+                download_dir: None,
+                paused: false,
+            }))
+        );
+        assert_eq!(
+            route(
+                "POST",
+                "/torrents/action",
+                br#"{"ids": [1, 2], "action": "verify"}"#
+            ),
+            Ok(Route::Action(ActionRequest {
+                ids: vec![1, 2],
+                action: Command::Verify,
+            }))
+        );
+        assert_eq!(
+            route(
+                "POST",
+                "/torrents/action",
+                br#"{"ids": [1], "action": "explode"}"#
+            )
+            .unwrap_err()
+            .status,
+            400
+        );
+        assert_eq!(route("DELETE", "/torrents", b"").unwrap_err().status, 405);
+        assert_eq!(route("GET", "/peers", b"").unwrap_err().status, 404);
+    }
+
+    #[test]
+    fn test_reply_to_bytes() {
+        let reply = String::from_utf8(Reply::ok(json!({})).to_bytes()).unwrap();
+        assert!(reply.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(reply.ends_with("Content-Length: 2\r\nConnection: close\r\n\r\n{}"));
+    }
+}
@@ -0,0 +1,293 @@
+//! Local metadata kept in the data dir, in SQLite with the `sqlite` feature and JSON files
+//! otherwise.
+
+use color_eyre::Result;
+
+use crate::{config::get_data_dir, history::Sample};
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::Store;
+
+#[cfg(not(feature = "sqlite"))]
+pub use file::Store;
+
+/// Where the history samples were kept before the SQLite store.
+const HISTORY_FILE: &str = "history.json";
+/// Where the paused torrents are kept without the SQLite store.
+#[cfg(not(feature = "sqlite"))]
+const PAUSED_FILE: &str = "paused.json";
+
+fn read_history_file() -> Option<Vec<Sample>> {
+    let content = std::fs::read_to_string(get_data_dir().join(HISTORY_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use rusqlite::{params, Connection};
+
+    use super::*;
+
+    /// Schema changes, applied in order. The index of the last applied one is stored in the
+    /// database's `user_version`.
+    const MIGRATIONS: &[&str] = &[
+        "CREATE TABLE history (
+            time INTEGER PRIMARY KEY,
+            downloaded INTEGER NOT NULL,
+            uploaded INTEGER NOT NULL
+        );",
+        "CREATE TABLE paused (hash TEXT PRIMARY KEY);",
+        // The torrents paused before are kept for any server, see `paused`.
+        "ALTER TABLE paused RENAME TO paused_unkeyed;
+        CREATE TABLE paused (
+            server TEXT NOT NULL,
+            hash TEXT NOT NULL,
+            PRIMARY KEY (server, hash)
+        );
+        INSERT INTO paused SELECT '', hash FROM paused_unkeyed;
+        DROP TABLE paused_unkeyed;",
+    ];
+
+    pub struct Store {
+        conn: Connection,
+    }
+
+    impl Store {
+        pub fn open() -> Result<Self> {
+            let data_dir = get_data_dir();
+            std::fs::create_dir_all(&data_dir)?;
+            let mut store = Self {
+                conn: Connection::open(data_dir.join("sparrow.db"))?,
+            };
+            if store.migrate()? == 0 {
+                store.import_history_file()?;
+            }
+            Ok(store)
+        }
+
+        /// A store that lasts as long as it does, for tests.
+        #[cfg(test)]
+        pub fn in_memory() -> Result<Self> {
+            let mut store = Self {
+                conn: Connection::open_in_memory()?,
+            };
+            store.migrate()?;
+            Ok(store)
+        }
+
+        /// Bring the schema up to date, returning the version it was at.
+        fn migrate(&mut self) -> Result<usize> {
+            let version: usize = self
+                .conn
+                .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+            for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+                let tx = self.conn.transaction()?;
+                tx.execute_batch(migration)?;
+                tx.pragma_update(None, "user_version", i + 1)?;
+                tx.commit()?;
+            }
+            Ok(version)
+        }
+
+        /// Move the samples of the former JSON file into the database.
+        fn import_history_file(&mut self) -> Result<()> {
+            let Some(samples) = read_history_file() else {
+                return Ok(());
+            };
+            let tx = self.conn.transaction()?;
+            for sample in samples {
+                tx.execute(
+                    "INSERT OR REPLACE INTO history VALUES (?1, ?2, ?3)",
+                    params![sample.time, sample.downloaded, sample.uploaded],
+                )?;
+            }
+            tx.commit()?;
+            std::fs::remove_file(get_data_dir().join(HISTORY_FILE))?;
+            Ok(())
+        }
+
+        /// Samples taken at or after `since`, oldest first.
+        pub fn samples(&self, since: i64) -> Result<Vec<Sample>> {
+            let mut statement = self.conn.prepare(
+                "SELECT time, downloaded, uploaded FROM history WHERE time >= ?1 ORDER BY time",
+            )?;
+            let samples = statement
+                .query_map([since], |row| {
+                    Ok(Sample {
+                        time: row.get(0)?,
+                        downloaded: row.get(1)?,
+                        uploaded: row.get(2)?,
+                    })
+                })?
+                .collect::<Result<_, _>>()?;
+            Ok(samples)
+        }
+
+        /// Store `sample` and drop the ones taken before `expired`.
+        pub fn insert_sample(&mut self, sample: Sample, expired: i64) -> Result<()> {
+            let tx = self.conn.transaction()?;
+            tx.execute(
+                "INSERT OR REPLACE INTO history VALUES (?1, ?2, ?3)",
+                params![sample.time, sample.downloaded, sample.uploaded],
+            )?;
+            tx.execute("DELETE FROM history WHERE time < ?1", [expired])?;
+            tx.commit()?;
+            Ok(())
+        }
+
+        /// Hashes of the torrents of `server` stopped by pausing everything, along with the
+        /// ones paused before the store told servers apart.
+        pub fn paused(&self, server: &str) -> Result<Vec<String>> {
+            let mut statement = self
+                .conn
+                .prepare("SELECT DISTINCT hash FROM paused WHERE server IN (?1, '')")?;
+            let hashes = statement
+                .query_map([server], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            Ok(hashes)
+        }
+
+        /// Replace the paused torrents of `server` with `hashes`.
+        pub fn set_paused(&mut self, server: &str, hashes: &[String]) -> Result<()> {
+            let tx = self.conn.transaction()?;
+            tx.execute("DELETE FROM paused WHERE server IN (?1, '')", [server])?;
+            for hash in hashes {
+                tx.execute(
+                    "INSERT OR IGNORE INTO paused VALUES (?1, ?2)",
+                    [server, hash],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_sqlite_store() -> Result<()> {
+            let mut store = Store {
+                conn: Connection::open_in_memory()?,
+            };
+            assert_eq!(store.migrate()?, 0);
+            assert_eq!(store.migrate()?, MIGRATIONS.len());
+
+            let sample = |time| Sample {
+                time,
+                downloaded: time * 2,
+                uploaded: time * 3,
+            };
+            store.insert_sample(sample(10), 0)?;
+            store.insert_sample(sample(20), 0)?;
+            store.insert_sample(sample(30), 15)?;
+            assert_eq!(store.samples(0)?, [sample(20), sample(30)]);
+            assert_eq!(store.samples(25)?, [sample(30)]);
+
+            let hashes = vec!["a".to_string(), "b".to_string()];
+            store.set_paused("http://nas:9091/transmission/rpc", &hashes)?;
+            assert_eq!(store.paused("http://nas:9091/transmission/rpc")?.len(), 2);
+            assert!(store.paused("http://seedbox/transmission/rpc")?.is_empty());
+            store.set_paused("http://nas:9091/transmission/rpc", &[])?;
+            assert!(store.paused("http://nas:9091/transmission/rpc")?.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn test_paused_before_servers_are_kept() -> Result<()> {
+            let mut store = Store {
+                conn: Connection::open_in_memory()?,
+            };
+            store.conn.execute_batch(&MIGRATIONS[..2].join("\n"))?;
+            store.conn.pragma_update(None, "user_version", 2)?;
+            store.conn.execute("INSERT INTO paused VALUES ('a')", [])?;
+            store.migrate()?;
+            assert_eq!(store.paused("http://nas:9091/transmission/rpc")?, ["a"]);
+            store.set_paused("http://nas:9091/transmission/rpc", &[])?;
+            assert!(store.paused("http://seedbox/transmission/rpc")?.is_empty());
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+mod file {
+    use std::{collections::HashMap, fs};
+
+    use super::*;
+
+    pub struct Store;
+
+    /// The paused torrents by server. Those of a former list of hashes are kept under no server,
+    /// for any server, as in the SQLite store.
+    fn read_paused() -> HashMap<String, Vec<String>> {
+        let Ok(content) = fs::read_to_string(get_data_dir().join(PAUSED_FILE)) else {
+            return HashMap::new();
+        };
+        serde_json::from_str(&content)
+            .or_else(|_| {
+                serde_json::from_str(&content)
+                    .map(|hashes| HashMap::from([(String::new(), hashes)]))
+            })
+            .unwrap_or_default()
+    }
+
+    impl Store {
+        pub fn open() -> Result<Self> {
+            Ok(Self)
+        }
+
+        /// The files are only touched once the store is, as with `open`.
+        #[cfg(test)]
+        pub fn in_memory() -> Result<Self> {
+            Ok(Self)
+        }
+
+        /// Samples taken at or after `since`, oldest first.
+        pub fn samples(&self, since: i64) -> Result<Vec<Sample>> {
+            let mut samples = read_history_file().unwrap_or_default();
+            samples.retain(|s| s.time >= since);
+            Ok(samples)
+        }
+
+        /// Store `sample` and drop the ones taken before `expired`.
+        pub fn insert_sample(&mut self, sample: Sample, expired: i64) -> Result<()> {
+            let mut samples = self.samples(expired)?;
+            samples.push(sample);
+            fs::create_dir_all(get_data_dir())?;
+            fs::write(
+                get_data_dir().join(HISTORY_FILE),
+                serde_json::to_string(&samples)?,
+            )?;
+            Ok(())
+        }
+
+        /// Hashes of the torrents of `server` stopped by pausing everything, along with the
+        /// ones paused before the store told servers apart.
+        pub fn paused(&self, server: &str) -> Result<Vec<String>> {
+            let mut paused = read_paused();
+            let mut hashes = paused.remove(server).unwrap_or_default();
+            hashes.extend(paused.remove("").unwrap_or_default());
+            hashes.sort();
+            hashes.dedup();
+            Ok(hashes)
+        }
+
+        /// Replace the paused torrents of `server` with `hashes`.
+        pub fn set_paused(&mut self, server: &str, hashes: &[String]) -> Result<()> {
+            let mut paused = read_paused();
+            paused.remove("");
+            match hashes.is_empty() {
+                true => paused.remove(server),
+                false => paused.insert(server.to_string(), hashes.to_vec()),
+            };
+            fs::create_dir_all(get_data_dir())?;
+            fs::write(
+                get_data_dir().join(PAUSED_FILE),
+                serde_json::to_string(&paused)?,
+            )?;
+            Ok(())
+        }
+    }
+}
@@ -15,7 +15,7 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
-use ratatui::backend::CrosstermBackend as Backend;
+use ratatui::{backend::CrosstermBackend as Backend, TerminalOptions, Viewport};
 use serde::{Deserialize, Serialize};
 use tokio::{
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
@@ -51,6 +51,8 @@ pub struct Tui {
     pub tick_rate: f64,
     pub mouse: bool,
     pub paste: bool,
+    /// Draw in the current buffer instead of the alternate screen.
+    pub inline: bool,
 }
 
 impl Tui {
@@ -66,6 +68,7 @@ impl Tui {
             tick_rate: 2.0,
             mouse: false,
             paste: false,
+            inline: false,
         })
     }
 
@@ -89,6 +92,18 @@ impl Tui {
         self
     }
 
+    /// Render into `height` lines below the cursor, leaving the output in scrollback on exit.
+    pub fn inline(mut self, height: u16) -> Result<Self> {
+        self.terminal = ratatui::Terminal::with_options(
+            Backend::new(stdout()),
+            TerminalOptions {
+                viewport: Viewport::Inline(height),
+            },
+        )?;
+        self.inline = true;
+        Ok(self)
+    }
+
     pub fn start(&mut self) {
         self.cancel(); // Cancel any existing task
         self.cancellation_token = CancellationToken::new();
@@ -165,7 +180,10 @@ impl Tui {
 
     pub fn enter(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
-        crossterm::execute!(stdout(), EnterAlternateScreen, cursor::Hide)?;
+        if !self.inline {
+            crossterm::execute!(stdout(), EnterAlternateScreen)?;
+        }
+        crossterm::execute!(stdout(), cursor::Hide)?;
         if self.mouse {
             crossterm::execute!(stdout(), EnableMouseCapture)?;
         }
@@ -186,7 +204,15 @@ impl Tui {
             if self.mouse {
                 crossterm::execute!(stdout(), DisableMouseCapture)?;
             }
-            crossterm::execute!(stdout(), LeaveAlternateScreen, cursor::Show)?;
+            if self.inline {
+                // Continue below the last frame so the shell doesn't draw over it.
+                let bottom = self.terminal.get_frame().area().bottom();
+                crossterm::execute!(stdout(), cursor::MoveTo(0, bottom.saturating_sub(1)))?;
+                println!();
+            } else {
+                crossterm::execute!(stdout(), LeaveAlternateScreen)?;
+            }
+            crossterm::execute!(stdout(), cursor::Show)?;
             crossterm::terminal::disable_raw_mode()?;
         }
         Ok(())
@@ -0,0 +1,570 @@
+use std::{cmp::Ordering, str::FromStr};
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, FromRepr, IntoEnumIterator};
+
+use transmission_rpc::types::TorrentStatus;
+
+use crate::{data::Torrent, utils::locale};
+
+/// Restricts the torrent list to torrents in a given state.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Display,
+    EnumString,
+    EnumIter,
+    FromRepr,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Downloading,
+    Seeding,
+    Paused,
+    Error,
+    /// Completed since midnight.
+    Today,
+    /// Completed since Monday.
+    Week,
+    /// Completed since the first of the month.
+    Month,
+}
+
+impl StatusFilter {
+    pub fn matches(self, torrent: &Torrent) -> bool {
+        self.matches_at(torrent, Local::now())
+    }
+
+    /// Whether `torrent` passes the filter at the time `now`, for the ones on the done date.
+    fn matches_at(self, torrent: &Torrent, now: DateTime<Local>) -> bool {
+        let today = now.date_naive();
+        let completed_since = |start: Option<NaiveDate>| {
+            // Torrents that never completed have no done date.
+            torrent.done_date.timestamp() > 0
+                && start.is_some_and(|start| {
+                    torrent.done_date.with_timezone(&Local).date_naive() >= start
+                })
+        };
+        match self {
+            Self::All => true,
+            Self::Downloading => matches!(
+                torrent.status,
+                TorrentStatus::Downloading | TorrentStatus::QueuedToDownload
+            ),
+            Self::Seeding => matches!(
+                torrent.status,
+                TorrentStatus::Seeding | TorrentStatus::QueuedToSeed
+            ),
+            Self::Paused => torrent.status == TorrentStatus::Stopped,
+            Self::Error => !torrent.error.is_empty(),
+            Self::Today => completed_since(Some(today)),
+            Self::Week => completed_since(
+                today.checked_sub_days(Days::new(today.weekday().num_days_from_monday().into())),
+            ),
+            Self::Month => completed_since(today.with_day(1)),
+        }
+    }
+
+    /// The next filter, wrapping around to the first.
+    pub fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or_default()
+    }
+
+    /// The previous filter, wrapping around to the last.
+    pub fn previous(self) -> Self {
+        match self as usize {
+            0 => Self::Month,
+            i => Self::from_repr(i - 1).unwrap_or_default(),
+        }
+    }
+}
+
+/// The number of `torrents` each filter lets through.
+pub fn status_counts(torrents: &[Torrent]) -> Vec<(StatusFilter, usize)> {
+    StatusFilter::iter()
+        .map(|filter| {
+            (
+                filter,
+                torrents.iter().filter(|t| filter.matches(t)).count(),
+            )
+        })
+        .collect()
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Display,
+    EnumString,
+    EnumIter,
+    FromRepr,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    #[default]
+    Name,
+    Done,
+    Eta,
+    Down,
+    Up,
+    Ratio,
+    Added,
+    Queue,
+    /// Transmission's order: stopped, verifying, downloading then seeding.
+    Status,
+}
+
+/// A sort key and direction, and optionally a second one breaking the ties, written as
+/// `key[:asc|:desc][,key[:asc|:desc]]`. Torrents still tied keep the order of their ids, so rows
+/// don't swap places between refreshes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Sort {
+    pub key: SortKey,
+    pub descending: bool,
+    /// The key breaking the ties of `key`, and whether it's descending.
+    pub then: Option<(SortKey, bool)>,
+}
+
+impl SortKey {
+    /// The next key, wrapping around to the first.
+    pub fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or_default()
+    }
+}
+
+impl Sort {
+    /// Sort by `key`, flipping the direction when already sorting by it.
+    pub fn toggle(self, key: SortKey) -> Self {
+        Self {
+            key,
+            descending: key == self.key && !self.descending,
+            then: self.then.filter(|&(then, _)| then != key),
+        }
+    }
+
+    /// Break the ties with `key`, flipping its direction when already breaking them with it.
+    pub fn then_by(self, key: Option<SortKey>) -> Self {
+        let then = key
+            .filter(|&key| key != self.key)
+            .map(|key| match self.then {
+                Some((then, descending)) if then == key => (key, !descending),
+                _ => (key, false),
+            });
+        Self { then, ..self }
+    }
+
+    pub fn compare(self, a: &Torrent, b: &Torrent) -> Ordering {
+        compare_by(self.key, self.descending, a, b)
+            .then_with(|| match self.then {
+                Some((key, descending)) => compare_by(key, descending, a, b),
+                None => Ordering::Equal,
+            })
+            .then_with(|| a.id.cmp(&b.id))
+    }
+}
+
+fn compare_by(key: SortKey, descending: bool, a: &Torrent, b: &Torrent) -> Ordering {
+    let ordering = match key {
+        SortKey::Name => a.name.cmp(&b.name),
+        SortKey::Done => a.percent.total_cmp(&b.percent),
+        // Unknown and infinite ETAs are negative, they belong after every real one.
+        SortKey::Eta => eta_rank(a.eta_secs).cmp(&eta_rank(b.eta_secs)),
+        SortKey::Down => a.rate_download.cmp(&b.rate_download),
+        SortKey::Up => a.rate_upload.cmp(&b.rate_upload),
+        SortKey::Ratio => a.upload_ratio.total_cmp(&b.upload_ratio),
+        SortKey::Added => a.added_date.cmp(&b.added_date),
+        SortKey::Queue => a.queue_position.cmp(&b.queue_position),
+        SortKey::Status => (a.status as u8).cmp(&(b.status as u8)),
+    };
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+fn eta_rank(eta: i64) -> i64 {
+    if eta < 0 {
+        i64::MAX
+    } else {
+        eta
+    }
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |s: &str| {
+            let (key, order) = s.trim().split_once(':').unwrap_or((s.trim(), "asc"));
+            let key = SortKey::from_str(key).map_err(|_| format!("Unknown sort key `{key}`"))?;
+            match order.to_ascii_lowercase().as_str() {
+                "asc" => Ok((key, false)),
+                "desc" => Ok((key, true)),
+                _ => Err(format!("Unknown sort order `{order}`")),
+            }
+        };
+        let (first, then) = match s.split_once(',') {
+            Some((first, then)) => (first, Some(parse(then)?)),
+            None => (s, None),
+        };
+        let (key, descending) = parse(first)?;
+        Ok(Self {
+            key,
+            descending,
+            then,
+        })
+    }
+}
+
+impl TryFrom<String> for Sort {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Display, EnumString, EnumIter, Deserialize,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum Group {
+    #[default]
+    None,
+    Tracker,
+}
+
+impl Group {
+    /// The label of the group `torrent` belongs to, if grouping is enabled.
+    pub fn key(self, torrent: &Torrent) -> Option<String> {
+        match self {
+            Self::None => None,
+            Self::Tracker => Some(
+                torrent
+                    .trackers
+                    .iter()
+                    .find(|t| !t.is_backup)
+                    .map_or_else(|| "No tracker".to_string(), |t| t.host.clone()),
+            ),
+        }
+    }
+}
+
+/// A column of Home's table.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    Name,
+    Done,
+    Eta,
+    Down,
+    Up,
+    Ratio,
+    Size,
+    /// Peers sending to us, and seeders in the swarm as last scraped.
+    Seeds,
+    /// Peers downloading from us, and leechers in the swarm as last scraped.
+    Peers,
+    /// Connected peers, with those we upload to and download from.
+    Connected,
+    Added,
+    Label,
+    Queue,
+    Uploaded,
+    /// When the torrent finished downloading.
+    Completed,
+}
+
+/// The columns shown unless `table.columns` picks others.
+pub const DEFAULT_COLUMNS: [Column; 6] = [
+    Column::Name,
+    Column::Done,
+    Column::Eta,
+    Column::Down,
+    Column::Up,
+    Column::Ratio,
+];
+
+impl Column {
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::Name => "NAME",
+            Self::Done => "DONE",
+            Self::Eta => "ETA",
+            Self::Down => "DOWN",
+            Self::Up => "UP",
+            Self::Ratio => "RATIO",
+            Self::Size => "SIZE",
+            Self::Seeds => "SEEDS",
+            Self::Peers => "PEERS",
+            Self::Connected => "CONNECTED",
+            Self::Added => "ADDED",
+            Self::Label => "LABEL",
+            Self::Queue => "#",
+            Self::Uploaded => "UPLOADED",
+            Self::Completed => "COMPLETED",
+        }
+    }
+
+    /// The key sorting the table by this column, if it has one.
+    pub const fn sort_key(self) -> Option<SortKey> {
+        match self {
+            Self::Name => Some(SortKey::Name),
+            Self::Done => Some(SortKey::Done),
+            Self::Eta => Some(SortKey::Eta),
+            Self::Down => Some(SortKey::Down),
+            Self::Up => Some(SortKey::Up),
+            Self::Ratio => Some(SortKey::Ratio),
+            Self::Added => Some(SortKey::Added),
+            Self::Queue => Some(SortKey::Queue),
+            Self::Size
+            | Self::Seeds
+            | Self::Peers
+            | Self::Connected
+            | Self::Label
+            | Self::Uploaded
+            | Self::Completed => None,
+        }
+    }
+
+    /// What the column shows for `torrent`, the exact numbers if `raw`; the name and progress
+    /// span several lines.
+    pub fn content(self, torrent: &Torrent, raw: bool) -> String {
+        if let Some(value) = raw.then(|| self.raw_content(torrent)).flatten() {
+            return value;
+        }
+        let swarm = |count: fn(&crate::data::Tracker) -> i64| {
+            torrent.trackers.iter().map(count).max().unwrap_or(0).max(0)
+        };
+        match self {
+            Self::Name => torrent.formatted_name(),
+            Self::Done => torrent.percent_done(),
+            Self::Eta => torrent.eta(),
+            Self::Down => torrent.download_speed(),
+            Self::Up => torrent.upload_speed(),
+            Self::Ratio => torrent.ratio(),
+            Self::Size => torrent.size_done(),
+            Self::Seeds => format!("{} ({})", torrent.peers_sending, swarm(|t| t.seeders)),
+            Self::Peers => format!("{} ({})", torrent.peers_getting, swarm(|t| t.leechers)),
+            Self::Connected => format!(
+                "{} (↑{}/↓{})",
+                torrent.peers_connected, torrent.peers_getting, torrent.peers_sending
+            ),
+            Self::Added => locale().date(torrent.added_date),
+            Self::Label => torrent.labels.join(", "),
+            Self::Queue => (torrent.queue_position + 1).to_string(),
+            Self::Uploaded => torrent.uploaded(),
+            Self::Completed => match torrent.done_date.timestamp() {
+                0 => "-".to_string(),
+                _ => locale().date(torrent.done_date),
+            },
+        }
+    }
+
+    /// The exact number behind the column, in bytes, seconds or as a fraction, if it has one.
+    fn raw_content(self, torrent: &Torrent) -> Option<String> {
+        let value = match self {
+            Self::Done => torrent.percent.to_string(),
+            Self::Eta => torrent.eta_secs.to_string(),
+            Self::Down => torrent.rate_download.to_string(),
+            Self::Up => torrent.rate_upload.to_string(),
+            Self::Ratio => torrent.upload_ratio.to_string(),
+            Self::Size => (torrent.have_bytes + torrent.left_bytes).to_string(),
+            Self::Added => torrent.added_date.timestamp().to_string(),
+            Self::Uploaded => torrent.uploaded_bytes.to_string(),
+            Self::Completed => torrent.done_date.timestamp().to_string(),
+            Self::Name
+            | Self::Seeds
+            | Self::Peers
+            | Self::Connected
+            | Self::Label
+            | Self::Queue => return None,
+        };
+        Some(value)
+    }
+}
+
+/// How Home filters, sorts and groups the torrent list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct View {
+    pub filter: StatusFilter,
+    pub sort: Sort,
+    pub group: Group,
+}
+
+impl View {
+    pub fn apply(self, torrents: Vec<Torrent>) -> Vec<Torrent> {
+        torrents
+            .into_iter()
+            .filter(|t| self.filter.matches(t))
+            .sorted_by(|a, b| {
+                self.group
+                    .key(a)
+                    .cmp(&self.group.key(b))
+                    .then_with(|| self.sort.compare(a, b))
+            })
+            .collect()
+    }
+}
+
+/// Whether `name` contains `query`, ignoring case.
+pub fn matches_search(name: &str, query: &str) -> bool {
+    name.to_lowercase().contains(&query.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::torrent;
+
+    #[test]
+    fn test_parse_sort() {
+        assert_eq!(
+            "added:desc".parse(),
+            Ok(Sort {
+                key: SortKey::Added,
+                descending: true,
+                then: None,
+            })
+        );
+        assert_eq!(
+            "status, name:desc".parse(),
+            Ok(Sort {
+                key: SortKey::Status,
+                descending: false,
+                then: Some((SortKey::Name, true)),
+            })
+        );
+        assert_eq!(
+            "Ratio".parse(),
+            Ok(Sort {
+                key: SortKey::Ratio,
+                descending: false,
+                then: None,
+            })
+        );
+        assert!("size".parse::<Sort>().is_err());
+        assert!("name,size".parse::<Sort>().is_err());
+        assert!("name:up".parse::<Sort>().is_err());
+    }
+
+    #[test]
+    fn test_eta_rank() {
+        assert!(eta_rank(-1) > eta_rank(86400));
+        assert!(eta_rank(10) < eta_rank(20));
+    }
+
+    #[test]
+    fn test_sort_toggle() {
+        let sort = Sort::default().toggle(SortKey::Eta);
+        assert_eq!((sort.key, sort.descending), (SortKey::Eta, false));
+        let sort = sort.toggle(SortKey::Eta);
+        assert_eq!((sort.key, sort.descending), (SortKey::Eta, true));
+        let sort = sort.toggle(SortKey::Up);
+        assert_eq!((sort.key, sort.descending), (SortKey::Up, false));
+        assert_eq!(SortKey::Added.next(), SortKey::Queue);
+        assert_eq!(SortKey::Status.next(), SortKey::Name);
+
+        let sort = sort.then_by(Some(SortKey::Name));
+        assert_eq!(sort.then, Some((SortKey::Name, false)));
+        assert_eq!(
+            sort.then_by(Some(SortKey::Name)).then,
+            Some((SortKey::Name, true))
+        );
+        assert_eq!(sort.then_by(Some(SortKey::Up)).then, None);
+        assert_eq!(sort.then_by(None).then, None);
+        assert_eq!(sort.toggle(SortKey::Name).then, None);
+    }
+
+    #[test]
+    fn test_sort_ties() {
+        let mut a = torrent(2, false);
+        let mut b = torrent(1, false);
+        let sort: Sort = "status,up:desc".parse().unwrap();
+        assert_eq!(sort.compare(&a, &b), Ordering::Greater);
+        a.rate_upload = 50;
+        assert_eq!(sort.compare(&a, &b), Ordering::Less);
+        b.status = TorrentStatus::Stopped;
+        assert_eq!(sort.compare(&a, &b), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_column() {
+        assert_eq!("Seeds".parse(), Ok(Column::Seeds));
+        assert!("size_done".parse::<Column>().is_err());
+        assert_eq!(Column::Added.sort_key(), Some(SortKey::Added));
+        assert_eq!(Column::Label.sort_key(), None);
+        assert!(DEFAULT_COLUMNS
+            .iter()
+            .all(|column| column.sort_key().is_some()));
+        let torrent = torrent(1, false);
+        assert_eq!(Column::Connected.content(&torrent, false), "5 (↑1/↓2)");
+        assert_eq!(Column::Connected.content(&torrent, true), "5 (↑1/↓2)");
+    }
+
+    #[test]
+    fn test_filter_cycle() {
+        assert_eq!(StatusFilter::All.next(), StatusFilter::Downloading);
+        assert_eq!(StatusFilter::Month.next(), StatusFilter::All);
+        assert_eq!(StatusFilter::All.previous(), StatusFilter::Month);
+        assert_eq!(StatusFilter::Error.next(), StatusFilter::Today);
+        assert_eq!(StatusFilter::Paused.previous(), StatusFilter::Seeding);
+    }
+
+    #[test]
+    fn test_completed_filters() {
+        let local = |day, hour| {
+            NaiveDate::from_ymd_opt(2024, 5, day)
+                .and_then(|date| date.and_hms_opt(hour, 0, 0))
+                .and_then(|time| time.and_local_timezone(Local).single())
+                .unwrap()
+        };
+        // A Wednesday.
+        let now = local(15, 10);
+        let filters = [StatusFilter::Today, StatusFilter::Week, StatusFilter::Month];
+        let matching = |done_date: DateTime<Local>| {
+            let mut torrent = torrent(1, false);
+            torrent.done_date = done_date.to_utc();
+            filters.map(|filter| filter.matches_at(&torrent, now))
+        };
+        assert_eq!(matching(local(15, 1)), [true, true, true]);
+        assert_eq!(matching(local(13, 9)), [false, true, true]);
+        assert_eq!(matching(local(2, 9)), [false, false, true]);
+        assert_eq!(matching(local(12, 23)), [false, false, true]);
+        let never = torrent(1, false);
+        assert!(!StatusFilter::Month.matches_at(&never, now));
+    }
+
+    #[test]
+    fn test_matches_search() {
+        assert!(matches_search("Ubuntu 24.04 Desktop", "ubuntu"));
+        assert!(matches_search("Ubuntu 24.04 Desktop", "4 DESK"));
+        assert!(matches_search("anything", ""));
+        assert!(!matches_search("Debian", "ubuntu"));
+    }
+}